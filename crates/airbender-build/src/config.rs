@@ -1,15 +1,29 @@
 //! Build configuration and artifact packaging flow.
 
-use crate::constants::DEFAULT_APP_NAME;
-use crate::errors::Result;
+use crate::constants::{DEFAULT_APP_NAME, INHERITED_BUILD_ENV};
+use crate::elf::extract_artifacts;
+use crate::errors::{BuildError, Result};
+use crate::provenance::{
+    build_timestamp, resolve_rustc_version, resolve_target_from_executable, sdk_versions,
+    RustcVersion,
+};
+use crate::size::SizeReport;
 use crate::utils::{
-    find_package, load_metadata, resolve_bin_name, resolve_git_metadata, run_command,
-    sha256_file_hex, validate_app_name,
+    discover_guests, find_package, load_metadata, resolve_bin_name, resolve_git_metadata,
+    resolve_interface, sha256_file_hex, sha256_hex, validate_app_name, GitMetadata,
+};
+use crate::{
+    ArtifactEntry, BuildMetadata, Bundle, DistIndex, DistIndexEntry, ImageId, InterfaceDescription,
+    Lto, MachineConfig, Manifest, ManifestPublicKey, ManifestSigningKey, OptLevel, Profile,
+    BUNDLE_EXTENSION, DIST_INDEX_FILE, MANIFEST_SIGNATURE_FILE, MANIFEST_VERSION_LATEST,
 };
-use crate::{ArtifactEntry, BuildMetadata, Manifest, Profile, MANIFEST_VERSION_V1};
+use cargo_metadata::{Message, Metadata};
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
 use std::fs;
+use std::io::BufReader;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 /// Input settings for guest compilation and dist packaging.
 #[derive(Clone, Debug)]
@@ -26,8 +40,30 @@ pub struct BuildConfig {
     pub profile: Profile,
     /// Output root directory for `dist/` artifacts.
     pub dist_dir: Option<PathBuf>,
-    /// Additional arguments forwarded to `cargo build` and `cargo objcopy`.
+    /// Additional arguments forwarded to `cargo build`.
     pub cargo_args: Vec<String>,
+    /// Cargo features to enable for the guest package.
+    pub features: Vec<String>,
+    /// Disable the default features of the guest package.
+    pub no_default_features: bool,
+    /// Flags appended to the guest's configured `build.rustflags`.
+    pub rustflags: Vec<String>,
+    /// Override for the `opt-level` of the build profile.
+    pub opt_level: Option<OptLevel>,
+    /// Override for the `lto` setting of the build profile.
+    pub lto: Option<Lto>,
+    /// Override for the `codegen-units` of the build profile.
+    pub codegen_units: Option<u32>,
+    /// Key file used to write a detached signature over `manifest.toml`.
+    pub sign_key: Option<PathBuf>,
+    /// Also pack the dist directory into a single-file bundle next to it.
+    pub bundle: bool,
+    /// Maximum size of `app.bin` in bytes; the build fails when the image is larger.
+    pub rom_budget: Option<u64>,
+    /// Cargo target directory for the guest build; Cargo's default resolution applies otherwise.
+    pub target_dir: Option<PathBuf>,
+    /// Drop toolchain overrides inherited from an outer cargo invocation (see `embed_guests`).
+    pub(crate) isolate_env: bool,
 }
 
 impl BuildConfig {
@@ -41,6 +77,17 @@ impl BuildConfig {
             profile: Profile::Release,
             dist_dir: None,
             cargo_args: Vec::new(),
+            features: Vec::new(),
+            no_default_features: false,
+            rustflags: Vec::new(),
+            opt_level: None,
+            lto: None,
+            codegen_units: None,
+            sign_key: None,
+            bundle: false,
+            rom_budget: None,
+            target_dir: None,
+            isolate_env: false,
         }
     }
 
@@ -50,145 +97,359 @@ impl BuildConfig {
         let project_dir = self.resolve_project_dir(&invocation_cwd);
 
         let app_name = self.resolve_app_name()?;
-        let manifest_names = self.resolve_manifest_names(&project_dir)?;
+        let settings_args = self.resolve_cargo_settings()?;
+        let manifest_path = project_dir.join("Cargo.toml");
+        let metadata = load_metadata(&manifest_path)?;
+        let manifest_names = self.resolve_manifest_names(&metadata, &manifest_path)?;
         let target = self.resolve_target()?;
-        let dist_dir = self.resolve_dist_dir(&app_name, &project_dir, &invocation_cwd);
-        fs::create_dir_all(&dist_dir)?;
-
-        self.run_cargo_build(&project_dir, &manifest_names.bin_name, target.as_deref())?;
+        let sign_key = self.resolve_sign_key(&invocation_cwd)?;
+        let built_at = build_timestamp()?;
+        let dist_dir = self
+            .resolve_dist_root(&project_dir, &invocation_cwd)
+            .join(&app_name);
 
-        let app_bin = dist_dir.join("app.bin");
-        let app_elf = dist_dir.join("app.elf");
-        let app_text = dist_dir.join("app.text");
-
-        self.run_cargo_objcopy(
-            &project_dir,
-            &manifest_names.bin_name,
-            target.as_deref(),
-            &["-O", "binary"],
-            &app_bin,
-        )?;
-        self.run_cargo_objcopy(
+        let mut executables = self.run_cargo_build(
             &project_dir,
-            &manifest_names.bin_name,
+            std::slice::from_ref(&manifest_names),
+            false,
             target.as_deref(),
-            &["-R", ".text"],
-            &app_elf,
+            &settings_args,
         )?;
-        self.run_cargo_objcopy(
+        let context =
+            self.packaging_context(&project_dir, &metadata, target, sign_key, built_at)?;
+        self.package(
+            &context,
+            manifest_names,
+            executables.remove(0),
+            &app_name,
+            &dist_dir,
+        )
+    }
+
+    /// Builds every guest of the workspace in one cargo invocation and packages each into the
+    /// dist root, together with an index listing them.
+    fn build_workspace(&self) -> Result<WorkspaceArtifacts> {
+        if let Some(bin_name) = &self.bin_name {
+            return Err(BuildError::InvalidConfig(format!(
+                "binary target `{bin_name}` cannot be selected in a workspace build, which packages every guest"
+            )));
+        }
+        let invocation_cwd = std::env::current_dir()?;
+        let project_dir = self.resolve_project_dir(&invocation_cwd);
+
+        let settings_args = self.resolve_cargo_settings()?;
+        let metadata = load_metadata(&project_dir.join("Cargo.toml"))?;
+        let guests = discover_guests(&metadata)?;
+        let target = self.resolve_target()?;
+        let sign_key = self.resolve_sign_key(&invocation_cwd)?;
+        let built_at = build_timestamp()?;
+        let dist_root = self.resolve_dist_root(&project_dir, &invocation_cwd);
+
+        let executables = self.run_cargo_build(
             &project_dir,
-            &manifest_names.bin_name,
+            &guests,
+            true,
             target.as_deref(),
-            &["-O", "binary", "--only-section=.text"],
-            &app_text,
+            &settings_args,
         )?;
+        let context =
+            self.packaging_context(&project_dir, &metadata, target, sign_key, built_at)?;
+
+        let mut index = DistIndex::default();
+        let mut artifacts = Vec::with_capacity(guests.len());
+        for (names, executable) in guests.into_iter().zip(executables) {
+            let app_name = names.bin_name.clone();
+            let package = names.package.clone();
+            let guest = self.package(
+                &context,
+                names,
+                executable,
+                &app_name,
+                &dist_root.join(&app_name),
+            )?;
+            index.guests.push(DistIndexEntry {
+                app: app_name.clone(),
+                package,
+                bin_name: app_name,
+                image_id: guest.image_id,
+            });
+            artifacts.push(guest);
+        }
 
-        let bin_sha256 = sha256_file_hex(&app_bin)?;
-        let elf_sha256 = sha256_file_hex(&app_elf)?;
-        let text_sha256 = sha256_file_hex(&app_text)?;
-        let git_metadata = resolve_git_metadata(&project_dir);
+        let index_path = dist_root.join(DIST_INDEX_FILE);
+        index.write_to_file(&index_path)?;
+        Ok(WorkspaceArtifacts {
+            dist_root,
+            index: index_path,
+            guests: artifacts,
+        })
+    }
+
+    /// Resolves the provenance and signing settings shared by every program of one build.
+    fn packaging_context(
+        &self,
+        project_dir: &Path,
+        metadata: &Metadata,
+        target: Option<String>,
+        sign_key: Option<ManifestSigningKey>,
+        built_at: u64,
+    ) -> Result<PackagingContext> {
+        let cargo_lock = metadata.workspace_root.as_std_path().join("Cargo.lock");
+        let (cargo_lock_sha256, sdk_versions) = if cargo_lock.is_file() {
+            (
+                Some(sha256_file_hex(&cargo_lock)?),
+                sdk_versions(&fs::read_to_string(&cargo_lock)?),
+            )
+        } else {
+            (None, BTreeMap::new())
+        };
+
+        Ok(PackagingContext {
+            target,
+            git_metadata: resolve_git_metadata(project_dir),
+            rustc: resolve_rustc_version(self.rustc_command(), project_dir),
+            cargo_lock_sha256,
+            sdk_versions,
+            sign_key,
+            built_at,
+        })
+    }
+
+    /// Extracts the artifacts of one built executable into `dist_dir` and writes its manifest.
+    fn package(
+        &self,
+        context: &PackagingContext,
+        manifest_names: ManifestNames,
+        executable: BuiltExecutable,
+        app_name: &str,
+        dist_dir: &Path,
+    ) -> Result<DistArtifacts> {
+        fs::create_dir_all(dist_dir)?;
+        let executable_elf = fs::read(&executable.path)?;
+        let rustc = context.rustc.as_ref();
+        let resolved_target = rustc
+            .and_then(|rustc| resolve_target_from_executable(&executable.path, &rustc.host))
+            .or_else(|| context.target.clone());
         let manifest_bin_name = manifest_names.manifest_bin_name();
 
+        let app_bin = dist_dir.join("app.bin");
+        let app_elf = dist_dir.join("app.elf");
+        let app_text = dist_dir.join("app.text");
         let manifest_path = dist_dir.join("manifest.toml");
-        let manifest = Manifest {
+        let artifact = |path: &str| ArtifactEntry {
+            path: path.to_string(),
+            sha256: String::new(),
+        };
+        let mut manifest = Manifest {
             package: manifest_names.package,
             bin_name: manifest_bin_name,
-            manifest: MANIFEST_VERSION_V1.to_string(),
+            manifest: MANIFEST_VERSION_LATEST.to_string(),
             codec: format!("v{}", airbender_codec::AIRBENDER_CODEC_V0),
-            target,
-            bin: ArtifactEntry {
-                path: "app.bin".to_string(),
-                sha256: bin_sha256,
-            },
-            elf: ArtifactEntry {
-                path: "app.elf".to_string(),
-                sha256: elf_sha256,
-            },
-            text: ArtifactEntry {
-                path: "app.text".to_string(),
-                sha256: text_sha256,
-            },
+            target: context.target.clone(),
+            image_id: None,
+            bin: artifact("app.bin"),
+            elf: artifact("app.elf"),
+            text: artifact("app.text"),
+            artifacts: BTreeMap::new(),
+            interface: manifest_names.interface,
             build: BuildMetadata {
                 profile: self.profile,
-                git_branch: git_metadata.branch,
-                git_commit: git_metadata.commit,
-                is_dirty: git_metadata.is_dirty,
+                git_branch: context.git_metadata.branch.clone(),
+                git_commit: context.git_metadata.commit.clone(),
+                is_dirty: context.git_metadata.is_dirty,
+                rustc_version: rustc.map(|rustc| rustc.release.clone()),
+                rustc_commit_hash: rustc.and_then(|rustc| rustc.commit_hash.clone()),
+                resolved_target,
+                cargo_args: self.cargo_args.clone(),
+                no_default_features: self.no_default_features,
+                rustflags: self.rustflags.clone(),
+                opt_level: self.opt_level,
+                lto: self.lto,
+                codegen_units: self.codegen_units,
+                features: executable.features,
+                cargo_lock_sha256: context.cargo_lock_sha256.clone(),
+                sdk_versions: context.sdk_versions.clone(),
+                executable_sha256: Some(sha256_hex(&executable_elf)),
+                built_at: Some(context.built_at),
             },
+            size: None,
+        };
+
+        let (image_id, size, up_to_date) =
+            match reusable_manifest(&manifest, &executable_elf, dist_dir)? {
+                Some((image_id, size)) => (image_id, size, true),
+                None => {
+                    let artifacts = extract_artifacts(&executable_elf)?;
+                    let size = SizeReport::from_elf(&executable_elf, artifacts.bin.len() as u64)?;
+                    check_rom_budget(self.rom_budget, &size)?;
+
+                    fs::write(&app_bin, &artifacts.bin)?;
+                    fs::write(&app_elf, &artifacts.elf)?;
+                    fs::write(&app_text, &artifacts.text)?;
+
+                    let image_id =
+                        ImageId::compute(&artifacts.bin, &artifacts.text, MachineConfig::default());
+                    manifest.bin.sha256 = sha256_file_hex(&app_bin)?;
+                    manifest.elf.sha256 = sha256_file_hex(&app_elf)?;
+                    manifest.text.sha256 = sha256_file_hex(&app_text)?;
+                    manifest.image_id = Some(image_id);
+                    manifest.size = Some(size.summary);
+                    manifest.write_to_file(&manifest_path)?;
+                    (image_id, size, false)
+                }
+            };
+        if up_to_date {
+            check_rom_budget(self.rom_budget, &size)?;
+        }
+
+        let signature_path = dist_dir.join(MANIFEST_SIGNATURE_FILE);
+        let signature = match &context.sign_key {
+            Some(sign_key) => {
+                let signature = sign_key.sign(&fs::read(&manifest_path)?);
+                signature.write_to_file(&signature_path)?;
+                Some(ManifestSignatureArtifact {
+                    path: signature_path,
+                    public_key: signature.public_key,
+                })
+            }
+            None => {
+                // A signature left over from a previous build no longer matches a rewritten
+                // manifest; an up-to-date manifest keeps it valid.
+                if !up_to_date && signature_path.exists() {
+                    fs::remove_file(&signature_path)?;
+                }
+                None
+            }
+        };
+
+        let bundle = if self.bundle {
+            let bundle_path = dist_dir.with_file_name(format!("{app_name}.{BUNDLE_EXTENSION}"));
+            Bundle::from_dist_dir(dist_dir)?.write_to_file(&bundle_path)?;
+            Some(bundle_path)
+        } else {
+            None
         };
-        manifest.write_to_file(&manifest_path)?;
 
         Ok(DistArtifacts {
-            dist_dir,
+            dist_dir: dist_dir.to_path_buf(),
             app_bin,
             app_elf,
             app_text,
             manifest: manifest_path,
+            image_id,
+            signature,
+            bundle,
+            size,
+            up_to_date,
         })
     }
 
-    /// Runs `cargo build` using this config and optional target override.
-    fn run_cargo_build(
-        &self,
-        project_dir: &Path,
-        bin_name: &str,
-        target: Option<&str>,
-    ) -> Result<()> {
-        let mut cmd = Command::new("cargo");
-
-        cmd.arg("build");
-        if self.profile == Profile::Release {
-            cmd.arg("--release");
+    /// Creates a command for a toolchain program honoring the environment settings.
+    fn command(&self, program: impl AsRef<OsStr>) -> Command {
+        let mut cmd = Command::new(program);
+        if self.isolate_env {
+            for name in INHERITED_BUILD_ENV {
+                cmd.env_remove(name);
+            }
         }
+        cmd
+    }
 
-        cmd.arg("--bin").arg(bin_name);
-        if let Some(target) = target {
-            cmd.arg("--target").arg(target);
+    /// Creates the `rustc` command cargo would use for the guest build.
+    pub(crate) fn rustc_command(&self) -> Command {
+        match std::env::var_os("RUSTC") {
+            Some(rustc) if !self.isolate_env => self.command(rustc),
+            _ => self.command("rustc"),
         }
+    }
 
-        cmd.args(&self.cargo_args);
-        cmd.current_dir(project_dir);
-        run_command(cmd, "cargo build")
+    /// Creates a `cargo` command honoring the target dir and environment settings.
+    fn cargo_command(&self) -> Command {
+        let mut cmd = self.command("cargo");
+        if let Some(target_dir) = &self.target_dir {
+            cmd.env("CARGO_TARGET_DIR", target_dir);
+        }
+        cmd
     }
 
-    /// Runs `cargo objcopy` to generate one concrete output artifact.
-    fn run_cargo_objcopy(
+    /// Runs `cargo build` for the binaries of `guests` using this config, optional target
+    /// override and typed settings; `select_packages` also passes each guest's `-p <package>`.
+    ///
+    /// Returns the built executables in `guests` order, as reported in cargo's JSON messages.
+    fn run_cargo_build(
         &self,
         project_dir: &Path,
-        bin_name: &str,
+        guests: &[ManifestNames],
+        select_packages: bool,
         target: Option<&str>,
-        objcopy_args: &[&str],
-        output: &Path,
-    ) -> Result<()> {
-        let mut cmd = Command::new("cargo");
+        settings_args: &[String],
+    ) -> Result<Vec<BuiltExecutable>> {
+        let mut cmd = self.cargo_command();
 
-        cmd.arg("objcopy");
+        cmd.arg("build");
         if self.profile == Profile::Release {
             cmd.arg("--release");
         }
 
-        cmd.arg("--bin").arg(bin_name);
+        for guest in guests {
+            if select_packages {
+                cmd.arg("-p").arg(&guest.package);
+            }
+            cmd.arg("--bin").arg(&guest.bin_name);
+        }
         if let Some(target) = target {
             cmd.arg("--target").arg(target);
         }
 
+        cmd.args(settings_args);
         cmd.args(&self.cargo_args);
-        cmd.arg("--");
-        cmd.args(objcopy_args);
-        cmd.arg(output);
+        cmd.arg("--message-format=json-render-diagnostics");
         cmd.current_dir(project_dir);
-        run_command(cmd, "cargo objcopy")
+        cmd.stdout(Stdio::piped());
+
+        let mut child = cmd.spawn()?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let mut executables: Vec<Option<BuiltExecutable>> = vec![None; guests.len()];
+        for message in Message::parse_stream(BufReader::new(stdout)) {
+            if let Message::CompilerArtifact(artifact) = message? {
+                let position = guests
+                    .iter()
+                    .position(|guest| guest.bin_name == artifact.target.name);
+                if let (Some(position), Some(path)) = (position, artifact.executable) {
+                    executables[position] = Some(BuiltExecutable {
+                        path: path.into_std_path_buf(),
+                        features: artifact.features,
+                    });
+                }
+            }
+        }
+
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(BuildError::ProcessFailed {
+                cmd: "cargo build".to_string(),
+                status,
+            });
+        }
+        executables
+            .into_iter()
+            .map(|executable| executable.ok_or(BuildError::MissingField("executable")))
+            .collect()
     }
 
     /// Resolves names used during build and manifest generation.
-    fn resolve_manifest_names(&self, project_dir: &Path) -> Result<ManifestNames> {
-        let manifest_path = project_dir.join("Cargo.toml");
-        let metadata = load_metadata(&manifest_path)?;
-        let package = find_package(&metadata, &manifest_path)?;
+    fn resolve_manifest_names(
+        &self,
+        metadata: &Metadata,
+        manifest_path: &Path,
+    ) -> Result<ManifestNames> {
+        let package = find_package(metadata, manifest_path)?;
         let bin_name = resolve_bin_name(package, self.bin_name.as_deref())?;
 
         Ok(ManifestNames {
             package: package.name.clone(),
             bin_name,
+            interface: resolve_interface(package)?,
         })
     }
 
@@ -203,6 +464,113 @@ impl BuildConfig {
         Ok(self.target.clone())
     }
 
+    /// Validates the typed cargo settings and translates them into `cargo build` arguments.
+    ///
+    /// A setting must not also be forwarded through `cargo_args`, so the manifest records it
+    /// unambiguously. Rustflags are appended to the guest's `build.rustflags` and profile
+    /// overrides apply to the profile being built, both through `--config`.
+    fn resolve_cargo_settings(&self) -> Result<Vec<String>> {
+        let forwarded = |flags: &[&str]| {
+            self.cargo_args.iter().find(|arg| {
+                flags.iter().any(|flag| {
+                    *arg == flag
+                        || arg.starts_with(&format!("{flag}="))
+                        || (flag.len() == 2 && arg.starts_with(flag))
+                })
+            })
+        };
+        let conflict = |setting: &str, arg: &str| {
+            Err(BuildError::InvalidConfig(format!(
+                "`{setting}` conflicts with `{arg}` in cargo args; set it in one place"
+            )))
+        };
+
+        let mut args = Vec::new();
+        if !self.features.is_empty() {
+            if let Some(arg) = forwarded(&["--features", "-F", "--all-features"]) {
+                return conflict("features", arg);
+            }
+            if let Some(feature) = self
+                .features
+                .iter()
+                .find(|feature| feature.is_empty() || feature.contains([',', ' ']))
+            {
+                return Err(BuildError::InvalidConfig(format!(
+                    "invalid feature name `{feature}`; pass one feature per entry"
+                )));
+            }
+            args.push("--features".to_string());
+            args.push(self.features.join(","));
+        }
+        if self.no_default_features {
+            if let Some(arg) = forwarded(&["--no-default-features", "--all-features"]) {
+                return conflict("no_default_features", arg);
+            }
+            args.push("--no-default-features".to_string());
+        }
+
+        if !self.rustflags.is_empty() {
+            for name in ["RUSTFLAGS", "CARGO_ENCODED_RUSTFLAGS"] {
+                if !self.isolate_env && std::env::var_os(name).is_some() {
+                    return Err(BuildError::InvalidConfig(format!(
+                        "`{name}` is set in the environment and would override `rustflags`"
+                    )));
+                }
+            }
+            let flags = self
+                .rustflags
+                .iter()
+                .cloned()
+                .map(toml::Value::String)
+                .collect();
+            args.push("--config".to_string());
+            args.push(format!("build.rustflags={}", toml::Value::Array(flags)));
+        }
+
+        let profile = match self.profile {
+            Profile::Debug => "dev",
+            Profile::Release => "release",
+        };
+        let mut overrides = Vec::new();
+        if let Some(opt_level) = self.opt_level {
+            let value = match opt_level {
+                OptLevel::Size | OptLevel::MinSize => format!("\"{}\"", opt_level.as_str()),
+                _ => opt_level.as_str().to_string(),
+            };
+            overrides.push(("opt_level", format!("opt-level={value}")));
+        }
+        if let Some(lto) = self.lto {
+            overrides.push(("lto", format!("lto=\"{}\"", lto.as_str())));
+        }
+        if let Some(codegen_units) = self.codegen_units {
+            if codegen_units == 0 {
+                return Err(BuildError::InvalidConfig(
+                    "`codegen_units` must be at least 1".to_string(),
+                ));
+            }
+            overrides.push(("codegen_units", format!("codegen-units={codegen_units}")));
+        }
+        for (setting, value) in overrides {
+            if let Some(arg) = forwarded(&["--profile"]) {
+                return conflict(setting, arg);
+            }
+            args.push("--config".to_string());
+            args.push(format!("profile.{profile}.{value}"));
+        }
+
+        Ok(args)
+    }
+
+    /// Loads the signing key before building, so a bad key path fails fast.
+    ///
+    /// Relative key paths are interpreted from command invocation cwd, like `--dist`.
+    fn resolve_sign_key(&self, invocation_cwd: &Path) -> Result<Option<ManifestSigningKey>> {
+        let Some(path) = &self.sign_key else {
+            return Ok(None);
+        };
+        load_signing_key(&invocation_cwd.join(path)).map(Some)
+    }
+
     /// Resolves the project directory relative to the command invocation cwd.
     fn resolve_project_dir(&self, invocation_cwd: &Path) -> PathBuf {
         if self.project_dir.is_absolute() {
@@ -212,17 +580,12 @@ impl BuildConfig {
         }
     }
 
-    /// Resolves the final dist directory for this app configuration.
+    /// Resolves the dist root that app directories are created under.
     ///
     /// `--dist` follows standard CLI semantics: relative paths are interpreted
     /// from command invocation cwd, not from the guest project directory.
-    fn resolve_dist_dir(
-        &self,
-        app_name: &str,
-        project_dir: &Path,
-        invocation_cwd: &Path,
-    ) -> PathBuf {
-        let dist_root = self.dist_dir.clone().map_or_else(
+    fn resolve_dist_root(&self, project_dir: &Path, invocation_cwd: &Path) -> PathBuf {
+        self.dist_dir.clone().map_or_else(
             || project_dir.join("dist"),
             |dist_dir| {
                 if dist_dir.is_absolute() {
@@ -231,15 +594,102 @@ impl BuildConfig {
                     invocation_cwd.join(dist_dir)
                 }
             },
-        );
-        dist_root.join(app_name)
+        )
+    }
+}
+
+/// Fails when the program image is larger than the configured ROM budget.
+fn check_rom_budget(budget: Option<u64>, size: &SizeReport) -> Result<()> {
+    match budget {
+        Some(budget) if size.summary.rom_bytes > budget => Err(BuildError::RomBudgetExceeded {
+            size: size.summary.rom_bytes,
+            budget,
+        }),
+        _ => Ok(()),
     }
 }
 
+/// Checks whether the dist already holds the artifacts `current` would be packaged into.
+///
+/// The existing manifest must record the same executable digest and match `current` in
+/// everything but the artifact digests, image id, size and build time, and the artifacts on
+/// disk must still match their recorded digests. Returns the recorded image id and the size
+/// report of `executable` with the existing `app.bin`.
+fn reusable_manifest(
+    current: &Manifest,
+    executable: &[u8],
+    dist_dir: &Path,
+) -> Result<Option<(ImageId, SizeReport)>> {
+    let Ok(previous) = Manifest::read_from_file(&dist_dir.join("manifest.toml")) else {
+        return Ok(None);
+    };
+    let (Some(image_id), Some(_)) = (previous.image_id, previous.size) else {
+        return Ok(None);
+    };
+    let expected = Manifest {
+        image_id: previous.image_id,
+        bin: previous.bin.clone(),
+        elf: previous.elf.clone(),
+        text: previous.text.clone(),
+        size: previous.size,
+        build: BuildMetadata {
+            built_at: previous.build.built_at,
+            ..current.build.clone()
+        },
+        ..current.clone()
+    };
+    if expected != previous {
+        return Ok(None);
+    }
+
+    let mut contents = Vec::new();
+    for entry in [&previous.bin, &previous.elf, &previous.text] {
+        let Ok(bytes) = fs::read(dist_dir.join(&entry.path)) else {
+            return Ok(None);
+        };
+        if !sha256_hex(&bytes).eq_ignore_ascii_case(&entry.sha256) {
+            return Ok(None);
+        }
+        contents.push(bytes);
+    }
+    let size = SizeReport::from_elf(executable, contents[0].len() as u64)?;
+    Ok(Some((image_id, size)))
+}
+
+/// Loads a manifest signing key, naming the key file on failure.
+pub(crate) fn load_signing_key(path: &Path) -> Result<ManifestSigningKey> {
+    ManifestSigningKey::read_from_file(path).map_err(|err| {
+        BuildError::InvalidConfig(format!(
+            "failed to load signing key `{}`: {err}",
+            path.display()
+        ))
+    })
+}
+
+/// Executable reported by `cargo build`.
+#[derive(Clone, Debug)]
+struct BuiltExecutable {
+    path: PathBuf,
+    /// Features enabled for the binary's package.
+    features: Vec<String>,
+}
+
+/// Provenance and signing settings shared by the programs of one build.
+struct PackagingContext {
+    target: Option<String>,
+    git_metadata: GitMetadata,
+    rustc: Option<RustcVersion>,
+    cargo_lock_sha256: Option<String>,
+    sdk_versions: BTreeMap<String, String>,
+    sign_key: Option<ManifestSigningKey>,
+    built_at: u64,
+}
+
 #[derive(Clone, Debug)]
-struct ManifestNames {
-    package: String,
-    bin_name: String,
+pub(crate) struct ManifestNames {
+    pub package: String,
+    pub bin_name: String,
+    pub interface: Option<InterfaceDescription>,
 }
 
 impl ManifestNames {
@@ -265,6 +715,36 @@ pub struct DistArtifacts {
     pub app_text: PathBuf,
     /// Path to `manifest.toml`.
     pub manifest: PathBuf,
+    /// Canonical image id recorded in the manifest.
+    pub image_id: ImageId,
+    /// Detached manifest signature, when a signing key was configured.
+    pub signature: Option<ManifestSignatureArtifact>,
+    /// Path to the single-file bundle, when bundling was requested.
+    pub bundle: Option<PathBuf>,
+    /// Section, symbol and memory usage of the program.
+    pub size: SizeReport,
+    /// The dist already held artifacts for this executable and config, so none were rewritten.
+    pub up_to_date: bool,
+}
+
+/// Detached manifest signature written next to `manifest.toml`.
+#[derive(Clone, Debug)]
+pub struct ManifestSignatureArtifact {
+    /// Path to `manifest.toml.sig`.
+    pub path: PathBuf,
+    /// Public key that verifies the signature.
+    pub public_key: ManifestPublicKey,
+}
+
+/// Output of a workspace build: one dist app directory per guest and the index listing them.
+#[derive(Clone, Debug)]
+pub struct WorkspaceArtifacts {
+    /// Dist root holding the app directories.
+    pub dist_root: PathBuf,
+    /// Path to `index.toml`.
+    pub index: PathBuf,
+    /// Packaged guests, in workspace member order.
+    pub guests: Vec<DistArtifacts>,
 }
 
 /// Builds and packages guest artifacts using the provided configuration.
@@ -272,10 +752,160 @@ pub fn build_dist(config: &BuildConfig) -> Result<DistArtifacts> {
     config.build_dist()
 }
 
+/// Builds and packages every guest of the workspace containing `config.project_dir`.
+///
+/// Guests are workspace members that depend on `airbender-sdk`; each binary target is packaged
+/// into `<dist-root>/<bin-name>/`. All guests are compiled by one `cargo build` invocation with
+/// the config's target, profile and cargo settings. `app_name` is ignored, and `bin_name` must
+/// be unset.
+pub fn build_workspace(config: &BuildConfig) -> Result<WorkspaceArtifacts> {
+    config.build_workspace()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn reuses_dist_only_for_unchanged_executable_and_config() {
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let dist_dir = temp_dir.path();
+        let executable = crate::elf::tests::sample_elf(crate::ROM_BYTE_SIZE);
+        let artifacts = extract_artifacts(&executable).expect("extract");
+        let entry = |path: &str, contents: &[u8]| {
+            fs::write(dist_dir.join(path), contents).expect("write artifact");
+            ArtifactEntry {
+                path: path.to_string(),
+                sha256: sha256_hex(contents),
+            }
+        };
+        let mut build = BuildMetadata::new(Profile::Release);
+        build.executable_sha256 = Some(sha256_hex(&executable));
+        build.built_at = Some(1_700_000_000);
+        let recorded = Manifest {
+            package: "demo".to_string(),
+            bin_name: None,
+            manifest: MANIFEST_VERSION_LATEST.to_string(),
+            codec: "v0".to_string(),
+            target: None,
+            image_id: Some(ImageId::from_bytes([0xab; 32])),
+            bin: entry("app.bin", &artifacts.bin),
+            elf: entry("app.elf", &artifacts.elf),
+            text: entry("app.text", &artifacts.text),
+            artifacts: BTreeMap::new(),
+            interface: None,
+            build,
+            size: Some(
+                SizeReport::from_elf(&executable, artifacts.bin.len() as u64)
+                    .expect("size")
+                    .summary,
+            ),
+        };
+        recorded
+            .write_to_file(&dist_dir.join("manifest.toml"))
+            .expect("write manifest");
+
+        let mut current = recorded.clone();
+        current.image_id = None;
+        current.size = None;
+        current.bin.sha256.clear();
+        current.build.built_at = Some(1_800_000_000);
+        let (image_id, size) = reusable_manifest(&current, &executable, dist_dir)
+            .expect("check dist")
+            .expect("unchanged build reuses the dist");
+        assert_eq!(Some(image_id), recorded.image_id);
+        assert_eq!(Some(size.summary), recorded.size);
+
+        let mut changed_args = current.clone();
+        changed_args.build.cargo_args = vec!["--locked".to_string()];
+        assert!(reusable_manifest(&changed_args, &executable, dist_dir)
+            .expect("check dist")
+            .is_none());
+
+        let mut changed_executable = current.clone();
+        changed_executable.build.executable_sha256 = Some("00".repeat(32));
+        assert!(
+            reusable_manifest(&changed_executable, &executable, dist_dir)
+                .expect("check dist")
+                .is_none()
+        );
+
+        fs::write(dist_dir.join("app.bin"), b"tampered").expect("tamper artifact");
+        assert!(reusable_manifest(&current, &executable, dist_dir)
+            .expect("check dist")
+            .is_none());
+    }
+
+    #[test]
+    fn translates_typed_cargo_settings() {
+        let mut config = BuildConfig::new(PathBuf::from("."));
+        assert!(config
+            .resolve_cargo_settings()
+            .expect("no settings")
+            .is_empty());
+
+        config.features = vec!["alpha".to_string(), "beta".to_string()];
+        config.no_default_features = true;
+        config.opt_level = Some(OptLevel::MinSize);
+        config.lto = Some(Lto::Fat);
+        config.codegen_units = Some(1);
+        config.cargo_args = vec!["--locked".to_string()];
+        assert_eq!(
+            config.resolve_cargo_settings().expect("settings"),
+            [
+                "--features",
+                "alpha,beta",
+                "--no-default-features",
+                "--config",
+                "profile.release.opt-level=\"z\"",
+                "--config",
+                "profile.release.lto=\"fat\"",
+                "--config",
+                "profile.release.codegen-units=1",
+            ]
+        );
+
+        config.profile = Profile::Debug;
+        config.opt_level = Some(OptLevel::O1);
+        assert!(config
+            .resolve_cargo_settings()
+            .expect("settings")
+            .contains(&"profile.dev.opt-level=1".to_string()));
+    }
+
+    #[test]
+    fn rejects_conflicting_cargo_settings() {
+        let mut config = BuildConfig::new(PathBuf::from("."));
+        config.features = vec!["alpha".to_string()];
+        config.cargo_args = vec!["--features=beta".to_string()];
+        let err = config
+            .resolve_cargo_settings()
+            .expect_err("duplicate features");
+        assert_eq!(
+            err.to_string(),
+            "invalid config: `features` conflicts with `--features=beta` in cargo args; set it in one place"
+        );
+
+        config.cargo_args = vec!["--all-features".to_string()];
+        config.features.clear();
+        config.no_default_features = true;
+        assert!(config.resolve_cargo_settings().is_err());
+
+        config.cargo_args = vec!["--profile".to_string(), "bench".to_string()];
+        config.no_default_features = false;
+        config.lto = Some(Lto::Thin);
+        assert!(config.resolve_cargo_settings().is_err());
+
+        config.cargo_args.clear();
+        config.lto = None;
+        config.codegen_units = Some(0);
+        assert!(config.resolve_cargo_settings().is_err());
+
+        config.codegen_units = None;
+        config.features = vec!["alpha,beta".to_string()];
+        assert!(config.resolve_cargo_settings().is_err());
+    }
+
     #[test]
     fn defaults_to_no_target_override() {
         let config = BuildConfig::new(PathBuf::from("."));
@@ -321,7 +951,9 @@ mod tests {
         let app_name = config.resolve_app_name().expect("app-name resolution");
         let invocation_cwd = Path::new("/workspace/caller");
         let project_dir = config.resolve_project_dir(invocation_cwd);
-        let dist_dir = config.resolve_dist_dir(&app_name, &project_dir, invocation_cwd);
+        let dist_dir = config
+            .resolve_dist_root(&project_dir, invocation_cwd)
+            .join(&app_name);
         assert_eq!(
             dist_dir,
             PathBuf::from("/workspace/project/dist/gpu-profile")
@@ -336,13 +968,33 @@ mod tests {
         let app_name = config.resolve_app_name().expect("app-name resolution");
         let invocation_cwd = Path::new("/workspace/caller");
         let project_dir = config.resolve_project_dir(invocation_cwd);
-        let dist_dir = config.resolve_dist_dir(&app_name, &project_dir, invocation_cwd);
+        let dist_dir = config
+            .resolve_dist_root(&project_dir, invocation_cwd)
+            .join(&app_name);
         assert_eq!(
             dist_dir,
             PathBuf::from("/workspace/caller/builds/gpu-profile")
         );
     }
 
+    #[test]
+    fn reports_unreadable_sign_key_path() {
+        let mut config = BuildConfig::new(PathBuf::from("."));
+        assert!(config
+            .resolve_sign_key(Path::new("/workspace/caller"))
+            .expect("no key configured")
+            .is_none());
+
+        config.sign_key = Some(PathBuf::from("missing.key"));
+        let err = config
+            .resolve_sign_key(Path::new("/nonexistent-airbender-dir"))
+            .expect_err("missing key file");
+        assert!(matches!(err, BuildError::InvalidConfig(_)));
+        assert!(err
+            .to_string()
+            .contains("/nonexistent-airbender-dir/missing.key"));
+    }
+
     #[test]
     fn resolves_relative_project_dir_from_invocation_cwd() {
         let config = BuildConfig::new(PathBuf::from("examples/fibonacci/guest"));
@@ -363,7 +1015,9 @@ mod tests {
         let app_name = config.resolve_app_name().expect("app-name resolution");
         let invocation_cwd = Path::new("/workspace/caller");
         let project_dir = config.resolve_project_dir(invocation_cwd);
-        let dist_dir = config.resolve_dist_dir(&app_name, &project_dir, invocation_cwd);
+        let dist_dir = config
+            .resolve_dist_root(&project_dir, invocation_cwd)
+            .join(&app_name);
 
         assert_eq!(dist_dir, PathBuf::from("/workspace/builds/gpu-profile"));
     }
@@ -1,7 +1,38 @@
 //! Build-time defaults shared across the crate.
 
+use common_constants::rom;
+
 pub const DEFAULT_APP_NAME: &str = "app";
 
+/// Package that guest programs depend on; workspace builds use it to discover guests.
+pub(crate) const SDK_PACKAGE_NAME: &str = "airbender-sdk";
+
+/// Address the runner loads `app.bin` at.
+pub const ROM_START: u64 = 0;
+
+/// Size of the ROM region. Code and the initial program image must fit below it; writable data
+/// lives above it in RAM.
+pub const ROM_BYTE_SIZE: u64 = rom::ROM_BYTE_SIZE as u64;
+
+/// End of RAM: the size of the machine memory the runners execute guests in, i.e.
+/// `riscv_transpiler::jit::RAM_SIZE`. Kept local so the build helper does not depend on the
+/// transpiler; `airbender-host` tests check that the two agree.
+pub const RAM_BYTE_SIZE: u64 = 1 << 30;
+
+/// Variables a build script inherits from the outer cargo invocation that would make a nested
+/// guest build use the host toolchain, flags or target instead of the guest's own.
+pub(crate) const INHERITED_BUILD_ENV: &[&str] = &[
+    "RUSTUP_TOOLCHAIN",
+    "RUSTC",
+    "RUSTC_WRAPPER",
+    "RUSTC_WORKSPACE_WRAPPER",
+    "RUSTDOC",
+    "RUSTFLAGS",
+    "CARGO_ENCODED_RUSTFLAGS",
+    "CARGO_BUILD_TARGET",
+    "CARGO_TARGET_DIR",
+];
+
 pub const DEFAULT_GUEST_TOOLCHAIN: &str = "nightly-2026-02-10";
 
 // TODO: We would love to use `riscv32im-unknown-openvm-elf` target
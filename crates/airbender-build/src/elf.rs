@@ -0,0 +1,590 @@
+//! Dist artifact extraction from the guest ELF.
+//!
+//! The guest executable is parsed directly instead of going through `cargo objcopy`:
+//! - `app.bin` is the ROM image, matching `objcopy -O binary`: every allocated section with file
+//!   contents placed at its load address, with gaps zero-filled.
+//! - `app.text` holds the contents of the `.text` section.
+//! - `app.elf` is the executable without the `.text` section, like `objcopy -R .text`: its section
+//!   header and the symbols defined in it are removed, while segments keep their layout.
+
+use crate::constants::{ROM_BYTE_SIZE, ROM_START};
+use crate::errors::{BuildError, Result};
+use object::build::elf::Builder;
+use object::elf;
+use object::read::elf::{FileHeader, ProgramHeader, SectionHeader, SectionTable};
+use object::Endianness;
+
+pub(crate) type ElfHeader = elf::FileHeader32<Endianness>;
+type ElfProgramHeader = elf::ProgramHeader32<Endianness>;
+type ElfSectionHeader = elf::SectionHeader32<Endianness>;
+
+const TEXT_SECTION: &[u8] = b".text";
+
+/// Contents of the artifacts extracted from one guest executable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ElfArtifacts {
+    /// ROM image written to `app.bin`.
+    pub bin: Vec<u8>,
+    /// `.text` contents written to `app.text`.
+    pub text: Vec<u8>,
+    /// Executable without the `.text` section, written to `app.elf`.
+    pub elf: Vec<u8>,
+}
+
+/// Validates the memory layout of a guest executable and extracts its dist artifacts.
+pub(crate) fn extract_artifacts(data: &[u8]) -> Result<ElfArtifacts> {
+    let header = ElfHeader::parse(data)
+        .map_err(|err| invalid_elf(format!("not a 32-bit ELF file: {err}")))?;
+    let endian = header.endian().map_err(read_error)?;
+    if header.e_machine(endian) != elf::EM_RISCV {
+        return Err(invalid_elf(format!(
+            "expected a RISC-V executable, found machine type {}",
+            header.e_machine(endian)
+        )));
+    }
+    if header.e_type(endian) != elf::ET_EXEC {
+        return Err(invalid_elf(format!(
+            "expected an executable, found ELF type {}",
+            header.e_type(endian)
+        )));
+    }
+
+    let segments = header.program_headers(endian, data).map_err(read_error)?;
+    let sections = header.sections(endian, data).map_err(read_error)?;
+    validate_segments(segments, endian)?;
+
+    let entry = u64::from(header.e_entry(endian));
+    if !(ROM_START..ROM_BYTE_SIZE).contains(&entry) {
+        return Err(invalid_elf(format!(
+            "entry point {entry:#x} is outside ROM ({ROM_START:#x}..{ROM_BYTE_SIZE:#x})"
+        )));
+    }
+
+    let bin = rom_image(data, endian, segments, &sections)?;
+    let (_, text_section) = sections
+        .section_by_name(endian, TEXT_SECTION)
+        .ok_or_else(|| invalid_elf("missing `.text` section".to_string()))?;
+    let text = text_section
+        .data(endian, data)
+        .map_err(read_error)?
+        .to_vec();
+    let elf = strip_text(data)?;
+
+    Ok(ElfArtifacts { bin, text, elf })
+}
+
+/// Checks loadable segments against the machine memory map.
+///
+/// Code must execute from ROM, and writable data must live in RAM above it.
+fn validate_segments(segments: &[ElfProgramHeader], endian: Endianness) -> Result<()> {
+    for segment in segments {
+        if segment.p_type(endian) != elf::PT_LOAD || segment.p_memsz(endian) == 0 {
+            continue;
+        }
+        let flags = segment.p_flags(endian);
+        let start = u64::from(segment.p_vaddr(endian));
+        let end = start + u64::from(segment.p_memsz(endian));
+
+        if flags & elf::PF_W != 0 && start < ROM_BYTE_SIZE {
+            return Err(invalid_elf(format!(
+                "writable segment {start:#x}..{end:#x} overlaps ROM; \
+                 data must be placed in RAM starting at {ROM_BYTE_SIZE:#x}"
+            )));
+        }
+        if flags & elf::PF_X != 0
+            && (!(ROM_START..ROM_BYTE_SIZE).contains(&start) || end > ROM_BYTE_SIZE)
+        {
+            return Err(invalid_elf(format!(
+                "executable segment {start:#x}..{end:#x} is outside ROM \
+                 ({ROM_START:#x}..{ROM_BYTE_SIZE:#x})"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Lays out allocated section contents at their load addresses, like `objcopy -O binary`.
+fn rom_image(
+    data: &[u8],
+    endian: Endianness,
+    segments: &[ElfProgramHeader],
+    sections: &SectionTable<'_, ElfHeader>,
+) -> Result<Vec<u8>> {
+    let mut chunks = Vec::new();
+    for section in sections.iter() {
+        let flags = u64::from(section.sh_flags(endian));
+        if flags & u64::from(elf::SHF_ALLOC) == 0
+            || section.sh_type(endian) == elf::SHT_NOBITS
+            || section.sh_size(endian) == 0
+        {
+            continue;
+        }
+        let name = sections.section_name(endian, section).map_err(read_error)?;
+        let contents = section.data(endian, data).map_err(read_error)?;
+        chunks.push((load_address(segments, endian, section), contents, name));
+    }
+
+    let start = chunks
+        .iter()
+        .map(|(address, _, _)| *address)
+        .min()
+        .ok_or_else(|| invalid_elf("no loadable sections".to_string()))?;
+    if start != ROM_START {
+        return Err(invalid_elf(format!(
+            "program image must start at ROM address {ROM_START:#x}, \
+             but the lowest load address is {start:#x}"
+        )));
+    }
+
+    let mut end = start;
+    for (address, contents, name) in &chunks {
+        let section_end = address + contents.len() as u64;
+        if section_end > ROM_BYTE_SIZE {
+            return Err(invalid_elf(format!(
+                "section `{}` is loaded at {address:#x}..{section_end:#x}, \
+                 beyond the {ROM_BYTE_SIZE}-byte ROM",
+                String::from_utf8_lossy(name)
+            )));
+        }
+        end = end.max(section_end);
+    }
+
+    let mut image = vec![0u8; (end - start) as usize];
+    for (address, contents, _) in chunks {
+        let offset = (address - start) as usize;
+        image[offset..offset + contents.len()].copy_from_slice(contents);
+    }
+    Ok(image)
+}
+
+/// Load address of a section: its position inside the containing `PT_LOAD` segment, or its
+/// virtual address when no segment holds it.
+fn load_address(
+    segments: &[ElfProgramHeader],
+    endian: Endianness,
+    section: &ElfSectionHeader,
+) -> u64 {
+    let offset = u64::from(section.sh_offset(endian));
+    let size = u64::from(section.sh_size(endian));
+    segments
+        .iter()
+        .filter(|segment| segment.p_type(endian) == elf::PT_LOAD)
+        .find_map(|segment| {
+            let segment_offset = u64::from(segment.p_offset(endian));
+            let segment_end = segment_offset + u64::from(segment.p_filesz(endian));
+            (offset >= segment_offset && offset + size <= segment_end)
+                .then(|| u64::from(segment.p_paddr(endian)) + (offset - segment_offset))
+        })
+        .unwrap_or_else(|| u64::from(section.sh_addr(endian)))
+}
+
+/// Section of the program image with its place in `app.bin`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ImageSection {
+    pub name: String,
+    /// Load address, which is also the offset into `app.bin`.
+    pub address: u64,
+    pub size: u64,
+}
+
+/// Sections contributing to `app.bin`, read from a stripped `app.elf` and the size of `app.text`.
+///
+/// `app.elf` has no `.text` header, so `.text` is placed at the lowest ROM address where it fits
+/// between the remaining image sections. Dists from older tooling kept the header as `NOBITS`,
+/// and its address is used as is.
+pub(crate) fn image_sections(data: &[u8], text_size: u64) -> Result<Vec<ImageSection>> {
+    let header = ElfHeader::parse(data).map_err(read_error)?;
+    let endian = header.endian().map_err(read_error)?;
+    let segments = header.program_headers(endian, data).map_err(read_error)?;
+    let sections = header.sections(endian, data).map_err(read_error)?;
+
+    let mut image_sections = Vec::new();
+    for section in sections.iter() {
+        let size = u64::from(section.sh_size(endian));
+        if u64::from(section.sh_flags(endian)) & u64::from(elf::SHF_ALLOC) == 0 || size == 0 {
+            continue;
+        }
+        let name = sections.section_name(endian, section).map_err(read_error)?;
+        let address = if name == TEXT_SECTION {
+            u64::from(section.sh_addr(endian))
+        } else if section.sh_type(endian) == elf::SHT_NOBITS {
+            continue;
+        } else {
+            load_address(segments, endian, section)
+        };
+        image_sections.push(ImageSection {
+            name: String::from_utf8_lossy(name).into_owned(),
+            address,
+            size,
+        });
+    }
+    image_sections.sort_by_key(|section| section.address);
+
+    let has_text = image_sections
+        .iter()
+        .any(|section| section.name.as_bytes() == TEXT_SECTION);
+    if text_size > 0 && !has_text {
+        let mut address = ROM_START;
+        for section in &image_sections {
+            if address + text_size <= section.address {
+                break;
+            }
+            address = address.max(section.address + section.size);
+        }
+        let position = image_sections.partition_point(|section| section.address < address);
+        image_sections.insert(
+            position,
+            ImageSection {
+                name: String::from_utf8_lossy(TEXT_SECTION).into_owned(),
+                address,
+                size: text_size,
+            },
+        );
+    }
+    Ok(image_sections)
+}
+
+/// Removes `.text` and the symbols defined in it, keeping every other section and all segments.
+fn strip_text(data: &[u8]) -> Result<Vec<u8>> {
+    let mut builder = Builder::read32(data).map_err(|err| invalid_elf(err.to_string()))?;
+    let mut text_id = None;
+    for section in builder.sections.iter_mut() {
+        if section.name.as_slice() == TEXT_SECTION {
+            section.delete = true;
+            text_id = Some(section.id());
+        }
+    }
+    // Segments left without sections would be dropped on write; the code segment stays, as it
+    // does with `objcopy -R`.
+    for segment in builder.segments.iter_mut() {
+        segment.sections.retain(|id| Some(*id) != text_id);
+    }
+
+    let mut stripped = Vec::new();
+    builder
+        .write(&mut stripped)
+        .map_err(|err| invalid_elf(err.to_string()))?;
+    Ok(stripped)
+}
+
+pub(crate) fn read_error(err: object::read::Error) -> BuildError {
+    invalid_elf(err.to_string())
+}
+
+pub(crate) fn invalid_elf(message: String) -> BuildError {
+    BuildError::InvalidElf(message)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use object::read::elf::Sym as _;
+    use object::write::elf::{FileHeader as WriteFileHeader, ProgramHeader as WriteProgramHeader};
+    use object::write::elf::{SectionHeader as WriteSectionHeader, Sym, Writer};
+
+    const TEXT: [u8; 8] = [0x13, 0, 0, 0, 0x6f, 0, 0, 0];
+    const RODATA: [u8; 4] = *b"ro!\0";
+    const DATA: [u8; 4] = [1, 2, 3, 4];
+
+    /// Guest-shaped executable: `.text` and `.rodata` in ROM from address 0, and `.data`/`.bss`
+    /// at `data_address` with the `.data` initializer stored in ROM right after `.rodata`.
+    pub(crate) fn sample_elf(data_address: u64) -> Vec<u8> {
+        let data_load_address = (TEXT.len() + RODATA.len()) as u64;
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(Endianness::Little, false, &mut buffer);
+
+        writer.reserve_file_header();
+        writer.reserve_program_headers(2);
+        let text_offset = writer.reserve(TEXT.len(), 4) as u64;
+        let rodata_offset = writer.reserve(RODATA.len(), 4) as u64;
+        let data_offset = writer.reserve(DATA.len(), 4) as u64;
+
+        writer.reserve_null_section_index();
+        let text_name = writer.add_section_name(TEXT_SECTION);
+        let text_index = writer.reserve_section_index();
+        let rodata_name = writer.add_section_name(b".rodata");
+        writer.reserve_section_index();
+        let data_name = writer.add_section_name(b".data");
+        writer.reserve_section_index();
+        let bss_name = writer.add_section_name(b".bss");
+        writer.reserve_section_index();
+
+        writer.reserve_null_symbol_index();
+        let main_name = writer.add_string(b"main");
+        writer.reserve_symbol_index(Some(text_index));
+        writer.reserve_symtab_section_index();
+        writer.reserve_strtab_section_index();
+        writer.reserve_shstrtab_section_index();
+        writer.reserve_symtab();
+        writer.reserve_strtab();
+        writer.reserve_shstrtab();
+        writer.reserve_section_headers();
+
+        writer
+            .write_file_header(&WriteFileHeader {
+                os_abi: elf::ELFOSABI_NONE,
+                abi_version: 0,
+                e_type: elf::ET_EXEC,
+                e_machine: elf::EM_RISCV,
+                e_entry: 0,
+                e_flags: 0,
+            })
+            .expect("write file header");
+        writer.write_align_program_headers();
+        writer.write_program_header(&WriteProgramHeader {
+            p_type: elf::PT_LOAD,
+            p_flags: elf::PF_R | elf::PF_X,
+            p_offset: text_offset,
+            p_vaddr: 0,
+            p_paddr: 0,
+            p_filesz: data_load_address,
+            p_memsz: data_load_address,
+            p_align: 4,
+        });
+        writer.write_program_header(&WriteProgramHeader {
+            p_type: elf::PT_LOAD,
+            p_flags: elf::PF_R | elf::PF_W,
+            p_offset: data_offset,
+            p_vaddr: data_address,
+            p_paddr: data_load_address,
+            p_filesz: DATA.len() as u64,
+            p_memsz: DATA.len() as u64 + 16,
+            p_align: 4,
+        });
+
+        for contents in [&TEXT[..], &RODATA, &DATA] {
+            writer.write_align(4);
+            writer.write(contents);
+        }
+
+        writer.write_null_symbol();
+        writer.write_symbol(&Sym {
+            name: Some(main_name),
+            section: Some(text_index),
+            st_info: (elf::STB_GLOBAL << 4) | elf::STT_FUNC,
+            st_other: 0,
+            st_shndx: 0,
+            st_value: 4,
+            st_size: 4,
+        });
+        writer.write_strtab();
+        writer.write_shstrtab();
+
+        writer.write_null_section_header();
+        let alloc_sections = [
+            (
+                text_name,
+                elf::SHT_PROGBITS,
+                elf::SHF_EXECINSTR,
+                0,
+                text_offset,
+                TEXT.len(),
+            ),
+            (
+                rodata_name,
+                elf::SHT_PROGBITS,
+                0,
+                TEXT.len() as u64,
+                rodata_offset,
+                RODATA.len(),
+            ),
+            (
+                data_name,
+                elf::SHT_PROGBITS,
+                elf::SHF_WRITE,
+                data_address,
+                data_offset,
+                DATA.len(),
+            ),
+            (
+                bss_name,
+                elf::SHT_NOBITS,
+                elf::SHF_WRITE,
+                data_address + 4,
+                data_offset + 4,
+                16,
+            ),
+        ];
+        for (name, sh_type, flags, sh_addr, sh_offset, size) in alloc_sections {
+            writer.write_section_header(&WriteSectionHeader {
+                name: Some(name),
+                sh_type,
+                sh_flags: u64::from(elf::SHF_ALLOC | flags),
+                sh_addr,
+                sh_offset,
+                sh_size: size as u64,
+                sh_link: 0,
+                sh_info: 0,
+                sh_addralign: 4,
+                sh_entsize: 0,
+            });
+        }
+        writer.write_symtab_section_header(1);
+        writer.write_strtab_section_header();
+        writer.write_shstrtab_section_header();
+
+        buffer
+    }
+
+    #[test]
+    fn extracts_rom_image_text_and_stripped_elf() {
+        let artifacts = extract_artifacts(&sample_elf(ROM_BYTE_SIZE)).expect("extract");
+
+        assert_eq!(artifacts.bin, [&TEXT[..], &RODATA, &DATA].concat());
+        assert_eq!(artifacts.text, TEXT);
+
+        let header = ElfHeader::parse(artifacts.elf.as_slice()).expect("parse stripped elf");
+        let endian = header.endian().expect("endian");
+        let sections = header
+            .sections(endian, artifacts.elf.as_slice())
+            .expect("sections");
+        assert!(sections.section_by_name(endian, TEXT_SECTION).is_none());
+        assert!(sections.section_by_name(endian, b".rodata").is_some());
+        assert!(!artifacts
+            .elf
+            .windows(TEXT.len())
+            .any(|window| window == TEXT));
+
+        let segments = header
+            .program_headers(endian, artifacts.elf.as_slice())
+            .expect("segments");
+        assert_eq!(segments.len(), 2, "the code segment is kept");
+
+        let symbols = sections
+            .symbols(endian, artifacts.elf.as_slice(), elf::SHT_SYMTAB)
+            .expect("symbols");
+        assert!(
+            !symbols
+                .iter()
+                .any(|symbol| symbols.symbol_name(endian, symbol) == Ok(&b"main"[..])),
+            "code symbols are removed with `.text`"
+        );
+    }
+
+    #[test]
+    fn lists_image_sections_of_stripped_elf() {
+        let artifacts = extract_artifacts(&sample_elf(ROM_BYTE_SIZE)).expect("extract");
+        let sections =
+            image_sections(&artifacts.elf, artifacts.text.len() as u64).expect("image sections");
+        let layout: Vec<_> = sections
+            .iter()
+            .map(|section| (section.name.as_str(), section.address, section.size))
+            .collect();
+        assert_eq!(
+            layout,
+            [(".text", 0, 8), (".rodata", 8, 4), (".data", 12, 4)]
+        );
+    }
+
+    /// `guest.elf` is built from `main.rs` and `link.x` in the fixture directory, against a `core`
+    /// compiled for the target:
+    ///
+    /// ```text
+    /// rustc --target riscv32im-unknown-none-elf -C opt-level=z -C panic=abort -C lto=fat \
+    ///     -C target-feature=+m,-unaligned-scalar-mem,+relax -C force-frame-pointers \
+    ///     -C link-arg=-Tlink.x main.rs -o guest.elf
+    /// ```
+    ///
+    /// The other files are what `cargo objcopy` produced for it: `rust-objcopy -O binary`,
+    /// `-O binary --only-section=.text` and `-R .text`.
+    #[test]
+    fn matches_objcopy_on_a_guest_executable() {
+        let fixture = |name: &str| {
+            let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/guest");
+            std::fs::read(std::path::Path::new(path).join(name)).expect("read fixture")
+        };
+        let artifacts = extract_artifacts(&fixture("guest.elf")).expect("extract");
+        assert_eq!(artifacts.bin, fixture("app.bin"));
+        assert_eq!(artifacts.text, fixture("app.text"));
+
+        let objcopy_elf = fixture("app.elf");
+        assert_eq!(
+            elf_layout(&artifacts.elf),
+            elf_layout(&objcopy_elf),
+            "`app.elf` must keep the sections, segments and symbols `objcopy -R .text` keeps"
+        );
+        assert_eq!(
+            image_sections(&artifacts.elf, artifacts.text.len() as u64).expect("image sections"),
+            image_sections(&objcopy_elf, artifacts.text.len() as u64).expect("image sections"),
+        );
+    }
+
+    /// Headers and symbols of an executable, without file offsets.
+    fn elf_layout(data: &[u8]) -> (Vec<String>, Vec<String>, Vec<String>) {
+        let header = ElfHeader::parse(data).expect("parse");
+        let endian = header.endian().expect("endian");
+        let sections = header.sections(endian, data).expect("sections");
+        let section_headers = sections
+            .iter()
+            .map(|section| {
+                format!(
+                    "{} type={} flags={:#x} addr={:#x} size={:#x}",
+                    String::from_utf8_lossy(
+                        sections
+                            .section_name(endian, section)
+                            .expect("section name")
+                    ),
+                    section.sh_type(endian),
+                    section.sh_flags(endian),
+                    section.sh_addr(endian),
+                    section.sh_size(endian),
+                )
+            })
+            .collect();
+        let segments = header
+            .program_headers(endian, data)
+            .expect("segments")
+            .iter()
+            .map(|segment| {
+                format!(
+                    "type={:#x} flags={:#x} vaddr={:#x} paddr={:#x} filesz={:#x} memsz={:#x}",
+                    segment.p_type(endian),
+                    segment.p_flags(endian),
+                    segment.p_vaddr(endian),
+                    segment.p_paddr(endian),
+                    segment.p_filesz(endian),
+                    segment.p_memsz(endian),
+                )
+            })
+            .collect();
+        let symbols = sections
+            .symbols(endian, data, elf::SHT_SYMTAB)
+            .expect("symbols");
+        let mut symbol_names: Vec<_> = symbols
+            .iter()
+            .map(|symbol| {
+                format!(
+                    "{} value={:#x} size={:#x}",
+                    String::from_utf8_lossy(
+                        symbols.symbol_name(endian, symbol).expect("symbol name")
+                    ),
+                    symbol.st_value(endian),
+                    symbol.st_size(endian),
+                )
+            })
+            .collect();
+        symbol_names.sort();
+        (section_headers, segments, symbol_names)
+    }
+
+    #[test]
+    fn rejects_layouts_outside_the_memory_map() {
+        let err = extract_artifacts(&sample_elf(0x100)).expect_err("data in ROM");
+        assert!(err.to_string().contains("writable segment"), "{err}");
+
+        let mut elf = sample_elf(ROM_BYTE_SIZE);
+        let header = ElfHeader::parse(elf.as_slice()).expect("parse");
+        let program_headers = header.e_phoff(Endianness::Little) as usize;
+        // Move the code segment's load address away from ROM start.
+        let paddr = program_headers + 12;
+        elf[paddr..paddr + 4].copy_from_slice(&0x40u32.to_le_bytes());
+        let err = extract_artifacts(&elf).expect_err("image not at ROM start");
+        assert!(
+            err.to_string().contains("must start at ROM address"),
+            "{err}"
+        );
+
+        let err = extract_artifacts(b"not an elf").expect_err("not an ELF");
+        assert!(matches!(err, BuildError::InvalidElf(_)));
+    }
+}
@@ -0,0 +1,305 @@
+//! `build.rs` integration that builds guests before the host crate compiles.
+
+use crate::config::{build_dist, BuildConfig, DistArtifacts};
+use crate::errors::{BuildError, Result};
+use crate::utils::{find_package, load_metadata_with_deps};
+use cargo_metadata::{Metadata, Package, PackageId};
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the module [`embed_guests`] generates in `OUT_DIR`.
+pub const EMBEDDED_GUESTS_FILE: &str = "airbender_guests.rs";
+
+/// Guest built by [`embed_guests`].
+#[derive(Clone, Debug)]
+pub struct EmbeddedGuest {
+    /// Cargo package name of the guest.
+    pub package: String,
+    /// Name of the guest's module in the generated file.
+    pub module_name: String,
+    /// Dist artifacts written for the guest.
+    pub artifacts: DistArtifacts,
+}
+
+/// Build guest crates from a host `build.rs` and generate constants describing them.
+///
+/// `guest_dirs` are relative to the host crate. Each guest is built with [`build_dist`] into
+/// `OUT_DIR/airbender-guests/<package>/app`, using a target directory under `OUT_DIR` so the
+/// nested cargo invocation never waits on the lock of the outer build. Toolchain overrides
+/// inherited from the outer build are dropped, so guests still build with their own
+/// `rust-toolchain.toml`.
+///
+/// Cargo is told to rerun the build script when a guest's manifest, sources, build script,
+/// cargo config, toolchain file or local path dependencies change.
+///
+/// The generated `OUT_DIR/airbender_guests.rs` holds one module per guest, named after its
+/// package with `-` replaced by `_`, with `DIST_DIR: &str` and `IMAGE_ID: [u8; 32]` constants:
+///
+/// ```ignore
+/// // build.rs
+/// fn main() {
+///     airbender_build::embed_guests(&["../guest"]).expect("failed to build guests");
+/// }
+///
+/// // src/main.rs
+/// mod guests {
+///     include!(concat!(env!("OUT_DIR"), "/airbender_guests.rs"));
+/// }
+/// let program = airbender_host::Program::load(guests::my_guest::DIST_DIR)?;
+/// ```
+pub fn embed_guests<P: AsRef<Path>>(guest_dirs: &[P]) -> Result<Vec<EmbeddedGuest>> {
+    let out_dir = env_path("OUT_DIR")?;
+    let host_dir = env_path("CARGO_MANIFEST_DIR")?;
+    let guests_root = out_dir.join("airbender-guests");
+
+    let mut guests: Vec<EmbeddedGuest> = Vec::new();
+    for guest_dir in guest_dirs {
+        let project_dir = host_dir.join(guest_dir);
+        let package = track_guest_sources(&project_dir)?;
+        let module_name = module_name(&package);
+        if let Some(other) = guests.iter().find(|guest| guest.module_name == module_name) {
+            return Err(BuildError::InvalidConfig(format!(
+                "guests `{}` and `{package}` map to the same module `{module_name}`",
+                other.package
+            )));
+        }
+
+        let mut config = BuildConfig::new(&project_dir);
+        config.dist_dir = Some(guests_root.join(&package));
+        config.target_dir = Some(guests_root.join("target"));
+        config.isolate_env = true;
+        let artifacts = build_dist(&config)?;
+
+        guests.push(EmbeddedGuest {
+            package,
+            module_name,
+            artifacts,
+        });
+    }
+
+    fs::write(out_dir.join(EMBEDDED_GUESTS_FILE), render_module(&guests)?)?;
+    Ok(guests)
+}
+
+/// Emits `rerun-if-changed` for the inputs of a guest build and returns its package name.
+fn track_guest_sources(project_dir: &Path) -> Result<String> {
+    let (package, tracked) = guest_sources(project_dir)?;
+    for path in tracked {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+    Ok(package)
+}
+
+/// Existing inputs of a guest build: its own sources and configuration, plus the manifest,
+/// sources and build script of every local package in its resolved dependency graph.
+fn guest_sources(project_dir: &Path) -> Result<(String, Vec<PathBuf>)> {
+    let manifest_path = project_dir.join("Cargo.toml");
+    let metadata = load_metadata_with_deps(&manifest_path)?;
+    let package = find_package(&metadata, &manifest_path)?;
+    let workspace_root = metadata.workspace_root.as_std_path();
+
+    let mut tracked = vec![
+        project_dir.join(".cargo"),
+        project_dir.join("rust-toolchain.toml"),
+        project_dir.join("rust-toolchain"),
+        workspace_root.join("Cargo.lock"),
+        workspace_root.join(".cargo"),
+    ];
+    for local in local_dependency_closure(&metadata, &package.id) {
+        let package_dir = local
+            .manifest_path
+            .parent()
+            .map(|dir| dir.as_std_path())
+            .unwrap_or(project_dir);
+        tracked.push(local.manifest_path.clone().into_std_path_buf());
+        tracked.push(package_dir.join("src"));
+        tracked.push(package_dir.join("build.rs"));
+    }
+
+    // Missing paths would make cargo rerun the build script on every build.
+    tracked.retain(|path| path.exists());
+    tracked.sort();
+    tracked.dedup();
+    Ok((package.name.clone(), tracked))
+}
+
+/// `root` and every package without a registry or git source that it transitively depends on.
+fn local_dependency_closure<'a>(metadata: &'a Metadata, root: &PackageId) -> Vec<&'a Package> {
+    let Some(resolve) = &metadata.resolve else {
+        return metadata
+            .packages
+            .iter()
+            .filter(|pkg| &pkg.id == root)
+            .collect();
+    };
+    let mut visited = BTreeSet::new();
+    let mut pending = vec![root];
+    while let Some(id) = pending.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+        if let Some(node) = resolve.nodes.iter().find(|node| &node.id == id) {
+            pending.extend(node.deps.iter().map(|dep| &dep.pkg));
+        }
+    }
+    metadata
+        .packages
+        .iter()
+        .filter(|pkg| visited.contains(&pkg.id) && pkg.source.is_none())
+        .collect()
+}
+
+fn module_name(package: &str) -> String {
+    package.replace('-', "_")
+}
+
+fn render_module(guests: &[EmbeddedGuest]) -> Result<String> {
+    let mut module =
+        String::from("// @generated by `airbender_build::embed_guests`; do not edit.\n");
+    for guest in guests {
+        let dist_dir = guest.artifacts.dist_dir.to_str().ok_or_else(|| {
+            BuildError::InvalidConfig(format!(
+                "dist path is not valid UTF-8: {}",
+                guest.artifacts.dist_dir.display()
+            ))
+        })?;
+        let image_id = guest
+            .artifacts
+            .image_id
+            .as_bytes()
+            .iter()
+            .map(|byte| format!("0x{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        writeln!(
+            module,
+            "\n/// Guest package `{package}`.\n\
+             pub mod {module_name} {{\n    \
+                 /// Dist directory holding `manifest.toml` and the program artifacts.\n    \
+                 pub const DIST_DIR: &str = {dist_dir:?};\n    \
+                 /// Canonical image id of the program (`{image_id_hex}`).\n    \
+                 pub const IMAGE_ID: [u8; 32] = [{image_id}];\n\
+             }}",
+            package = guest.package,
+            module_name = guest.module_name,
+            image_id_hex = guest.artifacts.image_id,
+        )
+        .expect("writing to string cannot fail");
+    }
+    Ok(module)
+}
+
+fn env_path(name: &str) -> Result<PathBuf> {
+    std::env::var_os(name).map(PathBuf::from).ok_or_else(|| {
+        BuildError::InvalidConfig(format!(
+            "`{name}` is not set; `embed_guests` must be called from a build script"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ImageId, SizeReport, SizeSummary};
+
+    #[test]
+    fn renders_one_module_per_guest() {
+        let dist_dir = PathBuf::from("/out/airbender-guests/my-guest/app");
+        let guest = EmbeddedGuest {
+            package: "my-guest".to_string(),
+            module_name: module_name("my-guest"),
+            artifacts: DistArtifacts {
+                app_bin: dist_dir.join("app.bin"),
+                app_elf: dist_dir.join("app.elf"),
+                app_text: dist_dir.join("app.text"),
+                manifest: dist_dir.join("manifest.toml"),
+                dist_dir,
+                image_id: ImageId::from_bytes([0xab; 32]),
+                signature: None,
+                bundle: None,
+                up_to_date: false,
+                size: SizeReport {
+                    sections: Vec::new(),
+                    top_symbols: Vec::new(),
+                    summary: SizeSummary {
+                        rom_bytes: 0,
+                        rom_capacity: 0,
+                        text_bytes: 0,
+                        static_ram_bytes: 0,
+                        heap_bytes: 0,
+                    },
+                },
+            },
+        };
+
+        let module = render_module(&[guest]).expect("render module");
+        assert!(module.contains("pub mod my_guest {"));
+        assert!(
+            module.contains("pub const DIST_DIR: &str = \"/out/airbender-guests/my-guest/app\";")
+        );
+        assert!(module.contains(&format!(
+            "pub const IMAGE_ID: [u8; 32] = [{}];",
+            ["0xab"; 32].join(", ")
+        )));
+        assert!(module.contains(&"ab".repeat(32)));
+    }
+
+    #[test]
+    fn tracks_transitive_local_dependencies() {
+        let root = tempfile::tempdir().expect("create temp workspace");
+        let write = |path: &str, contents: &str| {
+            let path = root.path().join(path);
+            fs::create_dir_all(path.parent().expect("parent dir")).expect("create dir");
+            fs::write(path, contents).expect("write file");
+        };
+        let package = |name: &str, dependency: Option<&str>| {
+            let dependency = dependency
+                .map(|dep| format!("{dep} = {{ path = \"../{dep}\" }}\n"))
+                .unwrap_or_default();
+            format!(
+                "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+                 [workspace]\n\n[dependencies]\n{dependency}"
+            )
+        };
+        write("guest/Cargo.toml", &package("guest", Some("direct")));
+        write("guest/src/main.rs", "fn main() {}");
+        write("direct/Cargo.toml", &package("direct", Some("transitive")));
+        write("direct/src/lib.rs", "");
+        write("transitive/Cargo.toml", &package("transitive", None));
+        write("transitive/src/lib.rs", "");
+        write("transitive/build.rs", "fn main() {}");
+        write("unrelated/src/lib.rs", "");
+
+        let (name, tracked) = guest_sources(&root.path().join("guest")).expect("guest sources");
+        assert_eq!(name, "guest");
+        let root = root.path().canonicalize().expect("canonical root");
+        for expected in [
+            "guest/Cargo.toml",
+            "guest/src",
+            "direct/src",
+            "transitive/Cargo.toml",
+            "transitive/src",
+            "transitive/build.rs",
+        ] {
+            assert!(
+                tracked.contains(&root.join(expected)),
+                "`{expected}` must be tracked: {tracked:?}"
+            );
+        }
+        assert!(!tracked
+            .iter()
+            .any(|path| path.starts_with(root.join("unrelated"))));
+        assert!(tracked.iter().all(|path| path.exists()));
+    }
+
+    #[test]
+    fn requires_build_script_environment() {
+        let err = env_path("AIRBENDER_BUILD_TEST_UNSET_VAR").expect_err("variable is unset");
+        assert!(err
+            .to_string()
+            .contains("must be called from a build script"));
+    }
+}
@@ -1,6 +1,8 @@
 //! Error types surfaced by guest artifact building.
 
+use airbender_core::host::bundle::BundleError;
 use airbender_core::host::manifest::ManifestError;
+use airbender_core::host::signature::SignatureError;
 use std::process::ExitStatus;
 
 /// Unified error type for build and packaging operations.
@@ -21,6 +23,14 @@ pub enum BuildError {
     /// Signals invalid user inputs or metadata content.
     #[error("invalid config: {0}")]
     InvalidConfig(String),
+
+    /// Signals a guest executable that cannot be packaged for the machine.
+    #[error("invalid guest ELF: {0}")]
+    InvalidElf(String),
+
+    /// Signals a program image larger than the configured ROM budget.
+    #[error("program image is {size} bytes, exceeding the ROM budget of {budget} bytes")]
+    RomBudgetExceeded { size: u64, budget: u64 },
 }
 
 impl From<ManifestError> for BuildError {
@@ -32,5 +42,24 @@ impl From<ManifestError> for BuildError {
     }
 }
 
+impl From<BundleError> for BuildError {
+    fn from(err: BundleError) -> Self {
+        match err {
+            BundleError::Io(err) => Self::Io(err),
+            BundleError::Manifest(err) => err.into(),
+            _ => Self::InvalidConfig(err.to_string()),
+        }
+    }
+}
+
+impl From<SignatureError> for BuildError {
+    fn from(err: SignatureError) -> Self {
+        match err {
+            SignatureError::Io(err) => Self::Io(err),
+            _ => Self::InvalidConfig(err.to_string()),
+        }
+    }
+}
+
 /// Convenience result alias for crate APIs.
 pub type Result<T> = std::result::Result<T, BuildError>;
@@ -2,12 +2,34 @@
 
 mod config;
 mod constants;
+mod elf;
+mod embed;
 mod errors;
+mod migrate;
+mod provenance;
+mod size;
 mod utils;
+mod verify;
 
+pub use airbender_core::host::bundle::{Bundle, BundleEntry, BUNDLE_EXTENSION};
 pub use airbender_core::host::manifest::{
-    ArtifactEntry, BuildMetadata, Manifest, Profile, CODEC_VERSION_V0, MANIFEST_VERSION_V1,
+    ArtifactEntry, BuildMetadata, DistIndex, DistIndexEntry, InterfaceDescription, Lto, Manifest,
+    OptLevel, Profile, SizeSummary, CODEC_VERSION_V0, DIST_INDEX_FILE, MANIFEST_VERSION_LATEST,
+    MANIFEST_VERSION_V1, MANIFEST_VERSION_V2,
 };
-pub use config::{build_dist, BuildConfig, DistArtifacts};
-pub use constants::{DEFAULT_GUEST_TARGET, DEFAULT_GUEST_TOOLCHAIN};
+pub use airbender_core::host::signature::{
+    ManifestPublicKey, ManifestSignature, ManifestSigningKey, MANIFEST_SIGNATURE_FILE,
+};
+pub use airbender_core::image_id::{ImageId, MachineConfig};
+pub use config::{
+    build_dist, build_workspace, BuildConfig, DistArtifacts, ManifestSignatureArtifact,
+    WorkspaceArtifacts,
+};
+pub use constants::{DEFAULT_GUEST_TARGET, DEFAULT_GUEST_TOOLCHAIN, RAM_BYTE_SIZE, ROM_BYTE_SIZE};
+pub use embed::{embed_guests, EmbeddedGuest, EMBEDDED_GUESTS_FILE};
 pub use errors::{BuildError, Result};
+pub use migrate::{migrate_manifest, ManifestMigration};
+pub use size::{MemoryRegion, SectionSize, SizeReport, SymbolSize, TOP_SYMBOLS};
+pub use verify::{
+    verify_build, ArtifactComparison, BuildVerification, ProvenanceDiff, SectionDiff,
+};
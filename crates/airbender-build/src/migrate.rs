@@ -0,0 +1,152 @@
+//! In-place migration of dist manifests to the latest schema version.
+
+use crate::config::{load_signing_key, ManifestSignatureArtifact};
+use crate::errors::{BuildError, Result};
+use crate::utils::sha256_file_hex;
+use crate::{ArtifactEntry, ImageId, MachineConfig, Manifest, MANIFEST_SIGNATURE_FILE};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Outcome of [`migrate_manifest`].
+#[derive(Clone, Debug)]
+pub struct ManifestMigration {
+    /// Path to the migrated `manifest.toml`.
+    pub manifest: PathBuf,
+    /// Schema version the manifest was written with.
+    pub from_version: String,
+    /// Schema version after migration.
+    pub to_version: String,
+    /// Image id computed for a manifest that did not record one.
+    pub computed_image_id: Option<ImageId>,
+    /// Signature written over the migrated manifest, when a signing key was given.
+    pub signature: Option<ManifestSignatureArtifact>,
+}
+
+impl ManifestMigration {
+    /// Whether `manifest.toml` was rewritten.
+    pub fn changed(&self) -> bool {
+        self.from_version != self.to_version || self.computed_image_id.is_some()
+    }
+}
+
+/// Rewrite `manifest.toml` in `dist_dir` with the latest schema version.
+///
+/// Missing image ids are computed from `app.bin` and `app.text` once their recorded digests
+/// check out. Rewriting invalidates an existing `manifest.toml.sig`, so a signed dist needs
+/// `sign_key` to re-sign the migrated manifest. Up-to-date manifests are left untouched.
+pub fn migrate_manifest(dist_dir: &Path, sign_key: Option<&Path>) -> Result<ManifestMigration> {
+    let manifest_path = dist_dir.join("manifest.toml");
+    let manifest = Manifest::read_from_file(&manifest_path)?;
+    let from_version = manifest.manifest.clone();
+    let sign_key = sign_key.map(load_signing_key).transpose()?;
+
+    let computed_image_id = match manifest.image_id {
+        Some(_) => None,
+        None => Some(ImageId::compute(
+            &read_verified(dist_dir, &manifest.bin)?,
+            &read_verified(dist_dir, &manifest.text)?,
+            MachineConfig::default(),
+        )),
+    };
+    let mut migration = ManifestMigration {
+        manifest: manifest_path,
+        from_version,
+        to_version: manifest.manifest.clone(),
+        computed_image_id,
+        signature: None,
+    };
+    if manifest.is_latest() && computed_image_id.is_none() {
+        return Ok(migration);
+    }
+
+    let signature_path = dist_dir.join(MANIFEST_SIGNATURE_FILE);
+    if signature_path.exists() && sign_key.is_none() {
+        return Err(BuildError::InvalidConfig(format!(
+            "`{}` would no longer match the migrated manifest; pass a signing key to re-sign it",
+            signature_path.display()
+        )));
+    }
+
+    let mut manifest = manifest.upgrade();
+    manifest.image_id = manifest.image_id.or(computed_image_id);
+    manifest.write_to_file(&migration.manifest)?;
+    migration.to_version = manifest.manifest;
+
+    if let Some(sign_key) = sign_key {
+        let signature = sign_key.sign(&fs::read(&migration.manifest)?);
+        signature.write_to_file(&signature_path)?;
+        migration.signature = Some(ManifestSignatureArtifact {
+            path: signature_path,
+            public_key: signature.public_key,
+        });
+    }
+    Ok(migration)
+}
+
+fn read_verified(dist_dir: &Path, entry: &ArtifactEntry) -> Result<Vec<u8>> {
+    let path = dist_dir.join(&entry.path);
+    if !sha256_file_hex(&path)?.eq_ignore_ascii_case(&entry.sha256) {
+        return Err(BuildError::InvalidConfig(format!(
+            "`{}` does not match its manifest digest",
+            path.display()
+        )));
+    }
+    Ok(fs::read(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BuildMetadata, Profile, MANIFEST_VERSION_V1, MANIFEST_VERSION_V2};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn migrates_v1_dist_and_computes_missing_image_id() {
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let dist_dir = temp_dir.path();
+        let artifact = |name: &str, contents: &[u8]| {
+            let path = dist_dir.join(name);
+            fs::write(&path, contents).expect("write artifact");
+            ArtifactEntry {
+                path: name.to_string(),
+                sha256: sha256_file_hex(&path).expect("hash artifact"),
+            }
+        };
+        let manifest = Manifest {
+            package: "demo".to_string(),
+            bin_name: None,
+            manifest: MANIFEST_VERSION_V1.to_string(),
+            codec: "v0".to_string(),
+            target: None,
+            image_id: None,
+            bin: artifact("app.bin", &[1; 8]),
+            elf: artifact("app.elf", &[2; 8]),
+            text: artifact("app.text", &[3; 8]),
+            artifacts: BTreeMap::new(),
+            interface: None,
+            build: BuildMetadata::new(Profile::Release),
+            size: None,
+        };
+        manifest
+            .write_to_file(&dist_dir.join("manifest.toml"))
+            .expect("write manifest");
+
+        fs::write(dist_dir.join(MANIFEST_SIGNATURE_FILE), "stale").expect("write signature");
+        let err = migrate_manifest(dist_dir, None).expect_err("signed dist needs a key");
+        assert!(err.to_string().contains("pass a signing key"));
+        fs::remove_file(dist_dir.join(MANIFEST_SIGNATURE_FILE)).expect("remove signature");
+
+        let migration = migrate_manifest(dist_dir, None).expect("migrate");
+        let expected_image_id = ImageId::compute(&[1; 8], &[3; 8], MachineConfig::default());
+        assert!(migration.changed());
+        assert_eq!(migration.from_version, MANIFEST_VERSION_V1);
+        assert_eq!(migration.to_version, MANIFEST_VERSION_V2);
+        assert_eq!(migration.computed_image_id, Some(expected_image_id));
+        let migrated = Manifest::read_from_file(&migration.manifest).expect("read migrated");
+        assert!(migrated.is_latest());
+        assert_eq!(migrated.image_id, Some(expected_image_id));
+
+        let again = migrate_manifest(dist_dir, None).expect("migrate again");
+        assert!(!again.changed());
+    }
+}
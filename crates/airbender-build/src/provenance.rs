@@ -0,0 +1,194 @@
+//! Toolchain, dependency and timestamp provenance recorded in the manifest.
+
+use crate::errors::{BuildError, Result};
+use airbender_core::host::manifest::SDK_CRATES;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Release and commit of the `rustc` that compiles the guest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct RustcVersion {
+    pub release: String,
+    pub commit_hash: Option<String>,
+    pub host: String,
+}
+
+/// Runs `rustc -vV` in the guest project, so its `rust-toolchain.toml` applies.
+///
+/// Provenance is best-effort like git metadata: `None` if `rustc` cannot be queried.
+pub(crate) fn resolve_rustc_version(mut cmd: Command, project_dir: &Path) -> Option<RustcVersion> {
+    let output = cmd.arg("-vV").current_dir(project_dir).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_rustc_version(&String::from_utf8(output.stdout).ok()?)
+}
+
+fn parse_rustc_version(output: &str) -> Option<RustcVersion> {
+    let field = |name: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(": "))
+            .map(|value| value.trim().to_string())
+    };
+    Some(RustcVersion {
+        release: field("release")?,
+        commit_hash: field("commit-hash").filter(|hash| hash != "unknown"),
+        host: field("host")?,
+    })
+}
+
+/// Recovers the target triple from the executable path cargo reported.
+///
+/// Cargo writes executables to `<target-dir>/<triple>/<profile>/` when a target is configured
+/// and to `<target-dir>/<profile>/` otherwise; the target dir root holds `CACHEDIR.TAG`.
+pub(crate) fn resolve_target_from_executable(executable: &Path, host: &str) -> Option<String> {
+    let target_or_root = executable.parent()?.parent()?;
+    if target_or_root.join("CACHEDIR.TAG").is_file() {
+        return Some(host.to_string());
+    }
+    target_or_root
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_string)
+}
+
+/// Versions of the [`SDK_CRATES`] locked in the guest workspace `Cargo.lock`.
+///
+/// The lock is read directly because build metadata is loaded with `--no-deps`.
+/// An unreadable lock yields no versions, keeping provenance best-effort.
+pub(crate) fn sdk_versions(cargo_lock: &str) -> BTreeMap<String, String> {
+    let Ok(lock) = cargo_lock.parse::<toml::Table>() else {
+        return BTreeMap::new();
+    };
+    let mut versions = BTreeMap::new();
+    let packages = lock.get("package").and_then(toml::Value::as_array);
+    for package in packages.into_iter().flatten() {
+        let name = package.get("name").and_then(toml::Value::as_str);
+        let version = package.get("version").and_then(toml::Value::as_str);
+        if let (Some(name), Some(version)) = (name, version) {
+            if SDK_CRATES.contains(&name) {
+                versions
+                    .entry(name.to_string())
+                    .or_insert_with(|| version.to_string());
+            }
+        }
+    }
+    versions
+}
+
+/// Build timestamp, honoring `SOURCE_DATE_EPOCH` for reproducible builds.
+pub(crate) fn build_timestamp() -> Result<u64> {
+    parse_build_timestamp(std::env::var("SOURCE_DATE_EPOCH").ok().as_deref())
+}
+
+fn parse_build_timestamp(source_date_epoch: Option<&str>) -> Result<u64> {
+    match source_date_epoch {
+        Some(value) => value.trim().parse().map_err(|_| {
+            BuildError::InvalidConfig(format!(
+                "`SOURCE_DATE_EPOCH` must be a number of seconds, got `{value}`"
+            ))
+        }),
+        None => Ok(SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rustc_verbose_version() {
+        let output = "rustc 1.95.0-nightly (0123abcd 2026-02-09)\n\
+                      binary: rustc\n\
+                      commit-hash: 0123abcd\n\
+                      commit-date: 2026-02-09\n\
+                      host: x86_64-unknown-linux-gnu\n\
+                      release: 1.95.0-nightly\n\
+                      LLVM version: 21.1.0\n";
+        assert_eq!(
+            parse_rustc_version(output),
+            Some(RustcVersion {
+                release: "1.95.0-nightly".to_string(),
+                commit_hash: Some("0123abcd".to_string()),
+                host: "x86_64-unknown-linux-gnu".to_string(),
+            })
+        );
+
+        let local_build = output.replace("commit-hash: 0123abcd", "commit-hash: unknown");
+        assert_eq!(
+            parse_rustc_version(&local_build).and_then(|version| version.commit_hash),
+            None
+        );
+        assert_eq!(parse_rustc_version("rustc 1.95.0"), None);
+    }
+
+    #[test]
+    fn resolves_target_from_cargo_output_layout() {
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let target_dir = temp_dir.path();
+        std::fs::write(target_dir.join("CACHEDIR.TAG"), "").expect("write cache tag");
+        let host = "x86_64-unknown-linux-gnu";
+
+        assert_eq!(
+            resolve_target_from_executable(
+                &target_dir.join("riscv32im-risc0-zkvm-elf/release/guest"),
+                host
+            )
+            .as_deref(),
+            Some("riscv32im-risc0-zkvm-elf")
+        );
+        assert_eq!(
+            resolve_target_from_executable(&target_dir.join("release/guest"), host).as_deref(),
+            Some(host)
+        );
+    }
+
+    #[test]
+    fn reads_sdk_versions_from_cargo_lock() {
+        let lock = r#"
+version = 4
+
+[[package]]
+name = "airbender-sdk"
+version = "0.1.0"
+source = "git+https://github.com/popzxc/airbender-platform?branch=main#abc123"
+
+[[package]]
+name = "airbender-rt"
+version = "0.1.0"
+
+[[package]]
+name = "airbender-guest-utils"
+version = "2.0.0"
+
+[[package]]
+name = "guest"
+version = "0.3.0"
+"#;
+        let versions = sdk_versions(lock);
+        assert_eq!(
+            versions.into_iter().collect::<Vec<_>>(),
+            [
+                ("airbender-rt".to_string(), "0.1.0".to_string()),
+                ("airbender-sdk".to_string(), "0.1.0".to_string()),
+            ]
+        );
+        assert!(sdk_versions("not = [valid").is_empty());
+    }
+
+    #[test]
+    fn source_date_epoch_overrides_build_timestamp() {
+        assert_eq!(
+            parse_build_timestamp(Some("1700000000")).ok(),
+            Some(1_700_000_000)
+        );
+        assert!(parse_build_timestamp(Some("yesterday")).is_err());
+        assert!(parse_build_timestamp(None).expect("current time") > 1_700_000_000);
+    }
+}
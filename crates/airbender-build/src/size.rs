@@ -0,0 +1,184 @@
+//! Size report of a packaged guest program.
+
+use crate::constants::{RAM_BYTE_SIZE, ROM_BYTE_SIZE};
+use crate::elf::{invalid_elf, read_error, ElfHeader};
+use crate::errors::Result;
+use crate::SizeSummary;
+use object::elf;
+use object::read::elf::{FileHeader, SectionHeader, Sym};
+use std::fmt;
+
+/// Number of symbols listed in [`SizeReport::top_symbols`].
+pub const TOP_SYMBOLS: usize = 10;
+
+/// Memory region an allocated section lives in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryRegion {
+    Rom,
+    Ram,
+}
+
+impl fmt::Display for MemoryRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoryRegion::Rom => f.write_str("ROM"),
+            MemoryRegion::Ram => f.write_str("RAM"),
+        }
+    }
+}
+
+/// Size of one allocated section.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SectionSize {
+    pub name: String,
+    /// Virtual address of the section.
+    pub address: u64,
+    pub size: u64,
+    pub region: MemoryRegion,
+}
+
+/// Size of one function or data symbol.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbolSize {
+    /// Demangled symbol name without the trailing hash.
+    pub name: String,
+    pub address: u64,
+    pub size: u64,
+}
+
+/// Section and symbol sizes of a program, with the summary recorded in the manifest.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SizeReport {
+    /// Allocated sections in address order.
+    pub sections: Vec<SectionSize>,
+    /// Largest symbols, biggest first, at most [`TOP_SYMBOLS`].
+    pub top_symbols: Vec<SymbolSize>,
+    pub summary: SizeSummary,
+}
+
+impl SizeReport {
+    /// Builds the report from the guest executable and the size of `app.bin`.
+    ///
+    /// `app.elf` lacks `.text` and the code symbols, so the report reads the executable it was
+    /// stripped from.
+    pub(crate) fn from_elf(data: &[u8], rom_bytes: u64) -> Result<Self> {
+        let header = ElfHeader::parse(data).map_err(read_error)?;
+        let endian = header.endian().map_err(read_error)?;
+        let sections = header.sections(endian, data).map_err(read_error)?;
+
+        let mut section_sizes = Vec::new();
+        let mut static_ram_end = ROM_BYTE_SIZE;
+        for section in sections.iter() {
+            let size = u64::from(section.sh_size(endian));
+            if u64::from(section.sh_flags(endian)) & u64::from(elf::SHF_ALLOC) == 0 || size == 0 {
+                continue;
+            }
+            let name = sections.section_name(endian, section).map_err(read_error)?;
+            let address = u64::from(section.sh_addr(endian));
+            let region = if address < ROM_BYTE_SIZE {
+                MemoryRegion::Rom
+            } else {
+                static_ram_end = static_ram_end.max(address + size);
+                MemoryRegion::Ram
+            };
+            section_sizes.push(SectionSize {
+                name: String::from_utf8_lossy(name).into_owned(),
+                address,
+                size,
+                region,
+            });
+        }
+        section_sizes.sort_by_key(|section| section.address);
+
+        let symbols = sections
+            .symbols(endian, data, elf::SHT_SYMTAB)
+            .map_err(read_error)?;
+        let mut top_symbols = Vec::new();
+        for symbol in symbols.iter() {
+            let size = u64::from(symbol.st_size(endian));
+            if size == 0 || !matches!(symbol.st_type(), elf::STT_FUNC | elf::STT_OBJECT) {
+                continue;
+            }
+            let name = symbols.symbol_name(endian, symbol).map_err(read_error)?;
+            let name = std::str::from_utf8(name)
+                .map_err(|_| invalid_elf("symbol name is not UTF-8".to_string()))?;
+            top_symbols.push(SymbolSize {
+                name: format!("{:#}", rustc_demangle::demangle(name)),
+                address: u64::from(symbol.st_value(endian)),
+                size,
+            });
+        }
+        top_symbols.sort_by(|a, b| b.size.cmp(&a.size).then(a.address.cmp(&b.address)));
+        top_symbols.truncate(TOP_SYMBOLS);
+
+        let text_bytes = section_sizes
+            .iter()
+            .find(|section| section.name == ".text")
+            .map_or(0, |section| section.size);
+        let static_ram_bytes = section_sizes
+            .iter()
+            .filter(|section| section.region == MemoryRegion::Ram)
+            .map(|section| section.size)
+            .sum();
+
+        Ok(Self {
+            sections: section_sizes,
+            top_symbols,
+            summary: SizeSummary {
+                rom_bytes,
+                rom_capacity: ROM_BYTE_SIZE,
+                text_bytes,
+                static_ram_bytes,
+                heap_bytes: RAM_BYTE_SIZE.saturating_sub(static_ram_end),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elf::extract_artifacts;
+    use crate::elf::tests::sample_elf;
+
+    #[test]
+    fn reports_sections_symbols_and_memory_usage() {
+        let executable = sample_elf(ROM_BYTE_SIZE);
+        let artifacts = extract_artifacts(&executable).expect("extract");
+        let report =
+            SizeReport::from_elf(&executable, artifacts.bin.len() as u64).expect("size report");
+
+        let sections: Vec<_> = report
+            .sections
+            .iter()
+            .map(|section| (section.name.as_str(), section.size, section.region))
+            .collect();
+        assert_eq!(
+            sections,
+            [
+                (".text", 8, MemoryRegion::Rom),
+                (".rodata", 4, MemoryRegion::Rom),
+                (".data", 4, MemoryRegion::Ram),
+                (".bss", 16, MemoryRegion::Ram),
+            ]
+        );
+        assert_eq!(
+            report.top_symbols,
+            [SymbolSize {
+                name: "main".to_string(),
+                address: 4,
+                size: 4,
+            }]
+        );
+        assert_eq!(
+            report.summary,
+            SizeSummary {
+                rom_bytes: 16,
+                rom_capacity: ROM_BYTE_SIZE,
+                text_bytes: 8,
+                static_ram_bytes: 20,
+                heap_bytes: RAM_BYTE_SIZE - ROM_BYTE_SIZE - 20,
+            }
+        );
+    }
+}
@@ -1,9 +1,11 @@
 //! Internal helpers for command execution, metadata loading, and validation.
 
+use crate::config::ManifestNames;
+use crate::constants::SDK_PACKAGE_NAME;
 use crate::errors::{BuildError, Result};
-use cargo_metadata::{Metadata, MetadataCommand, Package};
+use crate::InterfaceDescription;
+use cargo_metadata::{DependencyKind, Metadata, MetadataCommand, Package};
 use sha2::Digest;
-use std::fmt::Write;
 use std::path::{Component, Path};
 use std::process::Command;
 
@@ -15,19 +17,6 @@ pub(crate) struct GitMetadata {
     pub is_dirty: bool,
 }
 
-/// Runs a command and maps non-success exit codes into [`BuildError`].
-pub(crate) fn run_command(mut cmd: Command, name: &str) -> Result<()> {
-    let status = cmd.status()?;
-    if status.success() {
-        Ok(())
-    } else {
-        Err(BuildError::ProcessFailed {
-            cmd: name.to_string(),
-            status,
-        })
-    }
-}
-
 /// Validates that `app_name` is exactly one normal path segment.
 ///
 /// This prevents accidental writes outside the dist root via separators,
@@ -64,6 +53,14 @@ pub(crate) fn load_metadata(manifest_path: &Path) -> Result<Metadata> {
         .map_err(|err| BuildError::InvalidConfig(format!("cargo metadata failed: {err}")))
 }
 
+/// Like [`load_metadata`], but also resolves the dependency graph.
+pub(crate) fn load_metadata_with_deps(manifest_path: &Path) -> Result<Metadata> {
+    MetadataCommand::new()
+        .manifest_path(manifest_path)
+        .exec()
+        .map_err(|err| BuildError::InvalidConfig(format!("cargo metadata failed: {err}")))
+}
+
 /// Finds the package that corresponds to `manifest_path` within metadata output.
 ///
 /// If an exact manifest match is absent, this falls back to Cargo's root package.
@@ -93,6 +90,49 @@ pub(crate) fn find_package<'a>(
         .ok_or(BuildError::MissingField("package.name"))
 }
 
+/// Finds the guest binaries of a workspace: every binary target of a member that depends on
+/// `airbender-sdk`, in workspace member order.
+///
+/// Binary names become app directory names, so they must be unique and valid app names.
+pub(crate) fn discover_guests(metadata: &Metadata) -> Result<Vec<ManifestNames>> {
+    let mut guests: Vec<ManifestNames> = Vec::new();
+    for package in metadata.workspace_packages() {
+        let is_guest = package
+            .dependencies
+            .iter()
+            .any(|dep| dep.name == SDK_PACKAGE_NAME && dep.kind == DependencyKind::Normal);
+        if !is_guest {
+            continue;
+        }
+        let interface = resolve_interface(package)?;
+        for target in &package.targets {
+            if !target.kind.iter().any(|kind| kind == "bin") {
+                continue;
+            }
+            validate_app_name(&target.name)?;
+            if let Some(other) = guests.iter().find(|guest| guest.bin_name == target.name) {
+                return Err(BuildError::InvalidConfig(format!(
+                    "binary target `{}` is defined by both `{}` and `{}`; guest binary names must be unique",
+                    target.name, other.package, package.name
+                )));
+            }
+            guests.push(ManifestNames {
+                package: package.name.clone(),
+                bin_name: target.name.clone(),
+                interface: interface.clone(),
+            });
+        }
+    }
+
+    if guests.is_empty() {
+        return Err(BuildError::InvalidConfig(format!(
+            "no guest packages found in workspace `{}`; guests depend on `{SDK_PACKAGE_NAME}`",
+            metadata.workspace_root
+        )));
+    }
+    Ok(guests)
+}
+
 /// Resolves the binary target name used for build commands.
 ///
 /// This enforces explicit selection when a package defines multiple binary targets.
@@ -113,7 +153,7 @@ fn resolve_bin_name_from_candidates(
     explicit_bin: Option<&str>,
 ) -> Result<String> {
     if let Some(explicit_bin) = explicit_bin {
-        if bin_names.iter().any(|bin_name| *bin_name == explicit_bin) {
+        if bin_names.contains(&explicit_bin) {
             return Ok(explicit_bin.to_string());
         }
 
@@ -140,15 +180,40 @@ fn resolve_bin_name_from_candidates(
     }
 }
 
+/// Reads the program interface from `[package.metadata.airbender]` in the guest `Cargo.toml`.
+///
+/// Returns `None` when the package declares neither `input` nor `output`.
+pub(crate) fn resolve_interface(package: &Package) -> Result<Option<InterfaceDescription>> {
+    interface_from_metadata(&package.name, &package.metadata)
+}
+
+fn interface_from_metadata(
+    package_name: &str,
+    metadata: &serde_json::Value,
+) -> Result<Option<InterfaceDescription>> {
+    let metadata = &metadata["airbender"];
+    let field = |name: &str| match &metadata[name] {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(value) => Ok(Some(value.clone())),
+        _ => Err(BuildError::InvalidConfig(format!(
+            "`package.metadata.airbender.{name}` of `{package_name}` must be a string"
+        ))),
+    };
+    let interface = InterfaceDescription {
+        input: field("input")?,
+        output: field("output")?,
+    };
+    Ok((!interface.is_empty()).then_some(interface))
+}
+
 /// Computes a lowercase hex SHA-256 digest for a file.
 pub(crate) fn sha256_file_hex(path: &Path) -> Result<String> {
-    let bytes = std::fs::read(path)?;
-    let digest = sha2::Sha256::digest(bytes);
-    let mut encoded = String::with_capacity(digest.len() * 2);
-    for byte in digest {
-        write!(&mut encoded, "{byte:02x}").expect("writing to string cannot fail");
-    }
-    Ok(encoded)
+    Ok(sha256_hex(&std::fs::read(path)?))
+}
+
+/// Computes a lowercase hex SHA-256 digest of `bytes`.
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    airbender_core::hex::encode(&sha2::Sha256::digest(bytes))
 }
 
 /// Resolves git metadata for the guest project repository.
@@ -294,6 +359,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn discovers_guest_binaries_of_workspace_members() {
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let root = temp_dir.path();
+        let write = |path: &str, contents: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().expect("parent dir")).expect("create dir");
+            std::fs::write(path, contents).expect("write file");
+        };
+        write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"sdk\", \"host\", \"guests\"]\nresolver = \"2\"\n",
+        );
+        write(
+            "sdk/Cargo.toml",
+            "[package]\nname = \"airbender-sdk\"\nversion = \"0.1.0\"\n",
+        );
+        write("sdk/src/lib.rs", "");
+        write(
+            "host/Cargo.toml",
+            "[package]\nname = \"host\"\nversion = \"0.1.0\"\n",
+        );
+        write("host/src/main.rs", "fn main() {}");
+        write(
+            "guests/Cargo.toml",
+            "[package]\nname = \"guests\"\nversion = \"0.1.0\"\n\n\
+             [package.metadata.airbender]\ninput = \"u32\"\n\n\
+             [dependencies]\nairbender = { package = \"airbender-sdk\", path = \"../sdk\" }\n\n\
+             [[bin]]\nname = \"alpha\"\npath = \"src/alpha.rs\"\n\n\
+             [[bin]]\nname = \"beta\"\npath = \"src/beta.rs\"\n",
+        );
+        write("guests/src/alpha.rs", "fn main() {}");
+        write("guests/src/beta.rs", "fn main() {}");
+
+        let metadata = load_metadata(&root.join("Cargo.toml")).expect("load metadata");
+        let guests = discover_guests(&metadata).expect("discover guests");
+        let names: Vec<_> = guests
+            .iter()
+            .map(|guest| (guest.package.as_str(), guest.bin_name.as_str()))
+            .collect();
+        assert_eq!(names, [("guests", "alpha"), ("guests", "beta")]);
+        assert_eq!(
+            guests[0]
+                .interface
+                .as_ref()
+                .and_then(|interface| interface.input.as_deref()),
+            Some("u32")
+        );
+    }
+
+    #[test]
+    fn reads_interface_from_package_metadata() {
+        let metadata = serde_json::json!({
+            "airbender": { "input": "u32", "output": "my_guest::Output" },
+        });
+        assert_eq!(
+            interface_from_metadata("guest", &metadata).expect("interface"),
+            Some(InterfaceDescription {
+                input: Some("u32".to_string()),
+                output: Some("my_guest::Output".to_string()),
+            })
+        );
+        assert_eq!(
+            interface_from_metadata("guest", &serde_json::Value::Null).expect("no metadata"),
+            None
+        );
+
+        let err =
+            interface_from_metadata("guest", &serde_json::json!({ "airbender": { "input": 1 } }))
+                .expect_err("non-string type must fail");
+        assert_eq!(
+            err.to_string(),
+            "invalid config: `package.metadata.airbender.input` of `guest` must be a string"
+        );
+    }
+
     fn unique_temp_dir_path(label: &str) -> std::path::PathBuf {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -0,0 +1,502 @@
+//! Reproducibility check that rebuilds a guest and compares it with an existing dist.
+
+use crate::config::{build_dist, BuildConfig};
+use crate::elf::{image_sections, ImageSection};
+use crate::errors::{BuildError, Result};
+use crate::provenance::{resolve_rustc_version, RustcVersion};
+use crate::utils::sha256_hex;
+use crate::{ArtifactEntry, BuildMetadata, Bundle, ImageId, Manifest, MANIFEST_SIGNATURE_FILE};
+use std::fs;
+use std::path::Path;
+
+/// Recorded and rebuilt digest of one artifact.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArtifactComparison {
+    /// Digest recorded in the reference manifest.
+    pub expected_sha256: String,
+    /// Digest of the rebuilt artifact.
+    pub actual_sha256: String,
+}
+
+impl ArtifactComparison {
+    pub fn matches(&self) -> bool {
+        self.expected_sha256
+            .eq_ignore_ascii_case(&self.actual_sha256)
+    }
+}
+
+/// Image section whose contents or size differ between the reference and the rebuild.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SectionDiff {
+    pub name: String,
+    /// Size in the reference image; `None` if the section only exists in the rebuild.
+    pub expected_size: Option<u64>,
+    /// Size in the rebuilt image; `None` if the section only exists in the reference.
+    pub actual_size: Option<u64>,
+    /// Offset of the first differing byte within the section, if the common bytes differ.
+    pub first_difference: Option<u64>,
+    /// Number of differing bytes within the common length of both sections.
+    pub differing_bytes: u64,
+}
+
+/// Provenance field recorded in the reference manifest that the rebuild does not match.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProvenanceDiff {
+    pub field: &'static str,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Outcome of [`verify_build`].
+#[derive(Clone, Debug)]
+pub struct BuildVerification {
+    /// Cargo package of the verified program.
+    pub package: String,
+    pub bin: ArtifactComparison,
+    pub text: ArtifactComparison,
+    /// Image id recorded in the reference manifest, if any.
+    pub expected_image_id: Option<ImageId>,
+    /// Image id of the rebuild.
+    pub actual_image_id: ImageId,
+    /// Differing image sections; empty when the build is reproducible.
+    pub section_diffs: Vec<SectionDiff>,
+    /// Toolchain and dependency differences that may explain a mismatch.
+    pub provenance_diffs: Vec<ProvenanceDiff>,
+}
+
+impl BuildVerification {
+    /// Whether `app.bin` and `app.text` were reproduced byte-for-byte.
+    pub fn is_reproducible(&self) -> bool {
+        self.bin.matches() && self.text.matches()
+    }
+}
+
+/// Rebuild the guest in `project_dir` with the provenance recorded in `dist` and compare.
+///
+/// `dist` is a dist directory or a single-file bundle. The rebuild uses the recorded profile,
+/// binary, target and cargo arguments, with a fresh target directory so no cached artifacts
+/// are reused; the temporary directory is removed afterwards.
+pub fn verify_build(dist: &Path, project_dir: &Path) -> Result<BuildVerification> {
+    let reference = ReferenceDist::load(dist)?;
+
+    let work_dir = tempfile::Builder::new()
+        .prefix("airbender-verify-build-")
+        .tempdir()?;
+    rebuild_and_compare(&reference, project_dir, work_dir.path())
+}
+
+fn rebuild_and_compare(
+    reference: &ReferenceDist,
+    project_dir: &Path,
+    work_dir: &Path,
+) -> Result<BuildVerification> {
+    let manifest = &reference.manifest;
+    let mut config = BuildConfig::new(project_dir);
+    config.bin_name = Some(
+        manifest
+            .bin_name
+            .clone()
+            .unwrap_or_else(|| manifest.package.clone()),
+    );
+    config.target = manifest
+        .target
+        .clone()
+        .or_else(|| manifest.build.resolved_target.clone());
+    config.profile = manifest.build.profile;
+    config.cargo_args = manifest.build.cargo_args.clone();
+    // Recorded features are the resolved set, so requesting them reproduces it. Manifests from
+    // before typed settings carry the requested features in the forwarded cargo args instead.
+    let forwards_features = config.cargo_args.iter().any(|arg| {
+        arg.starts_with("--features") || arg.starts_with("-F") || arg == "--all-features"
+    });
+    if !forwards_features {
+        config.features = manifest
+            .build
+            .features
+            .iter()
+            .filter(|feature| *feature != "default")
+            .cloned()
+            .collect();
+    }
+    config.no_default_features = manifest.build.no_default_features;
+    config.rustflags = manifest.build.rustflags.clone();
+    config.opt_level = manifest.build.opt_level;
+    config.lto = manifest.build.lto;
+    config.codegen_units = manifest.build.codegen_units;
+    config.dist_dir = Some(work_dir.join("dist"));
+    config.target_dir = Some(work_dir.join("target"));
+    check_toolchain(
+        &manifest.build,
+        resolve_rustc_version(config.rustc_command(), project_dir).as_ref(),
+    )?;
+    let artifacts = build_dist(&config)?;
+
+    let rebuilt = Manifest::read_from_file(&artifacts.manifest)?;
+    if rebuilt.package != manifest.package {
+        return Err(BuildError::InvalidConfig(format!(
+            "project builds package `{}`, but the dist was built from `{}`",
+            rebuilt.package, manifest.package
+        )));
+    }
+
+    let verification = BuildVerification {
+        package: manifest.package.clone(),
+        bin: ArtifactComparison {
+            expected_sha256: manifest.bin.sha256.clone(),
+            actual_sha256: rebuilt.bin.sha256.clone(),
+        },
+        text: ArtifactComparison {
+            expected_sha256: manifest.text.sha256.clone(),
+            actual_sha256: rebuilt.text.sha256.clone(),
+        },
+        expected_image_id: manifest.image_id,
+        actual_image_id: artifacts.image_id,
+        section_diffs: Vec::new(),
+        provenance_diffs: provenance_diffs(&manifest.build, &rebuilt.build),
+    };
+    if verification.is_reproducible() {
+        return Ok(verification);
+    }
+
+    let expected = image_sections(&reference.elf, reference.text_size)?;
+    let actual = image_sections(
+        &fs::read(&artifacts.app_elf)?,
+        fs::metadata(&artifacts.app_text)?.len(),
+    )?;
+    let section_diffs = section_diffs(
+        &expected,
+        &reference.bin,
+        &actual,
+        &fs::read(&artifacts.app_bin)?,
+    );
+    Ok(BuildVerification {
+        section_diffs,
+        ..verification
+    })
+}
+
+/// Manifest and artifact contents of the dist being verified.
+struct ReferenceDist {
+    manifest: Manifest,
+    bin: Vec<u8>,
+    elf: Vec<u8>,
+    text_size: u64,
+}
+
+impl ReferenceDist {
+    fn load(dist: &Path) -> Result<Self> {
+        let (manifest, files) = if dist.is_file() {
+            let bundle = Bundle::read_from_file(dist)?;
+            let manifest = bundle.manifest()?;
+            let files = bundle
+                .entries()
+                .iter()
+                .filter(|entry| entry.name != MANIFEST_SIGNATURE_FILE)
+                .map(|entry| (entry.name.clone(), entry.contents.clone()))
+                .collect();
+            (manifest, files)
+        } else {
+            let manifest = Manifest::read_from_file(&dist.join("manifest.toml"))?;
+            let files = [&manifest.bin, &manifest.elf, &manifest.text]
+                .into_iter()
+                .map(|entry| Ok((entry.path.clone(), fs::read(dist.join(&entry.path))?)))
+                .collect::<Result<Vec<_>>>()?;
+            (manifest, files)
+        };
+
+        let contents = |entry: &ArtifactEntry| -> Result<Vec<u8>> {
+            let (_, contents) = files
+                .iter()
+                .find(|(name, _)| *name == entry.path)
+                .ok_or_else(|| missing_artifact(dist, &entry.path))?;
+            let actual = sha256_hex(contents);
+            if !entry.sha256.eq_ignore_ascii_case(&actual) {
+                return Err(BuildError::InvalidConfig(format!(
+                    "`{}` in {} does not match its manifest digest",
+                    entry.path,
+                    dist.display()
+                )));
+            }
+            Ok(contents.clone())
+        };
+        let bin = contents(&manifest.bin)?;
+        let elf = contents(&manifest.elf)?;
+        let text_size = contents(&manifest.text)?.len() as u64;
+
+        Ok(Self {
+            manifest,
+            bin,
+            elf,
+            text_size,
+        })
+    }
+}
+
+fn missing_artifact(dist: &Path, name: &str) -> BuildError {
+    BuildError::InvalidConfig(format!("{} has no `{name}`", dist.display()))
+}
+
+/// Compares image sections by name, using each build's own layout to slice its `app.bin`.
+fn section_diffs(
+    expected: &[ImageSection],
+    expected_bin: &[u8],
+    actual: &[ImageSection],
+    actual_bin: &[u8],
+) -> Vec<SectionDiff> {
+    let mut names: Vec<&str> = expected
+        .iter()
+        .map(|section| section.name.as_str())
+        .collect();
+    for section in actual {
+        if !names.contains(&section.name.as_str()) {
+            names.push(&section.name);
+        }
+    }
+
+    let find = |sections: &'_ [ImageSection], name: &str| {
+        sections
+            .iter()
+            .find(|section| section.name == name)
+            .cloned()
+    };
+    let mut diffs = Vec::new();
+    for name in names {
+        let expected_section = find(expected, name);
+        let actual_section = find(actual, name);
+        let expected_bytes = expected_section
+            .as_ref()
+            .map_or(&[][..], |section| section_bytes(expected_bin, section));
+        let actual_bytes = actual_section
+            .as_ref()
+            .map_or(&[][..], |section| section_bytes(actual_bin, section));
+
+        let mut differences = expected_bytes
+            .iter()
+            .zip(actual_bytes)
+            .enumerate()
+            .filter(|(_, (expected, actual))| expected != actual)
+            .map(|(offset, _)| offset as u64);
+        let first_difference = differences.next();
+        let differing_bytes = first_difference.map_or(0, |_| 1 + differences.count() as u64);
+
+        let expected_size = expected_section.map(|section| section.size);
+        let actual_size = actual_section.map(|section| section.size);
+        if expected_size != actual_size || differing_bytes > 0 {
+            diffs.push(SectionDiff {
+                name: name.to_string(),
+                expected_size,
+                actual_size,
+                first_difference,
+                differing_bytes,
+            });
+        }
+    }
+    diffs
+}
+
+fn section_bytes<'a>(image: &'a [u8], section: &ImageSection) -> &'a [u8] {
+    let start = (section.address as usize).min(image.len());
+    let end = (section.address + section.size).min(image.len() as u64) as usize;
+    &image[start..end]
+}
+
+/// Provenance recorded in the reference that the rebuild does not match.
+///
+/// Fields missing from the reference, as in manifests written by older tooling, are skipped.
+fn provenance_diffs(expected: &BuildMetadata, actual: &BuildMetadata) -> Vec<ProvenanceDiff> {
+    let list = |values: &[String]| Some(values.join(" ")).filter(|joined| !joined.is_empty());
+    let sdk_versions = |build: &BuildMetadata| {
+        Some(
+            build
+                .sdk_versions
+                .iter()
+                .map(|(name, version)| format!("{name} {version}"))
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+        .filter(|joined| !joined.is_empty())
+    };
+    let known = |commit: &str| Some(commit.to_string()).filter(|commit| commit != "N/A");
+
+    let fields = [
+        (
+            "git_commit",
+            known(&expected.git_commit),
+            known(&actual.git_commit),
+        ),
+        (
+            "rustc_version",
+            expected.rustc_version.clone(),
+            actual.rustc_version.clone(),
+        ),
+        (
+            "rustc_commit_hash",
+            expected.rustc_commit_hash.clone(),
+            actual.rustc_commit_hash.clone(),
+        ),
+        (
+            "resolved_target",
+            expected.resolved_target.clone(),
+            actual.resolved_target.clone(),
+        ),
+        ("features", list(&expected.features), list(&actual.features)),
+        (
+            "cargo_lock_sha256",
+            expected.cargo_lock_sha256.clone(),
+            actual.cargo_lock_sha256.clone(),
+        ),
+        ("sdk_versions", sdk_versions(expected), sdk_versions(actual)),
+    ];
+    fields
+        .into_iter()
+        .filter_map(|(field, expected, actual)| {
+            let expected = expected?;
+            let actual = actual.unwrap_or_else(|| "N/A".to_string());
+            (expected != actual).then_some(ProvenanceDiff {
+                field,
+                expected,
+                actual,
+            })
+        })
+        .collect()
+}
+
+/// Fails before rebuilding when `project_dir` resolves another `rustc` than the one recorded:
+/// a different compiler cannot be expected to reproduce the artifacts, so the comparison would
+/// only report noise.
+fn check_toolchain(recorded: &BuildMetadata, resolved: Option<&RustcVersion>) -> Result<()> {
+    let Some(release) = &recorded.rustc_version else {
+        return Ok(());
+    };
+    let recorded_rustc = match &recorded.rustc_commit_hash {
+        Some(commit_hash) => format!("{release} ({commit_hash})"),
+        None => release.clone(),
+    };
+    let Some(resolved) = resolved else {
+        return Err(BuildError::InvalidConfig(format!(
+            "dist was built with rustc {recorded_rustc}, but `rustc -vV` could not be queried \
+             in the project"
+        )));
+    };
+    let commit_matches =
+        recorded.rustc_commit_hash.is_none() || resolved.commit_hash == recorded.rustc_commit_hash;
+    if resolved.release == *release && commit_matches {
+        return Ok(());
+    }
+    Err(BuildError::InvalidConfig(format!(
+        "dist was built with rustc {recorded_rustc}, but the project resolves rustc {}{}; \
+         select the recorded toolchain with `rust-toolchain.toml` or `RUSTUP_TOOLCHAIN`",
+        resolved.release,
+        resolved
+            .commit_hash
+            .as_ref()
+            .map(|hash| format!(" ({hash})"))
+            .unwrap_or_default()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Profile;
+
+    fn section(name: &str, address: u64, size: u64) -> ImageSection {
+        ImageSection {
+            name: name.to_string(),
+            address,
+            size,
+        }
+    }
+
+    #[test]
+    fn diffs_sections_by_name_and_layout() {
+        let expected = [section(".text", 0, 4), section(".rodata", 4, 4)];
+        let actual = [
+            section(".text", 0, 4),
+            section(".rodata", 4, 6),
+            section(".data", 10, 2),
+        ];
+        let diffs = section_diffs(
+            &expected,
+            &[1, 2, 3, 4, 5, 6, 7, 8],
+            &actual,
+            &[1, 2, 3, 4, 5, 0, 7, 0, 9, 9, 1, 1],
+        );
+
+        assert_eq!(
+            diffs,
+            [
+                SectionDiff {
+                    name: ".rodata".to_string(),
+                    expected_size: Some(4),
+                    actual_size: Some(6),
+                    first_difference: Some(1),
+                    differing_bytes: 2,
+                },
+                SectionDiff {
+                    name: ".data".to_string(),
+                    expected_size: None,
+                    actual_size: Some(2),
+                    first_difference: None,
+                    differing_bytes: 0,
+                },
+            ]
+        );
+        assert!(section_diffs(&expected, &[0; 8], &expected, &[0; 8]).is_empty());
+    }
+
+    #[test]
+    fn requires_the_recorded_toolchain() {
+        let resolved = RustcVersion {
+            release: "1.95.0-nightly".to_string(),
+            commit_hash: Some("0123abcd".to_string()),
+            host: "x86_64-unknown-linux-gnu".to_string(),
+        };
+        let mut recorded = BuildMetadata::new(Profile::Release);
+        check_toolchain(&recorded, None).expect("nothing recorded, nothing to check");
+
+        recorded.rustc_version = Some("1.95.0-nightly".to_string());
+        recorded.rustc_commit_hash = Some("0123abcd".to_string());
+        check_toolchain(&recorded, Some(&resolved)).expect("same toolchain");
+
+        recorded.rustc_commit_hash = Some("4567cdef".to_string());
+        let err = check_toolchain(&recorded, Some(&resolved)).expect_err("other nightly");
+        assert_eq!(
+            err.to_string(),
+            "invalid config: dist was built with rustc 1.95.0-nightly (4567cdef), but the \
+             project resolves rustc 1.95.0-nightly (0123abcd); select the recorded toolchain \
+             with `rust-toolchain.toml` or `RUSTUP_TOOLCHAIN`"
+        );
+        assert!(check_toolchain(&recorded, None).is_err());
+    }
+
+    #[test]
+    fn reports_recorded_provenance_the_rebuild_does_not_match() {
+        let mut expected = BuildMetadata::new(Profile::Release);
+        expected.git_commit = "abc123".to_string();
+        expected.rustc_commit_hash = Some("0123abcd".to_string());
+        expected.features = vec!["extra".to_string()];
+
+        let mut actual = expected.clone();
+        actual.rustc_version = Some("1.95.0-nightly".to_string());
+        assert!(provenance_diffs(&expected, &actual).is_empty());
+
+        actual.rustc_commit_hash = Some("4567cdef".to_string());
+        actual.features.clear();
+        assert_eq!(
+            provenance_diffs(&expected, &actual),
+            [
+                ProvenanceDiff {
+                    field: "rustc_commit_hash",
+                    expected: "0123abcd".to_string(),
+                    actual: "4567cdef".to_string(),
+                },
+                ProvenanceDiff {
+                    field: "features",
+                    expected: "extra".to_string(),
+                    actual: "N/A".to_string(),
+                },
+            ]
+        );
+    }
+}
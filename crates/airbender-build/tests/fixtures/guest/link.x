@@ -0,0 +1,13 @@
+ENTRY(_start)
+MEMORY {
+  ROM : ORIGIN = 0x0, LENGTH = 2M
+  RAM : ORIGIN = 0x200000, LENGTH = 1022M
+}
+SECTIONS {
+  .text : { KEEP(*(.init)) *(.text .text.*) } > ROM
+  .rodata : ALIGN(4) { *(.rodata .rodata.*) } > ROM
+  .data : ALIGN(4) { _sdata = .; *(.sdata .sdata.* .data .data.*); _edata = .; } > RAM AT > ROM
+  _sidata = LOADADDR(.data);
+  .bss (NOLOAD) : ALIGN(4) { _sbss = .; *(.sbss .sbss.* .bss .bss.*); _ebss = .; } > RAM
+  _stack_top = ORIGIN(RAM) + LENGTH(RAM);
+}
@@ -0,0 +1,38 @@
+#![no_std]
+#![no_main]
+
+use core::panic::PanicInfo;
+
+static LOOKUP: [u32; 8] = [0, 1, 1, 2, 3, 5, 8, 13];
+static mut INPUT: [u32; 4] = [10, 3, 7, 5];
+static mut CALLS: u32 = 0;
+
+core::arch::global_asm!(
+    ".section .init, \"ax\"",
+    ".global _start",
+    "_start:",
+    "la sp, _stack_top",
+    "call main",
+    "1: j 1b",
+);
+
+#[no_mangle]
+extern "C" fn main() -> u32 {
+    unsafe { CALLS += 1 };
+    let input = unsafe { core::ptr::addr_of!(INPUT).read_volatile() };
+    let mut a = 0u32;
+    let mut b = 1u32;
+    for _ in 0..input[0] {
+        let next = a.wrapping_add(b);
+        a = b;
+        b = next;
+    }
+    let index = input[1] as usize % LOOKUP.len();
+    a.wrapping_add(LOOKUP[index])
+        .wrapping_add(unsafe { core::ptr::addr_of!(CALLS).read_volatile() })
+}
+
+#[panic_handler]
+fn panic(_: &PanicInfo) -> ! {
+    loop {}
+}
@@ -10,9 +10,18 @@ use core::fmt;
 #[cfg(test)]
 extern crate std;
 
+/// Version of this crate; hosts compare it with the version a guest was built against.
+pub const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// Stable codec version for host/guest communication.
 pub const AIRBENDER_CODEC_V0: u32 = 0;
 
+/// Codec version for proofs, whose layout changed after v0.
+pub const AIRBENDER_CODEC_V1: u32 = 1;
+
+/// Magic bytes that start every payload encoded with a versioned codec (v1 and later).
+const VERSION_HEADER_MAGIC: &[u8; 4] = b"ABCV";
+
 /// A stable, versioned serializer used by Airbender host and guest programs.
 pub trait AirbenderCodec {
     /// Version identifier baked into manifests and tooling.
@@ -50,11 +59,56 @@ impl AirbenderCodec for AirbenderCodecV0 {
     }
 }
 
+/// Codec built on [`AirbenderCodecV0`] that prefixes payloads with a version header,
+/// so decoders can tell layouts apart instead of misreading older payloads.
+pub struct AirbenderCodecV1;
+
+impl AirbenderCodec for AirbenderCodecV1 {
+    const VERSION: u32 = AIRBENDER_CODEC_V1;
+
+    fn encode<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, CodecError> {
+        let mut bytes = version_header(Self::VERSION);
+        bytes.extend(AirbenderCodecV0::encode(value)?);
+        Ok(bytes)
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, CodecError> {
+        let found = encoded_version(bytes);
+        if found != Self::VERSION {
+            return Err(CodecError::UnsupportedVersion {
+                expected: Self::VERSION,
+                found,
+            });
+        }
+        AirbenderCodecV0::decode(&bytes[VERSION_HEADER_LEN..])
+    }
+}
+
+const VERSION_HEADER_LEN: usize = VERSION_HEADER_MAGIC.len() + 4;
+
+fn version_header(version: u32) -> Vec<u8> {
+    let mut header = Vec::with_capacity(VERSION_HEADER_LEN);
+    header.extend_from_slice(VERSION_HEADER_MAGIC);
+    header.extend_from_slice(&version.to_le_bytes());
+    header
+}
+
+/// Codec version a payload was encoded with; payloads without a version header are v0.
+pub fn encoded_version(bytes: &[u8]) -> u32 {
+    match bytes.split_at_checked(VERSION_HEADER_MAGIC.len()) {
+        Some((magic, rest)) if magic == VERSION_HEADER_MAGIC && rest.len() >= 4 => {
+            u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]])
+        }
+        _ => AIRBENDER_CODEC_V0,
+    }
+}
+
 #[derive(Debug)]
 pub enum CodecError {
     Encode(bincode::error::EncodeError),
     Decode(bincode::error::DecodeError),
     TrailingBytes { expected: usize, read: usize },
+    UnsupportedVersion { expected: u32, found: u32 },
 }
 
 impl fmt::Display for CodecError {
@@ -65,6 +119,9 @@ impl fmt::Display for CodecError {
             CodecError::TrailingBytes { expected, read } => {
                 write!(f, "decoded {read} bytes but expected {expected}")
             }
+            CodecError::UnsupportedVersion { expected, found } => {
+                write!(f, "payload uses codec v{found}, expected v{expected}")
+            }
         }
     }
 }
@@ -90,4 +147,27 @@ mod tests {
         let decoded: Sample = AirbenderCodecV0::decode(&encoded).expect("decode");
         assert_eq!(decoded, sample);
     }
+
+    #[test]
+    fn versioned_codec_tags_payloads() {
+        let sample = Sample {
+            value: 7,
+            payload: vec![9u8; 3],
+        };
+        let encoded = AirbenderCodecV1::encode(&sample).expect("encode");
+        assert_eq!(encoded_version(&encoded), AIRBENDER_CODEC_V1);
+        let decoded: Sample = AirbenderCodecV1::decode(&encoded).expect("decode");
+        assert_eq!(decoded, sample);
+
+        let legacy = AirbenderCodecV0::encode(&sample).expect("encode");
+        assert_eq!(encoded_version(&legacy), AIRBENDER_CODEC_V0);
+        let err = AirbenderCodecV1::decode::<Sample>(&legacy).expect_err("v0 payload");
+        assert!(matches!(
+            err,
+            CodecError::UnsupportedVersion {
+                expected: AIRBENDER_CODEC_V1,
+                found: AIRBENDER_CODEC_V0,
+            }
+        ));
+    }
 }
@@ -0,0 +1,78 @@
+//! Public claim of an aggregation proof.
+//!
+//! The aggregation guest verifies one recursion-unified proof per [`AggregatedClaim`] and commits
+//! [`claims_commitment`] of the claims as its output. Hosts recompute the commitment to check the
+//! aggregated claim. The commitment is a SHA-256 digest over:
+//! - the domain tag `airbender-aggregation-v2`,
+//! - the number of claims as a little-endian `u64`,
+//! - for each claim: the recursion chain and output words as little-endian `u32`s.
+//!
+//! Claims only hold what the guest verified. The recursion chain identifies the program; compare
+//! it with the program's unified verification key commitment to tell which program a claim is for.
+
+use sha2::{Digest, Sha256};
+
+const DOMAIN_TAG: &[u8] = b"airbender-aggregation-v2";
+
+/// One verified proof inside an aggregation proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AggregatedClaim {
+    /// Recursion-chain commitment (`x18..x25`) of the aggregated proof.
+    pub recursion_chain: [u32; 8],
+    /// Public output (`x10..x17`) of the aggregated proof.
+    pub output: [u32; 8],
+}
+
+/// Commitment to `claims`, committed by the aggregation guest as its public output.
+pub fn claims_commitment(claims: &[AggregatedClaim]) -> [u32; 8] {
+    let mut hasher = Sha256::new();
+    hasher.update(DOMAIN_TAG);
+    hasher.update((claims.len() as u64).to_le_bytes());
+    for claim in claims {
+        for word in claim.recursion_chain.iter().chain(claim.output.iter()) {
+            hasher.update(word.to_le_bytes());
+        }
+    }
+
+    let digest: [u8; 32] = hasher.finalize().into();
+    let mut commitment = [0u32; 8];
+    for (word, bytes) in commitment.iter_mut().zip(digest.chunks_exact(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+    commitment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claim(seed: u8) -> AggregatedClaim {
+        AggregatedClaim {
+            recursion_chain: [seed as u32; 8],
+            output: [seed as u32 + 1; 8],
+        }
+    }
+
+    #[test]
+    fn commitment_binds_every_claim_and_their_order() {
+        let claims = [claim(1), claim(2)];
+        let commitment = claims_commitment(&claims);
+        assert_eq!(commitment, claims_commitment(&[claim(1), claim(2)]));
+        assert_ne!(commitment, claims_commitment(&[claim(2), claim(1)]));
+        assert_ne!(commitment, claims_commitment(&[claim(1)]));
+
+        let mut tampered = claims;
+        tampered[1].output[7] ^= 1;
+        assert_ne!(commitment, claims_commitment(&tampered));
+        let mut tampered = claims;
+        tampered[0].recursion_chain[0] ^= 1;
+        assert_ne!(commitment, claims_commitment(&tampered));
+    }
+
+    #[test]
+    fn empty_claims_have_a_distinct_commitment() {
+        assert_ne!(claims_commitment(&[]), [0u32; 8]);
+        assert_ne!(claims_commitment(&[]), claims_commitment(&[claim(0)]));
+    }
+}
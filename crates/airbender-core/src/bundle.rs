@@ -0,0 +1,325 @@
+//! Single-file dist bundles.
+//!
+//! A bundle packs a dist directory (`manifest.toml`, its optional signature and the artifacts the
+//! manifest lists) into one file. Layout, with all integers little-endian:
+//! - the magic bytes [`BUNDLE_MAGIC`],
+//! - the entry count as a `u32`,
+//! - per entry: the file name length as a `u16`, the UTF-8 file name, the contents length as a
+//!   `u64` and the contents.
+//!
+//! The first entry is always `manifest.toml`. File names are plain names without directories, so
+//! a bundle unpacks into a single dist directory.
+
+use crate::manifest::{Manifest, ManifestError};
+use crate::signature::MANIFEST_SIGNATURE_FILE;
+use std::fs;
+use std::path::Path;
+
+/// Magic bytes starting every bundle file.
+pub const BUNDLE_MAGIC: &[u8; 8] = b"AIRBNDL\x01";
+
+/// File extension of bundles written by `cargo airbender build --bundle`.
+pub const BUNDLE_EXTENSION: &str = "airbundle";
+
+/// Name of the manifest entry, which always comes first.
+pub const MANIFEST_FILE: &str = "manifest.toml";
+
+/// Errors returned by bundle read, write and unpack operations.
+#[derive(Debug, thiserror::Error)]
+pub enum BundleError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid bundle: {0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Manifest(#[from] ManifestError),
+}
+
+/// One file stored in a bundle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BundleEntry {
+    /// File name inside the dist directory.
+    pub name: String,
+    pub contents: Vec<u8>,
+}
+
+/// In-memory single-file dist bundle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bundle {
+    entries: Vec<BundleEntry>,
+}
+
+impl Bundle {
+    /// Pack a dist directory: the manifest, its signature if present, and every listed artifact.
+    pub fn from_dist_dir(dist_dir: &Path) -> Result<Self, BundleError> {
+        let manifest_path = dist_dir.join(MANIFEST_FILE);
+        let manifest = Manifest::read_from_file(&manifest_path)?;
+
+        let mut names = vec![MANIFEST_FILE.to_string()];
+        if dist_dir.join(MANIFEST_SIGNATURE_FILE).exists() {
+            names.push(MANIFEST_SIGNATURE_FILE.to_string());
+        }
+        for artifact in manifest.artifact_entries() {
+            // Checked before reading, so a manifest cannot pull in files outside `dist_dir`.
+            validate_entry_name(&artifact.path)?;
+            names.push(artifact.path.clone());
+        }
+
+        let entries = names
+            .into_iter()
+            .map(|name| {
+                let contents = fs::read(dist_dir.join(&name))?;
+                Ok(BundleEntry { name, contents })
+            })
+            .collect::<Result<Vec<_>, BundleError>>()?;
+        Self::from_entries(entries)
+    }
+
+    /// Build a bundle from entries; the first one must be `manifest.toml`.
+    pub fn from_entries(entries: Vec<BundleEntry>) -> Result<Self, BundleError> {
+        match entries.first() {
+            Some(entry) if entry.name == MANIFEST_FILE => {}
+            _ => {
+                return Err(BundleError::Invalid(format!(
+                    "first entry must be `{MANIFEST_FILE}`"
+                )))
+            }
+        }
+        for (index, entry) in entries.iter().enumerate() {
+            validate_entry_name(&entry.name)?;
+            if entries[..index]
+                .iter()
+                .any(|other| other.name == entry.name)
+            {
+                return Err(BundleError::Invalid(format!(
+                    "duplicate entry `{}`",
+                    entry.name
+                )));
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn entries(&self) -> &[BundleEntry] {
+        &self.entries
+    }
+
+    /// Contents of the entry named `name`.
+    pub fn get(&self, name: &str) -> Option<&[u8]> {
+        self.entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| entry.contents.as_slice())
+    }
+
+    /// Parse the bundled manifest.
+    pub fn manifest(&self) -> Result<Manifest, BundleError> {
+        let content = std::str::from_utf8(&self.entries[0].contents)
+            .map_err(|err| BundleError::Invalid(format!("manifest is not UTF-8: {err}")))?;
+        Ok(Manifest::parse(content)?)
+    }
+
+    /// Whether `bytes` start with the bundle magic.
+    pub fn is_bundle(bytes: &[u8]) -> bool {
+        bytes.starts_with(BUNDLE_MAGIC)
+    }
+
+    /// Encode the bundle in the single-file layout.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = BUNDLE_MAGIC.to_vec();
+        bytes.extend((self.entries.len() as u32).to_le_bytes());
+        for entry in &self.entries {
+            bytes.extend((entry.name.len() as u16).to_le_bytes());
+            bytes.extend(entry.name.as_bytes());
+            bytes.extend((entry.contents.len() as u64).to_le_bytes());
+            bytes.extend(&entry.contents);
+        }
+        bytes
+    }
+
+    /// Decode a bundle, rejecting truncated input and trailing bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BundleError> {
+        let mut reader = Reader { bytes };
+        if reader.take(BUNDLE_MAGIC.len())? != BUNDLE_MAGIC {
+            return Err(BundleError::Invalid("missing bundle magic".to_string()));
+        }
+
+        let count = u32::from_le_bytes(reader.array()?);
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let name_len = u16::from_le_bytes(reader.array()?) as usize;
+            let name = std::str::from_utf8(reader.take(name_len)?)
+                .map_err(|err| BundleError::Invalid(format!("entry name is not UTF-8: {err}")))?
+                .to_string();
+            let contents_len = usize::try_from(u64::from_le_bytes(reader.array()?))
+                .map_err(|_| BundleError::Invalid(format!("entry `{name}` is too large")))?;
+            let contents = reader.take(contents_len)?.to_vec();
+            entries.push(BundleEntry { name, contents });
+        }
+        if !reader.bytes.is_empty() {
+            return Err(BundleError::Invalid(format!(
+                "{} trailing bytes after the last entry",
+                reader.bytes.len()
+            )));
+        }
+        Self::from_entries(entries)
+    }
+
+    /// Read a bundle file.
+    pub fn read_from_file(path: &Path) -> Result<Self, BundleError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// Write this bundle to a file.
+    pub fn write_to_file(&self, path: &Path) -> Result<(), BundleError> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Write every entry into `dist_dir`, creating it if needed.
+    ///
+    /// Each file is written to a temporary name first and renamed into place, so concurrent
+    /// readers never observe a partially written artifact.
+    pub fn unpack_to(&self, dist_dir: &Path) -> Result<(), BundleError> {
+        fs::create_dir_all(dist_dir)?;
+        for entry in &self.entries {
+            let path = dist_dir.join(&entry.name);
+            let temp_path = dist_dir.join(format!(".{}.{}.tmp", entry.name, std::process::id()));
+            fs::write(&temp_path, &entry.contents)?;
+            fs::rename(&temp_path, &path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Entry names are plain file names, so unpacking cannot escape the dist directory.
+fn validate_entry_name(name: &str) -> Result<(), BundleError> {
+    let is_plain = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\'])
+        && name.len() <= u16::MAX as usize;
+    if is_plain {
+        Ok(())
+    } else {
+        Err(BundleError::Invalid(format!(
+            "entry name `{name}` must be a plain file name"
+        )))
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], BundleError> {
+        if self.bytes.len() < len {
+            return Err(BundleError::Invalid("unexpected end of bundle".to_string()));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], BundleError> {
+        Ok(self
+            .take(N)?
+            .try_into()
+            .expect("slice has the requested length"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, contents: &[u8]) -> BundleEntry {
+        BundleEntry {
+            name: name.to_string(),
+            contents: contents.to_vec(),
+        }
+    }
+
+    fn sample_bundle() -> Bundle {
+        Bundle::from_entries(vec![
+            entry(MANIFEST_FILE, b"package = \"demo\"\n"),
+            entry("app.bin", &[1, 2, 3]),
+            entry("app.text", &[]),
+        ])
+        .expect("valid bundle")
+    }
+
+    #[test]
+    fn bundle_roundtrips_through_bytes() {
+        let bundle = sample_bundle();
+        let bytes = bundle.to_bytes();
+        assert!(Bundle::is_bundle(&bytes));
+        assert!(!Bundle::is_bundle(b"package = \"demo\""));
+
+        let decoded = Bundle::from_bytes(&bytes).expect("decode bundle");
+        assert_eq!(decoded, bundle);
+        assert_eq!(decoded.get("app.bin"), Some(&[1u8, 2, 3][..]));
+        assert_eq!(decoded.get("app.elf"), None);
+    }
+
+    #[test]
+    fn rejects_malformed_bundles() {
+        let bytes = sample_bundle().to_bytes();
+        assert!(Bundle::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Bundle::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+        assert!(Bundle::from_bytes(b"not a bundle").is_err());
+
+        let err = Bundle::from_entries(vec![entry("app.bin", &[])])
+            .expect_err("manifest must come first");
+        assert!(err.to_string().contains("first entry"));
+        for name in ["../escape", "dir/app.bin", "..", ""] {
+            assert!(
+                Bundle::from_entries(vec![entry(MANIFEST_FILE, &[]), entry(name, &[])]).is_err(),
+                "entry name `{name}` must be rejected"
+            );
+        }
+        assert!(Bundle::from_entries(vec![
+            entry(MANIFEST_FILE, &[]),
+            entry("app.bin", &[]),
+            entry("app.bin", &[]),
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn rejects_artifact_paths_outside_dist_dir_before_reading() {
+        let root = tempfile::tempdir().expect("create temp dir");
+        let dist_dir = root.path().join("dist");
+        fs::create_dir(&dist_dir).expect("create dist dir");
+        fs::write(root.path().join("secret"), b"secret").expect("write outside file");
+        let artifact = |name: &str| format!("[{name}]\npath = \"../secret\"\nsha256 = \"\"\n");
+        let manifest = format!(
+            "package = \"demo\"\nmanifest = \"v2\"\ncodec = \"v0\"\n{}{}{}\
+             [build]\nprofile = \"release\"\ngit_branch = \"N/A\"\ngit_commit = \"N/A\"\n",
+            artifact("bin"),
+            artifact("elf"),
+            artifact("text"),
+        );
+        fs::write(dist_dir.join(MANIFEST_FILE), manifest).expect("write manifest");
+
+        let err = Bundle::from_dist_dir(&dist_dir).expect_err("escaping path must be rejected");
+        assert!(
+            err.to_string().contains("must be a plain file name"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn unpacks_entries_into_directory() {
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let dir = temp_dir.path();
+        sample_bundle().unpack_to(dir).expect("unpack bundle");
+
+        assert_eq!(
+            fs::read(dir.join("app.bin")).expect("read app.bin"),
+            [1, 2, 3]
+        );
+        assert_eq!(fs::read_dir(dir).expect("list dir").count(), 3);
+    }
+}
@@ -0,0 +1,90 @@
+//! Lowercase hex encoding shared by image ids, keys, signatures and artifact digests.
+
+use alloc::string::String;
+use core::fmt;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Errors returned when decoding hex text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexError {
+    InvalidLength { expected: usize, len: usize },
+    InvalidHexDigit { index: usize },
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HexError::InvalidLength { expected, len } => {
+                write!(f, "expected {expected} hex characters, got {len}")
+            }
+            HexError::InvalidHexDigit { index } => {
+                write!(f, "invalid hex digit at position {index}")
+            }
+        }
+    }
+}
+
+/// Encode `bytes` as lowercase hex.
+pub fn encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        encoded.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+        encoded.push(HEX_DIGITS[(byte & 0x0f) as usize] as char);
+    }
+    encoded
+}
+
+/// Decode exactly `N` bytes from hex text (case-insensitive).
+pub fn decode<const N: usize>(value: &str) -> Result<[u8; N], HexError> {
+    let bytes = value.as_bytes();
+    if bytes.len() != N * 2 {
+        return Err(HexError::InvalidLength {
+            expected: N * 2,
+            len: bytes.len(),
+        });
+    }
+
+    let mut decoded = [0u8; N];
+    for (index, pair) in bytes.chunks_exact(2).enumerate() {
+        let high = hex_value(pair[0]).ok_or(HexError::InvalidHexDigit { index: index * 2 })?;
+        let low = hex_value(pair[1]).ok_or(HexError::InvalidHexDigit {
+            index: index * 2 + 1,
+        })?;
+        decoded[index] = (high << 4) | low;
+    }
+    Ok(decoded)
+}
+
+fn hex_value(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        b'A'..=b'F' => Some(digit - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrips_and_rejects_malformed_input() {
+        let bytes = [0x00, 0x7f, 0xa5, 0xff];
+        assert_eq!(encode(&bytes), "007fa5ff");
+        assert_eq!(decode::<4>("007fa5ff"), Ok(bytes));
+        assert_eq!(decode::<4>("007FA5FF"), Ok(bytes));
+        assert_eq!(
+            decode::<4>("007f"),
+            Err(HexError::InvalidLength {
+                expected: 8,
+                len: 4
+            })
+        );
+        assert_eq!(
+            decode::<4>("007fa5fg"),
+            Err(HexError::InvalidHexDigit { index: 7 })
+        );
+    }
+}
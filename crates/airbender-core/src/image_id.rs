@@ -0,0 +1,192 @@
+//! Canonical identity of a guest program image.
+//!
+//! An [`ImageId`] is a SHA-256 digest over:
+//! - the domain tag `airbender-image-id-v1`,
+//! - the machine configuration tag,
+//! - the `app.bin` image, prefixed by its byte length as a little-endian `u64`,
+//! - the `app.text` image, prefixed by its byte length as a little-endian `u64`.
+//!
+//! The same value is recorded in the manifest, embedded in proofs and verification keys,
+//! and compared by every verifier.
+
+use crate::hex::{self, HexError};
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+use sha2::{Digest, Sha256};
+
+const DOMAIN_TAG: &[u8] = b"airbender-image-id-v1";
+
+/// Machine configuration that executes the program image.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MachineConfig {
+    /// RV32IM with unsigned multiplication/division, used for application programs.
+    #[default]
+    StandardWithUnsignedMulDiv,
+}
+
+impl MachineConfig {
+    /// Stable tag hashed into the image id.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MachineConfig::StandardWithUnsignedMulDiv => "rv32im-unsigned-muldiv",
+        }
+    }
+}
+
+/// Errors returned when parsing an [`ImageId`] from text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageIdError {
+    InvalidLength { len: usize },
+    InvalidHexDigit { index: usize },
+}
+
+impl fmt::Display for ImageIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageIdError::InvalidLength { len } => {
+                write!(f, "image id must be 64 hex characters, got {len}")
+            }
+            ImageIdError::InvalidHexDigit { index } => {
+                write!(f, "invalid hex digit at position {index} in image id")
+            }
+        }
+    }
+}
+
+/// Canonical 32-byte identifier of a program image and its machine configuration.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ImageId([u8; 32]);
+
+impl ImageId {
+    /// Compute the image id of `app.bin` and `app.text` contents for `machine`.
+    pub fn compute(bin: &[u8], text: &[u8], machine: MachineConfig) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(DOMAIN_TAG);
+        hash_prefixed(&mut hasher, machine.as_str().as_bytes());
+        hash_prefixed(&mut hasher, bin);
+        hash_prefixed(&mut hasher, text);
+        Self(hasher.finalize().into())
+    }
+
+    pub const fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Lowercase hex encoding, as recorded in the manifest.
+    pub fn to_hex(&self) -> String {
+        hex::encode(&self.0)
+    }
+
+    /// Parse a 64-character hex string (case-insensitive).
+    pub fn from_hex(value: &str) -> Result<Self, ImageIdError> {
+        hex::decode(value).map(Self).map_err(|err| match err {
+            HexError::InvalidLength { len, .. } => ImageIdError::InvalidLength { len },
+            HexError::InvalidHexDigit { index } => ImageIdError::InvalidHexDigit { index },
+        })
+    }
+}
+
+fn hash_prefixed(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_le_bytes());
+    hasher.update(bytes);
+}
+
+impl fmt::Display for ImageId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl fmt::Debug for ImageId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ImageId({self})")
+    }
+}
+
+impl FromStr for ImageId {
+    type Err = ImageIdError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(value)
+    }
+}
+
+/// Hex string in human-readable formats (TOML, JSON), raw bytes otherwise.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ImageId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_hex())
+        } else {
+            self.0.serialize(serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ImageId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let value = String::deserialize(deserializer)?;
+            Self::from_hex(&value).map_err(serde::de::Error::custom)
+        } else {
+            <[u8; 32]>::deserialize(deserializer).map(Self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_id_binds_bin_and_text() {
+        let id = ImageId::compute(b"bin", b"text", MachineConfig::default());
+        assert_eq!(
+            id,
+            ImageId::compute(b"bin", b"text", MachineConfig::default())
+        );
+        assert_ne!(
+            id,
+            ImageId::compute(b"bin", b"other", MachineConfig::default())
+        );
+        assert_ne!(
+            id,
+            ImageId::compute(b"other", b"text", MachineConfig::default())
+        );
+        // Length prefixes keep the bin/text boundary unambiguous.
+        assert_ne!(
+            id,
+            ImageId::compute(b"bint", b"ext", MachineConfig::default())
+        );
+    }
+
+    #[test]
+    fn hex_roundtrip() {
+        let id = ImageId::compute(b"bin", b"text", MachineConfig::default());
+        let hex = id.to_hex();
+        assert_eq!(hex.len(), 64);
+        assert_eq!(hex, alloc::format!("{id}"));
+        assert_eq!(ImageId::from_hex(&hex), Ok(id));
+        assert_eq!(hex.to_uppercase().parse::<ImageId>(), Ok(id));
+    }
+
+    #[test]
+    fn rejects_malformed_hex() {
+        assert_eq!(
+            ImageId::from_hex("abcd"),
+            Err(ImageIdError::InvalidLength { len: 4 })
+        );
+        let mut hex = ImageId::from_bytes([0x11; 32]).to_hex();
+        hex.replace_range(5..6, "z");
+        assert_eq!(
+            ImageId::from_hex(&hex),
+            Err(ImageIdError::InvalidHexDigit { index: 5 })
+        );
+    }
+}
@@ -4,13 +4,25 @@
 
 extern crate alloc;
 
+/// Version of this crate, which guests and hosts share through the wire and image-id formats.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub mod aggregation;
 pub mod guest;
+pub mod hex;
+pub mod image_id;
 pub mod wire;
 
+#[cfg(feature = "host")]
+pub mod bundle;
 #[cfg(feature = "host")]
 pub mod manifest;
+#[cfg(feature = "host")]
+pub mod signature;
 
 #[cfg(feature = "host")]
 pub mod host {
+    pub use crate::bundle;
     pub use crate::manifest;
+    pub use crate::signature;
 }
@@ -1,12 +1,35 @@
 //! Manifest schema shared between build and host tooling.
 
+use crate::image_id::ImageId;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
 pub const MANIFEST_VERSION_V1: &str = "v1";
+pub const MANIFEST_VERSION_V2: &str = "v2";
+/// Schema version written by current tooling.
+pub const MANIFEST_VERSION_LATEST: &str = MANIFEST_VERSION_V2;
 pub const CODEC_VERSION_V0: &str = "v0";
 
+/// Airbender crates a guest links, whose locked versions [`BuildMetadata::sdk_versions`] records.
+pub const SDK_CRATES: &[&str] = &[
+    "airbender-sdk",
+    "airbender-guest",
+    "airbender-rt",
+    "airbender-macros",
+    "airbender-core",
+    "airbender-codec",
+    "airbender-crypto",
+];
+
+/// Top-level fields of the `v1` schema; every other field was introduced in `v2`.
+const V1_FIELDS: &[&str] = &[
+    "package", "bin_name", "manifest", "codec", "target", "bin", "elf", "text", "build",
+];
+/// `[build]` fields of the `v1` schema.
+const V1_BUILD_FIELDS: &[&str] = &["profile", "git_branch", "git_commit", "is_dirty"];
+
 /// Build profile recorded in the manifest for reproducibility.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -24,6 +47,57 @@ impl Profile {
     }
 }
 
+/// Cargo `opt-level` override for the guest build profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OptLevel {
+    #[serde(rename = "0")]
+    O0,
+    #[serde(rename = "1")]
+    O1,
+    #[serde(rename = "2")]
+    O2,
+    #[serde(rename = "3")]
+    O3,
+    #[serde(rename = "s")]
+    Size,
+    #[serde(rename = "z")]
+    MinSize,
+}
+
+impl OptLevel {
+    /// Value as written in a Cargo profile.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OptLevel::O0 => "0",
+            OptLevel::O1 => "1",
+            OptLevel::O2 => "2",
+            OptLevel::O3 => "3",
+            OptLevel::Size => "s",
+            OptLevel::MinSize => "z",
+        }
+    }
+}
+
+/// Cargo `lto` override for the guest build profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lto {
+    Off,
+    Thin,
+    Fat,
+}
+
+impl Lto {
+    /// Value as written in a Cargo profile.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Lto::Off => "off",
+            Lto::Thin => "thin",
+            Lto::Fat => "fat",
+        }
+    }
+}
+
 /// Serialized manifest describing the build artifacts for a guest program.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Manifest {
@@ -38,14 +112,26 @@ pub struct Manifest {
     pub codec: String,
     /// Optional target triple used for the build.
     pub target: Option<String>,
+    /// Canonical image id of `bin` + `text`; absent in manifests written by older tooling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_id: Option<ImageId>,
     /// Binary image consumed by runtime and proving flows.
     pub bin: ArtifactEntry,
     /// ELF image used for symbol/debug workflows.
     pub elf: ArtifactEntry,
     /// Text-section image used by transpiler execution.
     pub text: ArtifactEntry,
+    /// Additional artifacts by name, such as debug symbols or fixtures; v2 only.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub artifacts: BTreeMap<String, ArtifactEntry>,
+    /// Host-facing interface of the program; v2 only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interface: Option<InterfaceDescription>,
     /// Build provenance metadata captured at packaging time.
     pub build: BuildMetadata,
+    /// Program size summary; absent in manifests written by older tooling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<SizeSummary>,
 }
 
 /// One artifact entry recorded in the manifest.
@@ -57,6 +143,25 @@ pub struct ArtifactEntry {
     pub sha256: String,
 }
 
+/// Input and output types the guest program exchanges with the host.
+///
+/// Declared by the guest in `[package.metadata.airbender]` of its `Cargo.toml`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceDescription {
+    /// Rust type the guest reads as input, e.g. `u32` or `my_guest::Request`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    /// Rust type the guest commits as output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+impl InterfaceDescription {
+    pub fn is_empty(&self) -> bool {
+        self.input.is_none() && self.output.is_none()
+    }
+}
+
 /// Build metadata captured while creating dist artifacts.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BuildMetadata {
@@ -69,6 +174,161 @@ pub struct BuildMetadata {
     /// Indicates unstaged changes at build time.
     #[serde(default, skip_serializing_if = "is_false")]
     pub is_dirty: bool,
+    /// `rustc` release that compiled the guest, e.g. `1.95.0-nightly`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rustc_version: Option<String>,
+    /// Commit hash of that `rustc`, identifying the exact toolchain build.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rustc_commit_hash: Option<String>,
+    /// Target triple the guest was compiled for, after applying Cargo config defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_target: Option<String>,
+    /// Extra arguments forwarded to `cargo build`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cargo_args: Vec<String>,
+    /// Default features of the guest package were disabled.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub no_default_features: bool,
+    /// Flags appended to the guest's configured `rustflags`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rustflags: Vec<String>,
+    /// `opt-level` override applied to the build profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opt_level: Option<OptLevel>,
+    /// `lto` override applied to the build profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lto: Option<Lto>,
+    /// `codegen-units` override applied to the build profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codegen_units: Option<u32>,
+    /// Cargo features enabled for the guest binary.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+    /// SHA-256 digest of the guest workspace `Cargo.lock`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cargo_lock_sha256: Option<String>,
+    /// Versions of the Airbender SDK crates linked into the guest, by crate name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sdk_versions: BTreeMap<String, String>,
+    /// SHA-256 digest of the executable cargo produced, before artifact extraction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executable_sha256: Option<String>,
+    /// Build time in seconds since the Unix epoch, or `SOURCE_DATE_EPOCH` when it was set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub built_at: Option<u64>,
+}
+
+impl BuildMetadata {
+    /// Metadata for `profile` with no provenance recorded; git fields are `N/A`.
+    pub fn new(profile: Profile) -> Self {
+        Self {
+            profile,
+            git_branch: "N/A".to_string(),
+            git_commit: "N/A".to_string(),
+            is_dirty: false,
+            rustc_version: None,
+            rustc_commit_hash: None,
+            resolved_target: None,
+            cargo_args: Vec::new(),
+            no_default_features: false,
+            rustflags: Vec::new(),
+            opt_level: None,
+            lto: None,
+            codegen_units: None,
+            features: Vec::new(),
+            cargo_lock_sha256: None,
+            sdk_versions: BTreeMap::new(),
+            executable_sha256: None,
+            built_at: None,
+        }
+    }
+
+    /// One-line description of where and how the artifacts were built, for diagnostics.
+    pub fn provenance(&self) -> String {
+        let mut provenance = format!(
+            "{} build of commit {}",
+            self.profile.as_str(),
+            self.git_commit
+        );
+        if self.is_dirty {
+            provenance.push_str(" (dirty)");
+        }
+        if let Some(rustc_version) = &self.rustc_version {
+            provenance.push_str(&format!(" with rustc {rustc_version}"));
+            if let Some(commit_hash) = &self.rustc_commit_hash {
+                provenance.push_str(&format!(" ({commit_hash})"));
+            }
+        }
+        if let Some(target) = &self.resolved_target {
+            provenance.push_str(&format!(" for {target}"));
+        }
+        if let Some(built_at) = self.built_at {
+            provenance.push_str(&format!(" at unix time {built_at}"));
+        }
+        provenance
+    }
+}
+
+/// Size of the program image against the machine memory map, recorded at packaging time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeSummary {
+    /// Size of `app.bin`, the image loaded into ROM.
+    pub rom_bytes: u64,
+    /// ROM capacity the image must fit in.
+    pub rom_capacity: u64,
+    /// Size of the `.text` section.
+    pub text_bytes: u64,
+    /// RAM occupied by writable sections (`.data`, `.bss`, ...).
+    pub static_ram_bytes: u64,
+    /// RAM left above static data for the heap and stack.
+    pub heap_bytes: u64,
+}
+
+impl SizeSummary {
+    /// Share of ROM used by the image, in percent.
+    pub fn rom_percent(&self) -> f64 {
+        if self.rom_capacity == 0 {
+            return 0.0;
+        }
+        self.rom_bytes as f64 * 100.0 / self.rom_capacity as f64
+    }
+}
+
+/// Name of the index written at the root of a workspace dist.
+pub const DIST_INDEX_FILE: &str = "index.toml";
+
+/// Programs in a dist root written by a workspace build.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DistIndex {
+    /// One entry per packaged guest binary.
+    #[serde(default, rename = "guest")]
+    pub guests: Vec<DistIndexEntry>,
+}
+
+/// One program listed in a [`DistIndex`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DistIndexEntry {
+    /// App directory relative to the dist root, holding the program's `manifest.toml`.
+    pub app: String,
+    /// Cargo package the program was built from.
+    pub package: String,
+    /// Binary target the program was built from.
+    pub bin_name: String,
+    /// Image id recorded in the program's manifest.
+    pub image_id: ImageId,
+}
+
+impl DistIndex {
+    /// Read an index from a TOML file.
+    pub fn read_from_file(path: &Path) -> Result<Self, ManifestError> {
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Write this index to a TOML file.
+    pub fn write_to_file(&self, path: &Path) -> Result<(), ManifestError> {
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
 }
 
 /// Errors returned by manifest read, write, and parse operations.
@@ -82,6 +342,8 @@ pub enum ManifestError {
     Serialize(#[from] toml::ser::Error),
     #[error("unsupported manifest version `{0}`")]
     UnsupportedManifestVersion(String),
+    #[error("field `{field}` is not part of manifest `{version}`")]
+    UnsupportedField { version: String, field: String },
 }
 
 impl Manifest {
@@ -99,12 +361,42 @@ impl Manifest {
     }
 
     /// Parse and validate a manifest from TOML text.
+    ///
+    /// Both `v1` and `v2` manifests are accepted; the version is kept as written, see
+    /// [`Manifest::upgrade`]. A `v1` manifest must not use fields introduced in `v2`.
     pub fn parse(content: &str) -> Result<Self, ManifestError> {
         let manifest: Self = toml::from_str(content)?;
-        if manifest.manifest != MANIFEST_VERSION_V1 {
-            return Err(ManifestError::UnsupportedManifestVersion(manifest.manifest));
+        match manifest.manifest.as_str() {
+            MANIFEST_VERSION_V1 => {
+                check_v1_fields(&toml::from_str(content)?)?;
+                Ok(manifest)
+            }
+            MANIFEST_VERSION_V2 => Ok(manifest),
+            _ => Err(ManifestError::UnsupportedManifestVersion(manifest.manifest)),
         }
-        Ok(manifest)
+    }
+
+    /// Whether this manifest uses the schema written by current tooling.
+    pub fn is_latest(&self) -> bool {
+        self.manifest == MANIFEST_VERSION_LATEST
+    }
+
+    /// Convert this manifest to the latest schema version.
+    ///
+    /// `v2` is a superset of `v1`: every `v1` field keeps its meaning, and the fields `v2` adds
+    /// (image id, artifact list, interface, size and build provenance beyond the git state) are
+    /// optional. Upgrading a `v1` manifest therefore only changes its version; the new fields
+    /// remain unset.
+    pub fn upgrade(mut self) -> Self {
+        self.manifest = MANIFEST_VERSION_LATEST.to_string();
+        self
+    }
+
+    /// Every artifact the manifest lists: `bin`, `elf`, `text`, then the additional ones.
+    pub fn artifact_entries(&self) -> impl Iterator<Item = &ArtifactEntry> {
+        [&self.bin, &self.elf, &self.text]
+            .into_iter()
+            .chain(self.artifacts.values())
     }
 
     /// Serialize this manifest to TOML text.
@@ -113,6 +405,29 @@ impl Manifest {
     }
 }
 
+fn check_v1_fields(table: &toml::Table) -> Result<(), ManifestError> {
+    let build_fields = table
+        .get("build")
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flat_map(|build| build.keys())
+        .filter(|field| !V1_BUILD_FIELDS.contains(&field.as_str()))
+        .map(|field| format!("build.{field}"));
+    let unsupported = table
+        .keys()
+        .filter(|field| !V1_FIELDS.contains(&field.as_str()))
+        .cloned()
+        .chain(build_fields)
+        .next();
+    match unsupported {
+        Some(field) => Err(ManifestError::UnsupportedField {
+            version: MANIFEST_VERSION_V1.to_string(),
+            field,
+        }),
+        None => Ok(()),
+    }
+}
+
 fn is_false(value: &bool) -> bool {
     !*value
 }
@@ -121,14 +436,14 @@ fn is_false(value: &bool) -> bool {
 mod tests {
     use super::*;
 
-    #[test]
-    fn manifest_roundtrip() {
-        let manifest = Manifest {
+    fn sample_manifest(version: &str) -> Manifest {
+        Manifest {
             package: "demo".to_string(),
             bin_name: None,
-            manifest: MANIFEST_VERSION_V1.to_string(),
+            manifest: version.to_string(),
             codec: CODEC_VERSION_V0.to_string(),
             target: None,
+            image_id: None,
             bin: ArtifactEntry {
                 path: "app.bin".to_string(),
                 sha256: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
@@ -144,13 +459,22 @@ mod tests {
                 sha256: "0476bd0bb997b387b72f721b3f0f38f112e43e32f151e1d1f2ec20bc7c5ad5a6"
                     .to_string(),
             },
+            artifacts: BTreeMap::new(),
+            interface: None,
             build: BuildMetadata {
                 profile: Profile::Release,
                 git_branch: "main".to_string(),
                 git_commit: "abc123".to_string(),
                 is_dirty: false,
+                ..BuildMetadata::new(Profile::Release)
             },
-        };
+            size: None,
+        }
+    }
+
+    #[test]
+    fn manifest_roundtrip() {
+        let manifest = sample_manifest(MANIFEST_VERSION_V1);
         let toml = manifest.to_toml().expect("serialize");
         let first_line = toml
             .lines()
@@ -167,71 +491,57 @@ mod tests {
         assert_eq!(parsed, manifest);
     }
 
+    #[test]
+    fn manifest_roundtrip_v2() {
+        let mut manifest = sample_manifest(MANIFEST_VERSION_V2);
+        let toml = manifest.to_toml().expect("serialize");
+        assert!(toml.contains("manifest = \"v2\""));
+        assert!(!toml.contains("image_id"));
+        assert!(!toml.contains("[size]"));
+        let parsed = Manifest::parse(&toml).expect("parse");
+        assert_eq!(parsed, manifest);
+
+        manifest.image_id = Some(ImageId::from_bytes([0xab; 32]));
+        manifest.size = Some(SizeSummary {
+            rom_bytes: 1 << 19,
+            rom_capacity: 1 << 21,
+            text_bytes: 1 << 18,
+            static_ram_bytes: 4096,
+            heap_bytes: 1 << 29,
+        });
+        let toml = manifest.to_toml().expect("serialize");
+        assert!(toml.contains(&format!("image_id = \"{}\"", "ab".repeat(32))));
+        assert!(toml.contains("[size]"));
+        assert!(toml.contains("rom_bytes = 524288"));
+        let parsed = Manifest::parse(&toml).expect("parse");
+        assert_eq!(parsed, manifest);
+        assert_eq!(parsed.size.expect("size summary").rom_percent(), 25.0);
+    }
+
     #[test]
     fn includes_dirty_flag_when_true() {
-        let manifest = Manifest {
-            package: "demo".to_string(),
-            bin_name: None,
-            manifest: MANIFEST_VERSION_V1.to_string(),
-            codec: CODEC_VERSION_V0.to_string(),
-            target: None,
-            bin: ArtifactEntry {
-                path: "app.bin".to_string(),
-                sha256: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
-                    .to_string(),
-            },
-            elf: ArtifactEntry {
-                path: "app.elf".to_string(),
-                sha256: "b8f1d1d6b064577aa66013024e69c0dcde721573ae58da439b84e1c862437288"
-                    .to_string(),
-            },
-            text: ArtifactEntry {
-                path: "app.text".to_string(),
-                sha256: "0476bd0bb997b387b72f721b3f0f38f112e43e32f151e1d1f2ec20bc7c5ad5a6"
-                    .to_string(),
-            },
-            build: BuildMetadata {
-                profile: Profile::Release,
-                git_branch: "main".to_string(),
-                git_commit: "abc123".to_string(),
-                is_dirty: true,
-            },
-        };
+        let mut manifest = sample_manifest(MANIFEST_VERSION_V1);
+        manifest.build.is_dirty = true;
 
         let toml = manifest.to_toml().expect("serialize");
         assert!(toml.contains("is_dirty = true"));
     }
 
+    #[test]
+    fn includes_dirty_flag_when_true_v2() {
+        let mut manifest = sample_manifest(MANIFEST_VERSION_V2);
+        manifest.build.is_dirty = true;
+
+        let toml = manifest.to_toml().expect("serialize");
+        assert!(toml.contains("is_dirty = true"));
+        let parsed = Manifest::parse(&toml).expect("parse");
+        assert!(parsed.build.is_dirty);
+    }
+
     #[test]
     fn includes_bin_name_when_present() {
-        let manifest = Manifest {
-            package: "demo".to_string(),
-            bin_name: Some("worker".to_string()),
-            manifest: MANIFEST_VERSION_V1.to_string(),
-            codec: CODEC_VERSION_V0.to_string(),
-            target: None,
-            bin: ArtifactEntry {
-                path: "app.bin".to_string(),
-                sha256: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
-                    .to_string(),
-            },
-            elf: ArtifactEntry {
-                path: "app.elf".to_string(),
-                sha256: "b8f1d1d6b064577aa66013024e69c0dcde721573ae58da439b84e1c862437288"
-                    .to_string(),
-            },
-            text: ArtifactEntry {
-                path: "app.text".to_string(),
-                sha256: "0476bd0bb997b387b72f721b3f0f38f112e43e32f151e1d1f2ec20bc7c5ad5a6"
-                    .to_string(),
-            },
-            build: BuildMetadata {
-                profile: Profile::Release,
-                git_branch: "main".to_string(),
-                git_commit: "abc123".to_string(),
-                is_dirty: false,
-            },
-        };
+        let mut manifest = sample_manifest(MANIFEST_VERSION_V1);
+        manifest.bin_name = Some("worker".to_string());
 
         let toml = manifest.to_toml().expect("serialize");
         assert!(toml.contains("bin_name = \"worker\""));
@@ -239,39 +549,178 @@ mod tests {
         assert_eq!(parsed.bin_name.as_deref(), Some("worker"));
     }
 
+    #[test]
+    fn includes_bin_name_when_present_v2() {
+        let mut manifest = sample_manifest(MANIFEST_VERSION_V2);
+        manifest.bin_name = Some("worker".to_string());
+
+        let toml = manifest.to_toml().expect("serialize");
+        assert!(toml.contains("bin_name = \"worker\""));
+        let parsed = Manifest::parse(&toml).expect("parse");
+        assert_eq!(parsed, manifest);
+    }
+
     #[test]
     fn rejects_unknown_manifest_version() {
-        let mut manifest = Manifest {
-            package: "demo".to_string(),
-            bin_name: None,
-            manifest: MANIFEST_VERSION_V1.to_string(),
-            codec: CODEC_VERSION_V0.to_string(),
-            target: None,
-            bin: ArtifactEntry {
-                path: "app.bin".to_string(),
-                sha256: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
-                    .to_string(),
-            },
-            elf: ArtifactEntry {
-                path: "app.elf".to_string(),
-                sha256: "b8f1d1d6b064577aa66013024e69c0dcde721573ae58da439b84e1c862437288"
-                    .to_string(),
-            },
-            text: ArtifactEntry {
-                path: "app.text".to_string(),
-                sha256: "0476bd0bb997b387b72f721b3f0f38f112e43e32f151e1d1f2ec20bc7c5ad5a6"
-                    .to_string(),
-            },
-            build: BuildMetadata {
-                profile: Profile::Release,
-                git_branch: "main".to_string(),
-                git_commit: "abc123".to_string(),
-                is_dirty: false,
-            },
-        };
-        manifest.manifest = "v2".to_string();
+        let mut manifest = sample_manifest(MANIFEST_VERSION_V1);
+        manifest.manifest = "v3".to_string();
         let toml = manifest.to_toml().expect("serialize");
         let err = Manifest::parse(&toml).expect_err("error");
         assert!(matches!(err, ManifestError::UnsupportedManifestVersion(_)));
     }
+
+    #[test]
+    fn build_provenance_roundtrips_and_stays_optional() {
+        let mut build = BuildMetadata::new(Profile::Release);
+        let toml = toml::to_string(&build).expect("serialize");
+        assert_eq!(
+            toml::from_str::<BuildMetadata>(
+                "profile = \"release\"\ngit_branch = \"N/A\"\ngit_commit = \"N/A\"\n"
+            )
+            .expect("parse metadata without provenance"),
+            build
+        );
+        assert!(!toml.contains("rustc_version"));
+        assert!(!toml.contains("sdk_versions"));
+
+        build.git_commit = "abc123".to_string();
+        build.rustc_version = Some("1.95.0-nightly".to_string());
+        build.rustc_commit_hash = Some("0123abcd".to_string());
+        build.resolved_target = Some("riscv32im-risc0-zkvm-elf".to_string());
+        build.cargo_args = vec!["--features".to_string(), "extra".to_string()];
+        build.features = vec!["extra".to_string()];
+        build.cargo_lock_sha256 = Some("ab".repeat(32));
+        build
+            .sdk_versions
+            .insert("airbender-sdk".to_string(), "0.1.0".to_string());
+        build.built_at = Some(1_700_000_000);
+        build.no_default_features = true;
+        build.rustflags = vec!["-C".to_string(), "debuginfo=0".to_string()];
+        build.opt_level = Some(OptLevel::MinSize);
+        build.lto = Some(Lto::Fat);
+        build.codegen_units = Some(1);
+
+        let toml = toml::to_string(&build).expect("serialize");
+        assert!(toml.contains("built_at = 1700000000"));
+        assert!(toml.contains("opt_level = \"z\""));
+        assert!(toml.contains("lto = \"fat\""));
+        assert!(toml.contains("airbender-sdk = \"0.1.0\""));
+        assert_eq!(
+            toml::from_str::<BuildMetadata>(&toml).expect("parse"),
+            build
+        );
+        assert_eq!(
+            build.provenance(),
+            "release build of commit abc123 with rustc 1.95.0-nightly (0123abcd) \
+             for riscv32im-risc0-zkvm-elf at unix time 1700000000"
+        );
+    }
+
+    #[test]
+    fn dist_index_roundtrips() {
+        let index = DistIndex {
+            guests: vec![DistIndexEntry {
+                app: "fibonacci".to_string(),
+                package: "guests".to_string(),
+                bin_name: "fibonacci".to_string(),
+                image_id: ImageId::from_bytes([0xab; 32]),
+            }],
+        };
+        let toml = toml::to_string(&index).expect("serialize");
+        assert!(toml.contains("[[guest]]"));
+        assert!(toml.contains("app = \"fibonacci\""));
+        assert_eq!(toml::from_str::<DistIndex>(&toml).expect("parse"), index);
+        assert_eq!(
+            toml::from_str::<DistIndex>("").expect("parse empty index"),
+            DistIndex::default()
+        );
+    }
+
+    #[test]
+    fn reads_v1_and_upgrades_to_v2() {
+        let v1 = r#"
+package = "demo"
+manifest = "v1"
+codec = "v0"
+
+[bin]
+path = "app.bin"
+sha256 = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+
+[elf]
+path = "app.elf"
+sha256 = "b8f1d1d6b064577aa66013024e69c0dcde721573ae58da439b84e1c862437288"
+
+[text]
+path = "app.text"
+sha256 = "0476bd0bb997b387b72f721b3f0f38f112e43e32f151e1d1f2ec20bc7c5ad5a6"
+
+[build]
+profile = "release"
+git_branch = "main"
+git_commit = "abc123"
+"#;
+        let manifest = Manifest::parse(v1).expect("parse v1");
+        assert_eq!(manifest.manifest, MANIFEST_VERSION_V1);
+        let image_id = format!("image_id = \"{}\"\n{v1}", "ab".repeat(32));
+        let interface = format!("{v1}\n[interface]\ninput = \"u32\"\n");
+        let built_at = v1.replace("[build]\n", "[build]\nbuilt_at = 1\n");
+        for (content, field) in [
+            (image_id, "image_id"),
+            (interface, "interface"),
+            (built_at, "build.built_at"),
+        ] {
+            match Manifest::parse(&content) {
+                Err(ManifestError::UnsupportedField { field: actual, .. }) => {
+                    assert_eq!(actual, field)
+                }
+                other => panic!("expected `{field}` to be rejected in v1, got {other:?}"),
+            }
+        }
+        assert!(!manifest.is_latest());
+        assert!(manifest.artifacts.is_empty());
+        assert_eq!(manifest.interface, None);
+
+        let mut upgraded = manifest.clone().upgrade();
+        assert!(upgraded.is_latest());
+        assert_eq!(
+            Manifest {
+                manifest: MANIFEST_VERSION_V1.to_string(),
+                ..upgraded.clone()
+            },
+            manifest
+        );
+
+        upgraded.artifacts.insert(
+            "symbols".to_string(),
+            ArtifactEntry {
+                path: "app.sym".to_string(),
+                sha256: "ab".repeat(32),
+            },
+        );
+        upgraded.interface = Some(InterfaceDescription {
+            input: Some("u32".to_string()),
+            output: None,
+        });
+        let toml = upgraded.to_toml().expect("serialize");
+        assert!(toml.contains("manifest = \"v2\""));
+        assert!(toml.contains("[artifacts.symbols]"));
+        assert!(toml.contains("[interface]"));
+        assert!(!toml.contains("output"));
+        let parsed = Manifest::parse(&toml).expect("parse v2");
+        let err = Manifest::parse(&toml.replace("\"v2\"", "\"v1\""))
+            .expect_err("v1 must not carry v2 fields");
+        assert!(matches!(
+            err,
+            ManifestError::UnsupportedField { ref field, .. } if field == "artifacts"
+        ));
+        assert_eq!(parsed, upgraded);
+        assert_eq!(
+            parsed
+                .artifact_entries()
+                .map(|entry| entry.path.as_str())
+                .collect::<Vec<_>>(),
+            ["app.bin", "app.elf", "app.text", "app.sym"]
+        );
+    }
 }
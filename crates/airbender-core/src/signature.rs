@@ -0,0 +1,277 @@
+//! Detached ed25519 signatures over dist manifests.
+//!
+//! The manifest records SHA-256 digests of every artifact, so a valid signature over the exact
+//! bytes of `manifest.toml` vouches for the whole dist bundle. The signature is stored next to the
+//! manifest in [`MANIFEST_SIGNATURE_FILE`].
+
+use crate::hex;
+use ed25519_dalek::{Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// File name of the detached manifest signature inside a dist directory.
+pub const MANIFEST_SIGNATURE_FILE: &str = "manifest.toml.sig";
+
+/// Signature algorithm recorded in the signature file.
+pub const SIGNATURE_ALGORITHM_ED25519: &str = "ed25519";
+
+const DOMAIN_TAG: &[u8] = b"airbender-manifest-signature-v1";
+
+/// Errors returned by signing key and signature operations.
+#[derive(Debug, thiserror::Error)]
+pub enum SignatureError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse manifest signature: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("failed to serialize manifest signature: {0}")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("invalid key: {0}")]
+    InvalidKey(String),
+    #[error("unsupported signature algorithm `{0}`")]
+    UnsupportedAlgorithm(String),
+    #[error("manifest is signed by untrusted key {0}")]
+    UntrustedKey(ManifestPublicKey),
+    #[error("manifest signature is invalid")]
+    InvalidSignature,
+}
+
+/// Secret ed25519 key signing dist manifests.
+///
+/// Key files contain the 32-byte seed as 64 hex characters.
+#[derive(Clone)]
+pub struct ManifestSigningKey(SigningKey);
+
+impl ManifestSigningKey {
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self(SigningKey::from_bytes(&seed))
+    }
+
+    /// Read a signing key from a file containing its hex-encoded seed.
+    pub fn read_from_file(path: &Path) -> Result<Self, SignatureError> {
+        let content = fs::read_to_string(path)?;
+        let seed = hex::decode::<32>(content.trim()).map_err(|_| {
+            SignatureError::InvalidKey(format!(
+                "{} must contain a 32-byte seed as 64 hex characters",
+                path.display()
+            ))
+        })?;
+        Ok(Self::from_seed(seed))
+    }
+
+    pub fn public_key(&self) -> ManifestPublicKey {
+        ManifestPublicKey(self.0.verifying_key().to_bytes())
+    }
+
+    /// Sign the exact bytes of a `manifest.toml`.
+    pub fn sign(&self, manifest: &[u8]) -> ManifestSignature {
+        ManifestSignature {
+            algorithm: SIGNATURE_ALGORITHM_ED25519.to_string(),
+            public_key: self.public_key(),
+            signature: hex::encode(&self.0.sign(&signed_message(manifest)).to_bytes()),
+        }
+    }
+}
+
+impl fmt::Debug for ManifestSigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ManifestSigningKey({})", self.public_key())
+    }
+}
+
+/// Public ed25519 key trusted to sign dist manifests.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ManifestPublicKey([u8; 32]);
+
+impl ManifestPublicKey {
+    pub const fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Parse a 64-character hex string (case-insensitive).
+    pub fn from_hex(value: &str) -> Result<Self, SignatureError> {
+        hex::decode::<32>(value).map(Self).map_err(|_| {
+            SignatureError::InvalidKey(format!(
+                "public key must be 64 hex characters, got `{value}`"
+            ))
+        })
+    }
+}
+
+impl fmt::Display for ManifestPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(&self.0))
+    }
+}
+
+impl fmt::Debug for ManifestPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ManifestPublicKey({self})")
+    }
+}
+
+impl FromStr for ManifestPublicKey {
+    type Err = SignatureError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(value)
+    }
+}
+
+impl Serialize for ManifestPublicKey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ManifestPublicKey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::from_hex(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Serialized detached signature over a `manifest.toml`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestSignature {
+    /// Signature algorithm; only `ed25519` is supported.
+    pub algorithm: String,
+    /// Key that produced the signature.
+    pub public_key: ManifestPublicKey,
+    /// Hex-encoded signature bytes.
+    pub signature: String,
+}
+
+impl ManifestSignature {
+    /// Read a signature from a TOML file.
+    pub fn read_from_file(path: &Path) -> Result<Self, SignatureError> {
+        let content = fs::read_to_string(path)?;
+        Self::parse(&content)
+    }
+
+    /// Write this signature to a TOML file.
+    pub fn write_to_file(&self, path: &Path) -> Result<(), SignatureError> {
+        fs::write(path, self.to_toml()?)?;
+        Ok(())
+    }
+
+    /// Parse a signature from TOML text.
+    pub fn parse(content: &str) -> Result<Self, SignatureError> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Serialize this signature to TOML text.
+    pub fn to_toml(&self) -> Result<String, SignatureError> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// Check that `manifest` was signed by one of `trusted_keys` and has not changed since.
+    pub fn verify(
+        &self,
+        manifest: &[u8],
+        trusted_keys: &[ManifestPublicKey],
+    ) -> Result<(), SignatureError> {
+        if self.algorithm != SIGNATURE_ALGORITHM_ED25519 {
+            return Err(SignatureError::UnsupportedAlgorithm(self.algorithm.clone()));
+        }
+        if !trusted_keys.contains(&self.public_key) {
+            return Err(SignatureError::UntrustedKey(self.public_key));
+        }
+
+        let key = VerifyingKey::from_bytes(&self.public_key.0).map_err(|_| {
+            SignatureError::InvalidKey(format!("{} is not an ed25519 key", self.public_key))
+        })?;
+        let signature = hex::decode::<64>(&self.signature)
+            .map(|bytes| ed25519_dalek::Signature::from_bytes(&bytes))
+            .map_err(|_| SignatureError::InvalidSignature)?;
+        key.verify(&signed_message(manifest), &signature)
+            .map_err(|_| SignatureError::InvalidSignature)
+    }
+}
+
+fn signed_message(manifest: &[u8]) -> Vec<u8> {
+    let mut message = DOMAIN_TAG.to_vec();
+    message.extend_from_slice(manifest);
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &[u8] = b"package = \"demo\"\nmanifest = \"v1\"\n";
+
+    #[test]
+    fn signature_roundtrips_and_verifies_with_trusted_key() {
+        let key = ManifestSigningKey::from_seed([1; 32]);
+        let signature = key.sign(MANIFEST);
+
+        let toml = signature.to_toml().expect("serialize");
+        assert!(toml.contains("algorithm = \"ed25519\""));
+        assert!(toml.contains(&format!("public_key = \"{}\"", key.public_key())));
+        let parsed = ManifestSignature::parse(&toml).expect("parse");
+        assert_eq!(parsed, signature);
+
+        parsed
+            .verify(MANIFEST, &[key.public_key()])
+            .expect("signature must verify");
+    }
+
+    #[test]
+    fn rejects_untrusted_keys_and_modified_manifests() {
+        let key = ManifestSigningKey::from_seed([1; 32]);
+        let other_key = ManifestSigningKey::from_seed([2; 32]).public_key();
+        let signature = key.sign(MANIFEST);
+
+        let err = signature
+            .verify(MANIFEST, &[other_key])
+            .expect_err("untrusted key must be rejected");
+        assert!(matches!(err, SignatureError::UntrustedKey(_)));
+        assert!(matches!(
+            signature.verify(MANIFEST, &[]),
+            Err(SignatureError::UntrustedKey(_))
+        ));
+
+        let err = signature
+            .verify(b"package = \"evil\"\n", &[key.public_key()])
+            .expect_err("modified manifest must be rejected");
+        assert!(matches!(err, SignatureError::InvalidSignature));
+
+        let mut forged = signature;
+        forged.public_key = other_key;
+        assert!(forged.verify(MANIFEST, &[other_key]).is_err());
+    }
+
+    #[test]
+    fn parses_public_keys_and_key_files() {
+        let key = ManifestSigningKey::from_seed([3; 32]);
+        let public_key = key.public_key();
+        assert_eq!(
+            public_key
+                .to_string()
+                .parse::<ManifestPublicKey>()
+                .expect("parse"),
+            public_key
+        );
+        assert!(ManifestPublicKey::from_hex("abcd").is_err());
+
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let path = temp_dir.path().join("signing-key");
+        fs::write(&path, format!("{}\n", "03".repeat(32))).expect("write key file");
+        let loaded = ManifestSigningKey::read_from_file(&path).expect("read key file");
+        assert_eq!(loaded.public_key(), public_key);
+
+        fs::write(&path, "not a key").expect("write key file");
+        assert!(matches!(
+            ManifestSigningKey::read_from_file(&path),
+            Err(SignatureError::InvalidKey(_))
+        ));
+    }
+}
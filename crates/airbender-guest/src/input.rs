@@ -10,6 +10,11 @@ use core::fmt;
 pub enum GuestError {
     Codec(CodecError),
     UnsupportedTarget,
+    /// A verified proof carries another recursion chain than the expected inner program's.
+    RecursionChainMismatch {
+        expected: [u32; 8],
+        found: [u32; 8],
+    },
 }
 
 impl From<CodecError> for GuestError {
@@ -25,6 +30,10 @@ impl fmt::Display for GuestError {
             GuestError::UnsupportedTarget => {
                 f.write_str("csr transport is only available on riscv32")
             }
+            GuestError::RecursionChainMismatch { expected, found } => write!(
+                f,
+                "verified proof has recursion chain {found:?}, expected {expected:?}"
+            ),
         }
     }
 }
@@ -10,7 +10,11 @@ extern crate std;
 pub mod commit;
 pub mod input;
 pub mod transport;
+#[cfg(feature = "proof-verification")]
+pub mod verify;
 
 pub use commit::{commit, exit_error, Commit};
 pub use input::{read, read_with, GuestError};
 pub use transport::{CsrTransport, MockTransport, Transport};
+#[cfg(feature = "proof-verification")]
+pub use verify::{verify_unified_proof, verify_unified_proof_for, VerifiedProof};
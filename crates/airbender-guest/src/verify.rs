@@ -0,0 +1,109 @@
+//! In-guest verification of recursion-unified proofs.
+//!
+//! The host appends a proof to the input stream with `Inputs::push_unified_proof`, and the guest
+//! consumes it with [`verify_unified_proof`] at the same position relative to other inputs.
+
+use crate::input::GuestError;
+
+/// Public output of a proof verified inside the guest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifiedProof {
+    /// Public output (`x10..x17`) committed by the inner program.
+    pub output: [u32; 8],
+    /// Recursion-chain commitment (`x18..x25`) to the verification keys of the inner program.
+    pub recursion_chain: [u32; 8],
+}
+
+impl VerifiedProof {
+    #[cfg_attr(not(target_arch = "riscv32"), allow(dead_code))]
+    fn from_registers(registers: [u32; 16]) -> Self {
+        let mut output = [0u32; 8];
+        let mut recursion_chain = [0u32; 8];
+        output.copy_from_slice(&registers[..8]);
+        recursion_chain.copy_from_slice(&registers[8..]);
+        Self {
+            output,
+            recursion_chain,
+        }
+    }
+}
+
+/// Verify the next recursion-unified proof in the input stream.
+///
+/// An invalid proof aborts the guest, so no proof of the outer program exists for it. Any valid
+/// proof is accepted, though: compare `recursion_chain` with the value reported by the host's
+/// `VerifiedOutput::recursion_chain()` for the expected inner program, or commit it.
+pub fn verify_unified_proof() -> Result<VerifiedProof, GuestError> {
+    #[cfg(target_arch = "riscv32")]
+    {
+        let registers =
+            full_statement_verifier::unified_circuit_statement::verify_unified_circuit_recursion_layer();
+        Ok(VerifiedProof::from_registers(registers))
+    }
+    #[cfg(not(target_arch = "riscv32"))]
+    {
+        Err(GuestError::UnsupportedTarget)
+    }
+}
+
+/// Verify the next recursion-unified proof and require the expected recursion chain.
+///
+/// Returns the inner program's public output, or [`GuestError::RecursionChainMismatch`] when
+/// the proof was produced for another program.
+pub fn verify_unified_proof_for(
+    expected_recursion_chain: &[u32; 8],
+) -> Result<[u32; 8], GuestError> {
+    check_recursion_chain(verify_unified_proof()?, expected_recursion_chain)
+}
+
+fn check_recursion_chain(
+    verified: VerifiedProof,
+    expected_recursion_chain: &[u32; 8],
+) -> Result<[u32; 8], GuestError> {
+    if &verified.recursion_chain != expected_recursion_chain {
+        return Err(GuestError::RecursionChainMismatch {
+            expected: *expected_recursion_chain,
+            found: verified.recursion_chain,
+        });
+    }
+    Ok(verified.output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_registers_into_output_and_recursion_chain() {
+        let mut registers = [0u32; 16];
+        for (index, register) in registers.iter_mut().enumerate() {
+            *register = index as u32;
+        }
+
+        let verified = VerifiedProof::from_registers(registers);
+        assert_eq!(verified.output, [0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(verified.recursion_chain, [8, 9, 10, 11, 12, 13, 14, 15]);
+    }
+
+    #[test]
+    fn mismatching_recursion_chain_is_an_error() {
+        let verified = VerifiedProof {
+            output: [1; 8],
+            recursion_chain: [2; 8],
+        };
+
+        assert_eq!(check_recursion_chain(verified, &[2; 8]).unwrap(), [1; 8]);
+        assert!(matches!(
+            check_recursion_chain(verified, &[3; 8]),
+            Err(GuestError::RecursionChainMismatch { found, .. }) if found == [2; 8]
+        ));
+    }
+
+    #[test]
+    fn verification_requires_riscv32() {
+        assert!(matches!(
+            verify_unified_proof(),
+            Err(GuestError::UnsupportedTarget)
+        ));
+    }
+}
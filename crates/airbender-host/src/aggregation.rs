@@ -0,0 +1,229 @@
+//! Aggregation of recursion-unified proofs into a single proof.
+//!
+//! The aggregation guest lives in `guests/aggregator`; build it with `cargo airbender build` and
+//! prove it with any [`Prover`] created from its dist directory. With the `aggregator` feature,
+//! [`Aggregator::program`] embeds that dist in the host binary.
+
+use crate::error::{HostError, Result};
+use crate::inputs::Inputs;
+use crate::prover::{Prover, ProverLevel};
+use airbender_core::aggregation::{claims_commitment, AggregatedClaim};
+use airbender_verifier::{Proof, RealProof, UnifiedVk};
+
+/// Collects recursion-unified proofs, possibly of different programs, for one aggregation proof.
+#[derive(Clone, Debug, Default)]
+pub struct Aggregator {
+    claims: Vec<AggregatedClaim>,
+    inputs: Inputs,
+}
+
+/// Aggregation proof together with the claims its public output commits to.
+#[derive(Clone, Debug)]
+pub struct AggregatedProof {
+    pub proof: Proof,
+    pub claims: Vec<AggregatedClaim>,
+    pub cycles: u64,
+}
+
+impl Aggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Verify `proof` against `vk` and add it to the aggregation.
+    ///
+    /// Returns the claim the aggregation proof will commit to for this proof. Its recursion chain
+    /// equals `vk.program_commitment`, which is how verifiers of the aggregation proof tell which
+    /// program it is for.
+    pub fn add(&mut self, proof: &RealProof, vk: &UnifiedVk) -> Result<AggregatedClaim> {
+        if proof.level() != ProverLevel::RecursionUnified {
+            return Err(HostError::Verification(format!(
+                "aggregation requires recursion-unified proofs, got {:?}",
+                proof.level()
+            )));
+        }
+        let registers =
+            airbender_verifier::verify_proof(proof.inner(), vk, Some(proof.image_id()), None)?;
+
+        let mut claim = AggregatedClaim {
+            recursion_chain: [0; 8],
+            output: [0; 8],
+        };
+        claim.output.copy_from_slice(&registers[..8]);
+        claim.recursion_chain.copy_from_slice(&registers[8..]);
+
+        // The aggregation guest reads `true` before each proof and `false` at the end.
+        self.inputs.push(&true)?;
+        self.inputs.push_verified_unified_proof(proof, vk);
+        self.claims.push(claim);
+        Ok(claim)
+    }
+
+    /// Aggregation guest embedded at compile time from `guests/aggregator/dist/app`.
+    ///
+    /// The dist is checked against its manifest when the host is compiled, so a missing or stale
+    /// build of the aggregation guest fails the build rather than the proof.
+    #[cfg(feature = "aggregator")]
+    pub fn program() -> Result<crate::Program> {
+        crate::include_program!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../guests/aggregator/dist/app"
+        ))
+    }
+
+    /// Claims added so far, in aggregation order.
+    pub fn claims(&self) -> &[AggregatedClaim] {
+        &self.claims
+    }
+
+    /// Prove the aggregation with a prover of the aggregation guest.
+    pub fn prove(&self, prover: &impl Prover) -> Result<AggregatedProof> {
+        if self.claims.is_empty() {
+            return Err(HostError::Prover("no proofs to aggregate".to_string()));
+        }
+
+        let mut inputs = self.inputs.clone();
+        inputs.push(&false)?;
+        let result = prover.prove(inputs.words())?;
+
+        let expected_output = claims_commitment(&self.claims);
+        if result.receipt.output != expected_output {
+            return Err(HostError::Prover(
+                "aggregation output does not commit to the added proofs; \
+                 is the prover running the aggregation guest?"
+                    .to_string(),
+            ));
+        }
+
+        Ok(AggregatedProof {
+            proof: result.proof,
+            claims: self.claims.clone(),
+            cycles: result.cycles,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::ProveResult;
+    use airbender_core::image_id::ImageId;
+    use airbender_verifier::{DevProof, DevSigningKey, Receipt};
+
+    struct FixedOutputProver {
+        output: [u32; 8],
+    }
+
+    impl Prover for FixedOutputProver {
+        fn prove(&self, _input_words: &[u32]) -> Result<ProveResult> {
+            let mut registers = [0u32; 32];
+            registers[10..18].copy_from_slice(&self.output);
+            let receipt = Receipt::from_registers(registers);
+            Ok(ProveResult {
+                proof: Proof::Dev(
+                    DevProof::sign(
+                        ImageId::from_bytes([0; 32]),
+                        [0; 32],
+                        receipt.clone(),
+                        1,
+                        &DevSigningKey::from_seed([7; 32]),
+                    )
+                    .expect("sign dev proof"),
+                ),
+                cycles: 1,
+                receipt,
+            })
+        }
+    }
+
+    fn aggregator_with_claim() -> Aggregator {
+        let mut aggregator = Aggregator::new();
+        aggregator.claims.push(AggregatedClaim {
+            recursion_chain: [2; 8],
+            output: [3; 8],
+        });
+        aggregator
+    }
+
+    #[test]
+    fn prove_rejects_empty_aggregation() {
+        let prover = FixedOutputProver { output: [0; 8] };
+        let err = Aggregator::new()
+            .prove(&prover)
+            .expect_err("empty aggregation must fail");
+        assert!(err.to_string().contains("no proofs to aggregate"));
+    }
+
+    #[test]
+    fn prove_checks_committed_claims() {
+        let aggregator = aggregator_with_claim();
+
+        let prover = FixedOutputProver {
+            output: claims_commitment(aggregator.claims()),
+        };
+        let aggregated = aggregator
+            .prove(&prover)
+            .expect("matching output must pass");
+        assert_eq!(aggregated.claims, aggregator.claims());
+
+        let prover = FixedOutputProver { output: [0; 8] };
+        let err = aggregator
+            .prove(&prover)
+            .expect_err("mismatching output must fail");
+        assert!(err.to_string().contains("aggregation guest"));
+    }
+
+    #[cfg(feature = "aggregator")]
+    #[test]
+    fn aggregator_guest_commits_to_empty_claims() {
+        use crate::runner::Runner;
+
+        let program = Aggregator::program().expect("load embedded aggregation guest");
+        let mut inputs = Inputs::new();
+        inputs.push(&false).expect("push end marker");
+        let execution = program
+            .transpiler_runner()
+            .build()
+            .expect("build runner")
+            .run(inputs.words())
+            .expect("run aggregation guest");
+
+        assert!(execution.reached_end);
+        assert_eq!(execution.receipt.output, claims_commitment(&[]));
+    }
+
+    #[cfg(feature = "aggregator")]
+    #[test]
+    #[ignore = "proves recursion layers on the CPU; run by the aggregator CI job"]
+    fn aggregates_a_real_recursion_unified_proof() {
+        use airbender_verifier::{RealUnifiedVerificationKey, VerificationKey};
+
+        let program = Aggregator::program().expect("load embedded aggregation guest");
+        let prover = program
+            .cpu_prover()
+            .with_level(ProverLevel::RecursionUnified)
+            .build()
+            .expect("build CPU prover");
+        let vk = crate::vk::unified_vk(&program.image()).expect("compute unified vk");
+
+        // The inner proof aggregates nothing, so the guest proves and verifies itself.
+        let mut inputs = Inputs::new();
+        inputs.push(&false).expect("push end marker");
+        let Proof::Real(inner) = prover.prove(inputs.words()).expect("prove inner").proof else {
+            panic!("CPU prover must produce a real proof");
+        };
+
+        let mut aggregator = Aggregator::new();
+        let claim = aggregator.add(&inner, &vk).expect("add inner proof");
+        assert_eq!(claim.output, claims_commitment(&[]));
+        assert_eq!(claim.recursion_chain, vk.program_commitment);
+
+        let aggregated = aggregator.prove(&prover).expect("prove aggregation");
+        let Proof::Real(proof) = &aggregated.proof else {
+            panic!("CPU prover must produce a real proof");
+        };
+        let aggregation_vk = VerificationKey::RealUnified(RealUnifiedVerificationKey { vk });
+        crate::verify_aggregated_proof(proof, &aggregation_vk, &aggregated.claims)
+            .expect("aggregation proof must verify");
+    }
+}
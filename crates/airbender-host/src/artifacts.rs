@@ -0,0 +1,71 @@
+//! Program images loaded by runners, provers and verifiers.
+
+use airbender_core::image_id::{ImageId, MachineConfig};
+use std::path::{Path, PathBuf};
+
+/// Where a builder reads `app.bin` and `app.text` from.
+#[derive(Clone, Debug)]
+pub(crate) enum ProgramSource {
+    /// `app.bin` on disk, with `app.text` and `app.elf` next to it.
+    Path(PathBuf),
+    /// Contents already held in memory.
+    Bytes {
+        bin: Vec<u8>,
+        text: Vec<u8>,
+        /// `app.bin` the contents were read from, used to find the project dev key and the
+        /// `app.elf` symbols; `None` for programs that never lived on disk.
+        location: Option<PathBuf>,
+    },
+}
+
+impl ProgramSource {
+    pub(crate) fn bytes(bin: impl Into<Vec<u8>>, text: impl Into<Vec<u8>>) -> Self {
+        Self::Bytes {
+            bin: bin.into(),
+            text: text.into(),
+            location: None,
+        }
+    }
+}
+
+/// `app.bin` and `app.text` contents of a loaded program.
+pub(crate) struct ProgramImage {
+    pub(crate) bin: Vec<u8>,
+    pub(crate) text: Vec<u8>,
+    /// `app.bin` on disk, when the image came from one.
+    pub(crate) location: Option<PathBuf>,
+}
+
+impl ProgramImage {
+    /// Reads `app.bin` and `app.text` from already resolved paths.
+    pub(crate) fn read(app_bin_path: &Path, app_text_path: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            bin: std::fs::read(app_bin_path)?,
+            text: std::fs::read(app_text_path)?,
+            location: Some(app_bin_path.to_path_buf()),
+        })
+    }
+
+    /// Canonical image id, binding `app.bin`, `app.text` and the machine config.
+    pub(crate) fn image_id(&self) -> ImageId {
+        ImageId::compute(&self.bin, &self.text, MachineConfig::default())
+    }
+
+    /// `app.elf` next to `app.bin`, when the image came from disk.
+    pub(crate) fn elf_path(&self) -> Option<PathBuf> {
+        self.location
+            .as_ref()
+            .map(|app_bin_path| app_bin_path.with_extension("elf"))
+    }
+}
+
+/// Little-endian words of `bytes`, or `None` when the length is not a multiple of 4.
+pub(crate) fn to_u32_words(bytes: &[u8]) -> Option<Vec<u32>> {
+    let (chunks, rest) = bytes.as_chunks::<4>();
+    rest.is_empty().then(|| {
+        chunks
+            .iter()
+            .map(|chunk| u32::from_le_bytes(*chunk))
+            .collect()
+    })
+}
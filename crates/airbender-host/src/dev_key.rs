@@ -0,0 +1,129 @@
+//! Dev keys stored under the project directory.
+//!
+//! `DevProver` signs dev proofs with the key at `<project>/.airbender/dev-key`, creating it on
+//! first use, and `DevVerifier` trusts the same key by default. The project directory is the
+//! closest ancestor of `app.bin` containing a `Cargo.toml`.
+
+use crate::error::{HostError, Result};
+use airbender_core::hex;
+use airbender_verifier::DevSigningKey;
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Location of the dev key, relative to the project directory.
+pub const DEV_KEY_PATH: &str = ".airbender/dev-key";
+
+/// Load the project's dev key, generating and storing a new one if none exists yet.
+pub fn load_or_create_dev_key(project_dir: impl AsRef<Path>) -> Result<DevSigningKey> {
+    let path = project_dir.as_ref().join(DEV_KEY_PATH);
+    if path.exists() {
+        return read_dev_key(&path);
+    }
+
+    let mut seed = [0u8; 32];
+    getrandom::getrandom(&mut seed)
+        .map_err(|err| HostError::Prover(format!("failed to generate dev key: {err}")))?;
+    let key = DevSigningKey::from_seed(seed);
+    write_dev_key(&path, &key)?;
+    Ok(key)
+}
+
+/// Load the project's existing dev key.
+pub fn load_dev_key(project_dir: impl AsRef<Path>) -> Result<DevSigningKey> {
+    let path = project_dir.as_ref().join(DEV_KEY_PATH);
+    if !path.exists() {
+        return Err(HostError::Verification(format!(
+            "dev key not found at {}; it is created by the first dev proof of this project",
+            path.display()
+        )));
+    }
+    read_dev_key(&path)
+}
+
+/// Project directory owning `app_bin_path`, falling back to the directory containing it.
+pub(crate) fn project_dir_for(app_bin_path: &Path) -> PathBuf {
+    cargo_project_dir(app_bin_path).unwrap_or_else(|| bin_dir(app_bin_path).to_path_buf())
+}
+
+/// Closest ancestor of `app_bin_path` containing a `Cargo.toml`, if any.
+pub(crate) fn cargo_project_dir(app_bin_path: &Path) -> Option<PathBuf> {
+    bin_dir(app_bin_path)
+        .ancestors()
+        .find(|dir| dir.join("Cargo.toml").is_file())
+        .map(Path::to_path_buf)
+}
+
+fn bin_dir(app_bin_path: &Path) -> &Path {
+    app_bin_path.parent().unwrap_or(Path::new("."))
+}
+
+fn read_dev_key(path: &Path) -> Result<DevSigningKey> {
+    let contents = fs::read_to_string(path)?;
+    let seed = hex::decode::<32>(contents.trim()).map_err(|_| {
+        HostError::Verification(format!(
+            "invalid dev key at {}: expected 64 hex characters",
+            path.display()
+        ))
+    })?;
+    Ok(DevSigningKey::from_seed(seed))
+}
+
+fn write_dev_key(path: &Path, key: &DevSigningKey) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let contents = format!("{}\n", hex::encode(&key.seed()));
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creates_key_once_and_reloads_it() {
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let dir = temp_dir.path();
+        let err = load_dev_key(dir).expect_err("missing key must be reported");
+        assert!(err.to_string().contains("dev key not found"));
+
+        let created = load_or_create_dev_key(dir).expect("create dev key");
+        let reloaded = load_or_create_dev_key(dir).expect("reload dev key");
+        assert_eq!(created.public_key(), reloaded.public_key());
+        assert_eq!(
+            load_dev_key(dir).expect("load dev key").public_key(),
+            created.public_key()
+        );
+
+        fs::write(dir.join(DEV_KEY_PATH), "not hex").expect("corrupt dev key");
+        assert!(load_dev_key(dir).is_err());
+    }
+
+    #[test]
+    fn project_dir_is_closest_cargo_package() {
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let dir = temp_dir.path();
+        let dist = dir.join("dist").join("app");
+        fs::create_dir_all(&dist).expect("create dist dir");
+        assert_eq!(project_dir_for(&dist.join("app.bin")), dist);
+        assert_eq!(cargo_project_dir(&dist.join("app.bin")), None);
+
+        fs::write(dir.join("Cargo.toml"), "").expect("write Cargo.toml");
+        assert_eq!(project_dir_for(&dist.join("app.bin")), dir);
+        assert_eq!(
+            cargo_project_dir(&dist.join("app.bin")).as_deref(),
+            Some(dir)
+        );
+    }
+}
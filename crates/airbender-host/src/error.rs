@@ -22,6 +22,18 @@ pub enum HostError {
 
 pub type Result<T> = std::result::Result<T, HostError>;
 
+/// Renders the payload of a caught panic as a message.
+pub(crate) fn panic_payload_to_string(payload: Box<dyn std::any::Any + Send + 'static>) -> String {
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message.clone();
+    }
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return (*message).to_string();
+    }
+
+    "unknown panic payload".to_string()
+}
+
 impl From<airbender_codec::CodecError> for HostError {
     fn from(err: airbender_codec::CodecError) -> Self {
         Self::Codec(err)
@@ -33,3 +45,12 @@ impl From<airbender_core::wire::WireError> for HostError {
         Self::Wire(err)
     }
 }
+
+impl From<airbender_verifier::VerifierError> for HostError {
+    fn from(err: airbender_verifier::VerifierError) -> Self {
+        match err {
+            airbender_verifier::VerifierError::Codec(err) => Self::Codec(err),
+            airbender_verifier::VerifierError::Verification(message) => Self::Verification(message),
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use crate::error::Result;
+use crate::error::{HostError, Result};
 use airbender_codec::{AirbenderCodec, AirbenderCodecV0};
 use airbender_core::wire::frame_words_from_bytes;
+use airbender_verifier::{ProverLevel, RealProof, UnifiedVk};
 use std::fmt::Write as _;
 use std::path::Path;
 
@@ -29,6 +30,33 @@ impl Inputs {
         Ok(())
     }
 
+    /// Append a recursion-unified proof for `airbender_guest::verify_unified_proof`.
+    ///
+    /// The proof is verified against `vk` first, then appended as the raw (unframed) words the
+    /// in-guest verifier reads.
+    pub fn push_unified_proof(&mut self, proof: &RealProof, vk: &UnifiedVk) -> Result<()> {
+        if proof.level() != ProverLevel::RecursionUnified {
+            return Err(HostError::Verification(format!(
+                "guest verification requires a recursion-unified proof, got {:?}",
+                proof.level()
+            )));
+        }
+        airbender_verifier::verify_proof(proof.inner(), vk, Some(proof.image_id()), None)?;
+        self.push_verified_unified_proof(proof, vk);
+        Ok(())
+    }
+
+    /// Append a recursion-unified proof that the caller has already verified against `vk`.
+    pub(crate) fn push_verified_unified_proof(&mut self, proof: &RealProof, vk: &UnifiedVk) {
+        self.words
+            .extend(execution_utils::unrolled::flatten_proof_for_recursion(
+                proof.inner(),
+                &vk.unified_setup,
+                &vk.unified_layouts,
+                false,
+            ));
+    }
+
     /// Access the framed input words.
     pub fn words(&self) -> &[u32] {
         &self.words
@@ -1,36 +1,71 @@
 //! Host-side APIs for executing, proving, and verifying Airbender programs.
 
+// Lets `include_program!` expand to `::airbender_host` paths inside this crate as well.
+extern crate self as airbender_host;
+
+mod aggregation;
+mod artifacts;
+mod dev_key;
 mod error;
 mod inputs;
 mod program;
 mod proof;
 mod prover;
-mod receipt;
+mod recursion;
 mod runner;
+pub mod service;
 mod verifier;
 mod vk;
+#[cfg(feature = "async")]
+mod worker;
 
+pub use aggregation::{AggregatedProof, Aggregator};
 pub use airbender_core::guest::Commit;
+pub use airbender_core::host::signature::{ManifestPublicKey, MANIFEST_SIGNATURE_FILE};
+pub use airbender_core::image_id::{ImageId, MachineConfig};
+/// Embed a dist directory, including every artifact its manifest lists, in the binary.
+///
+/// The path is resolved like [`include_bytes!`], relative to the invoking file, and may also be
+/// built with `concat!` and `env!`, e.g. `concat!(env!("OUT_DIR"), "/airbender-guests/app")`.
+/// It must contain a dist written by `cargo airbender build`. The artifact digests and image id
+/// are checked against the manifest at compile time, so a stale or tampered dist fails the build;
+/// the expression yields a [`Result<Program>`](crate::Result) that repeats those checks at run
+/// time.
+///
+/// ```ignore
+/// let program = airbender_host::include_program!("../guest/dist/app")?;
+/// ```
+pub use airbender_macros::include_program;
+pub use airbender_verifier::{
+    claims_commitment, decode_proof, verify_aggregated_proof, verify_proof, verify_proof_bytes,
+    verify_real_proof_with_vk, verify_unrolled_proof, AggregatedClaim, CircuitCounts, DevProof,
+    DevProofMarker, DevPublicKey, DevSignature, DevSigningKey, DevVerificationKey, Proof,
+    RealProof, RealUnifiedVerificationKey, RealUnrolledVerificationKey, Receipt, UnifiedVk,
+    UnrolledVk, VerificationKey, VerifiedOutput, DEV_PROOF_MARKER,
+};
+pub use dev_key::{load_dev_key, load_or_create_dev_key, DEV_KEY_PATH};
 pub use error::{HostError, Result};
 pub use inputs::Inputs;
 pub use program::Program;
-pub use proof::{DevProof, Proof, RealProof};
+#[cfg(feature = "async")]
+pub use prover::{AsyncProver, ProverWorker};
 pub use prover::{
     CpuProver, CpuProverBuilder, DevProver, DevProverBuilder, ProveResult, Prover, ProverLevel,
 };
 #[cfg(feature = "gpu-prover")]
 pub use prover::{GpuProver, GpuProverBuilder};
-pub use receipt::Receipt;
+#[cfg(feature = "remote")]
+pub use prover::{RemoteProver, RemoteProverBuilder};
 pub use runner::{
     resolve_cycles, ExecutionResult, FlamegraphConfig, Runner, SimulatorRunner,
     SimulatorRunnerBuilder, TranspilerRunner, TranspilerRunnerBuilder, DEFAULT_CYCLES,
 };
+#[cfg(feature = "async")]
+pub use runner::{AsyncRunner, RunnerWorker};
 pub use verifier::{
-    verify_real_proof_with_vk, DevVerificationKey, DevVerifier, DevVerifierBuilder,
-    RealUnifiedVerificationKey, RealUnrolledVerificationKey, RealVerifier, RealVerifierBuilder,
-    VerificationKey, VerificationRequest, Verifier,
-};
-pub use vk::{
-    compute_unified_vk, compute_unrolled_vk, verify_proof, verify_unrolled_proof, UnifiedVk,
-    UnrolledVk,
+    DevVerifier, DevVerifierBuilder, RealVerifier, RealVerifierBuilder, VerificationRequest,
+    Verifier,
 };
+pub use vk::{compute_unified_vk, compute_unrolled_vk};
+#[cfg(feature = "async")]
+pub use worker::JobFuture;
@@ -1,29 +1,110 @@
+use crate::artifacts::{ProgramImage, ProgramSource};
 use crate::error::{HostError, Result};
 #[cfg(feature = "gpu-prover")]
 use crate::prover::GpuProverBuilder;
 use crate::prover::{CpuProverBuilder, DevProverBuilder, ProverLevel};
 use crate::runner::{SimulatorRunnerBuilder, TranspilerRunnerBuilder};
 use crate::verifier::{DevVerifierBuilder, RealVerifierBuilder};
-use airbender_core::host::manifest::Manifest;
+use airbender_core::hex;
+use airbender_core::host::bundle::{Bundle, MANIFEST_FILE};
+use airbender_core::host::manifest::{ArtifactEntry, Manifest};
+use airbender_core::host::signature::{
+    ManifestPublicKey, ManifestSignature, MANIFEST_SIGNATURE_FILE,
+};
+use airbender_core::image_id::{ImageId, MachineConfig};
 use sha2::Digest;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Loaded Airbender program distribution, including manifest and artifacts.
+///
+/// Artifacts are read once, checked against the manifest and kept in memory; runners, provers
+/// and verifiers created from the program load them from there.
 #[derive(Clone, Debug)]
 pub struct Program {
-    dist_dir: PathBuf,
+    /// Dist directory the program was loaded from; `None` for bundles and in-memory programs.
+    dist_dir: Option<PathBuf>,
     manifest: Manifest,
-    app_bin: PathBuf,
-    app_elf: PathBuf,
-    app_text: PathBuf,
+    image_id: ImageId,
+    /// Artifact contents keyed by their path in the manifest.
+    files: Arc<BTreeMap<String, Vec<u8>>>,
 }
 
 impl Program {
-    pub fn load(dist_dir: impl AsRef<Path>) -> Result<Self> {
-        let dist_dir = dist_dir.as_ref().to_path_buf();
-        let manifest_path = dist_dir.join("manifest.toml");
-        let manifest = Manifest::read_from_file(&manifest_path)
-            .map_err(|err| HostError::InvalidManifest(err.to_string()))?;
+    /// Load a dist directory, or a single-file bundle written by `cargo airbender build --bundle`.
+    pub fn load(dist: impl AsRef<Path>) -> Result<Self> {
+        let source = DistSource::open(dist.as_ref())?;
+        let manifest = parse_manifest(&source.read_required(MANIFEST_FILE)?)?;
+        Self::from_manifest(source, manifest)
+    }
+
+    /// Like [`Program::load`], and accepting the same inputs, but also requires
+    /// `manifest.toml.sig` to hold a valid signature over the manifest from one of `trusted_keys`.
+    ///
+    /// The manifest pins every artifact by digest, so the signature covers the whole bundle.
+    pub fn load_verified(
+        dist: impl AsRef<Path>,
+        trusted_keys: &[ManifestPublicKey],
+    ) -> Result<Self> {
+        let source = DistSource::open(dist.as_ref())?;
+        let manifest_bytes = source.read_required(MANIFEST_FILE)?;
+        let signature = source.read(MANIFEST_SIGNATURE_FILE)?.ok_or_else(|| {
+            HostError::InvalidManifest(format!(
+                "missing manifest signature: {}",
+                source.location(MANIFEST_SIGNATURE_FILE)
+            ))
+        })?;
+        std::str::from_utf8(&signature)
+            .map_err(|err| err.to_string())
+            .and_then(|signature| {
+                ManifestSignature::parse(signature)
+                    .and_then(|signature| signature.verify(&manifest_bytes, trusted_keys))
+                    .map_err(|err| err.to_string())
+            })
+            .map_err(HostError::InvalidManifest)?;
+
+        let manifest = parse_manifest(&manifest_bytes)?;
+        Self::from_manifest(source, manifest)
+    }
+
+    /// Load a program from in-memory `manifest.toml`, `app.bin`, `app.elf` and `app.text`
+    /// contents, with the same manifest checks as [`Program::load`].
+    ///
+    /// Manifests listing additional artifacts need [`Program::from_bundle_bytes`] instead.
+    pub fn from_bytes(manifest: &[u8], bin: &[u8], elf: &[u8], text: &[u8]) -> Result<Self> {
+        let parsed = parse_manifest(manifest)?;
+        Self::from_embedded(&[
+            (MANIFEST_FILE, manifest),
+            (&parsed.bin.path, bin),
+            (&parsed.elf.path, elf),
+            (&parsed.text.path, text),
+        ])
+    }
+
+    /// Load a program from named dist files, the first being `manifest.toml`.
+    ///
+    /// Used by [`include_program!`](crate::include_program) to embed every listed artifact.
+    #[doc(hidden)]
+    pub fn from_embedded(files: &[(&str, &[u8])]) -> Result<Self> {
+        let manifest = parse_manifest(files[0].1)?;
+        let files = files
+            .iter()
+            .map(|(name, contents)| (name.to_string(), contents.to_vec()))
+            .collect();
+        Self::from_manifest(DistSource::Files(files), manifest)
+    }
+
+    /// Load a program from the contents of a single-file bundle.
+    pub fn from_bundle_bytes(bytes: &[u8]) -> Result<Self> {
+        let bundle = Bundle::from_bytes(bytes)
+            .map_err(|err| HostError::InvalidManifest(format!("invalid bundle: {err}")))?;
+        let source = DistSource::from_bundle(&bundle);
+        let manifest = parse_manifest(&source.read_required(MANIFEST_FILE)?)?;
+        Self::from_manifest(source, manifest)
+    }
+
+    fn from_manifest(source: DistSource, manifest: Manifest) -> Result<Self> {
         let supported_codec = format!("v{}", airbender_codec::AIRBENDER_CODEC_V0);
         if manifest.codec != supported_codec {
             return Err(HostError::InvalidManifest(format!(
@@ -32,93 +113,308 @@ impl Program {
             )));
         }
 
-        let app_bin = dist_dir.join(&manifest.bin.path);
-        let app_elf = dist_dir.join(&manifest.elf.path);
-        let app_text = dist_dir.join(&manifest.text.path);
-
-        for path in [&app_bin, &app_elf, &app_text] {
-            if !path.exists() {
-                return Err(HostError::InvalidManifest(format!(
-                    "missing artifact: {}",
-                    path.display()
-                )));
-            }
-        }
-
-        verify_manifest_artifact_sha256(&app_bin, "bin.sha256", &manifest.bin.sha256)?;
-        verify_manifest_artifact_sha256(&app_elf, "elf.sha256", &manifest.elf.sha256)?;
-        verify_manifest_artifact_sha256(&app_text, "text.sha256", &manifest.text.sha256)?;
+        let (files, image_id) =
+            verify_manifest_artifacts(&manifest, &source).map_err(|err| match err {
+                HostError::InvalidManifest(message) => HostError::InvalidManifest(format!(
+                    "{message} (manifest records a {})",
+                    manifest.build.provenance()
+                )),
+                err => err,
+            })?;
+        warn_on_sdk_version_mismatch(&manifest);
 
         Ok(Self {
-            dist_dir,
+            dist_dir: source.dist_dir(),
             manifest,
-            app_bin,
-            app_elf,
-            app_text,
+            image_id,
+            files: Arc::new(files),
         })
     }
 
-    pub fn dist_dir(&self) -> &Path {
-        &self.dist_dir
+    /// Dist directory the program was loaded from; `None` for bundles and in-memory programs.
+    pub fn dist_dir(&self) -> Option<&Path> {
+        self.dist_dir.as_deref()
     }
 
     pub fn manifest(&self) -> &Manifest {
         &self.manifest
     }
 
-    pub fn app_bin(&self) -> &Path {
-        &self.app_bin
+    /// Canonical image id of the program, binding `app.bin`, `app.text` and the machine config.
+    pub fn image_id(&self) -> ImageId {
+        self.image_id
+    }
+
+    /// Path of `app.bin` inside [`Program::dist_dir`], if the program was loaded from one.
+    pub fn app_bin(&self) -> Option<PathBuf> {
+        self.path_of(&self.manifest.bin)
+    }
+
+    pub fn app_elf(&self) -> Option<PathBuf> {
+        self.path_of(&self.manifest.elf)
+    }
+
+    pub fn app_text(&self) -> Option<PathBuf> {
+        self.path_of(&self.manifest.text)
     }
 
-    pub fn app_elf(&self) -> &Path {
-        &self.app_elf
+    /// Contents of `app.bin`.
+    pub fn bin(&self) -> &[u8] {
+        &self.files[&self.manifest.bin.path]
     }
 
-    pub fn app_text(&self) -> &Path {
-        &self.app_text
+    /// Contents of `app.elf`.
+    pub fn elf(&self) -> &[u8] {
+        &self.files[&self.manifest.elf.path]
+    }
+
+    /// Contents of `app.text`.
+    pub fn text(&self) -> &[u8] {
+        &self.files[&self.manifest.text.path]
+    }
+
+    /// Contents of the additional artifact listed as `[artifacts.<name>]` in the manifest.
+    pub fn artifact(&self, name: &str) -> Option<&[u8]> {
+        let entry = self.manifest.artifacts.get(name)?;
+        self.files.get(&entry.path).map(Vec::as_slice)
     }
 
     /// Create a simulator runner builder bound to this program.
     pub fn simulator_runner(&self) -> SimulatorRunnerBuilder {
-        SimulatorRunnerBuilder::new(self.app_bin())
+        SimulatorRunnerBuilder::from_source(self.source())
     }
 
     /// Create a transpiler runner builder bound to this program.
     pub fn transpiler_runner(&self) -> TranspilerRunnerBuilder {
-        TranspilerRunnerBuilder::new(self.app_bin())
+        TranspilerRunnerBuilder::from_source(self.source())
     }
 
     #[cfg(feature = "gpu-prover")]
     /// Create a GPU prover builder bound to this program.
     pub fn gpu_prover(&self) -> GpuProverBuilder {
-        GpuProverBuilder::new(self.app_bin())
+        GpuProverBuilder::from_source(self.source())
     }
 
     /// Create a development prover builder bound to this program.
     pub fn dev_prover(&self) -> DevProverBuilder {
-        DevProverBuilder::new(self.app_bin())
+        DevProverBuilder::from_source(self.source())
     }
 
     /// Create a CPU prover builder bound to this program.
     pub fn cpu_prover(&self) -> CpuProverBuilder {
-        CpuProverBuilder::new(self.app_bin())
+        CpuProverBuilder::from_source(self.source())
     }
 
     /// Create a development verifier builder bound to this program.
     pub fn dev_verifier(&self) -> DevVerifierBuilder {
-        DevVerifierBuilder::new(self.app_bin())
+        DevVerifierBuilder::from_source(self.source())
     }
 
     /// Create a real verifier builder bound to this program.
     pub fn real_verifier(&self, level: ProverLevel) -> RealVerifierBuilder {
-        RealVerifierBuilder::new(self.app_bin(), level)
+        RealVerifierBuilder::from_source(self.source(), level)
+    }
+
+    fn path_of(&self, entry: &ArtifactEntry) -> Option<PathBuf> {
+        self.dist_dir
+            .as_ref()
+            .map(|dist_dir| dist_dir.join(&entry.path))
+    }
+
+    /// The checked `app.bin` and `app.text` contents, along with where they live on disk.
+    pub(crate) fn image(&self) -> ProgramImage {
+        ProgramImage {
+            bin: self.bin().to_vec(),
+            text: self.text().to_vec(),
+            location: self.app_bin(),
+        }
+    }
+
+    fn source(&self) -> ProgramSource {
+        let ProgramImage {
+            bin,
+            text,
+            location,
+        } = self.image();
+        ProgramSource::Bytes {
+            bin,
+            text,
+            location,
+        }
+    }
+}
+
+/// Files of a dist, either in a directory on disk or already in memory.
+enum DistSource {
+    Dir(PathBuf),
+    Files(BTreeMap<String, Vec<u8>>),
+}
+
+impl DistSource {
+    /// Opens a dist directory, or reads a single-file bundle.
+    fn open(dist: &Path) -> Result<Self> {
+        if !dist.is_file() {
+            return Ok(Self::Dir(dist.to_path_buf()));
+        }
+
+        let bundle = Bundle::read_from_file(dist).map_err(|err| {
+            HostError::InvalidManifest(format!("invalid bundle {}: {err}", dist.display()))
+        })?;
+        Ok(Self::from_bundle(&bundle))
+    }
+
+    fn from_bundle(bundle: &Bundle) -> Self {
+        Self::Files(
+            bundle
+                .entries()
+                .iter()
+                .map(|entry| (entry.name.clone(), entry.contents.clone()))
+                .collect(),
+        )
+    }
+
+    fn dist_dir(&self) -> Option<PathBuf> {
+        match self {
+            Self::Dir(dist_dir) => Some(dist_dir.clone()),
+            Self::Files(_) => None,
+        }
+    }
+
+    /// Where `name` lives, for error messages.
+    fn location(&self, name: &str) -> String {
+        match self {
+            Self::Dir(dist_dir) => dist_dir.join(name).display().to_string(),
+            Self::Files(_) => name.to_string(),
+        }
+    }
+
+    /// Contents of `name`, or `None` if the dist has no such file.
+    fn read(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        match self {
+            Self::Dir(dist_dir) => {
+                let path = dist_dir.join(name);
+                if !path.exists() {
+                    return Ok(None);
+                }
+                Ok(Some(std::fs::read(path)?))
+            }
+            Self::Files(files) => Ok(files.get(name).cloned()),
+        }
+    }
+
+    fn read_required(&self, name: &str) -> Result<Vec<u8>> {
+        self.read(name)?.ok_or_else(|| {
+            HostError::InvalidManifest(format!("missing artifact: {}", self.location(name)))
+        })
+    }
+}
+
+/// Checks every manifest artifact against its digest, returning their contents keyed by path
+/// along with the image id they yield.
+fn verify_manifest_artifacts(
+    manifest: &Manifest,
+    source: &DistSource,
+) -> Result<(BTreeMap<String, Vec<u8>>, ImageId)> {
+    let entries = [
+        ("bin.sha256".to_string(), &manifest.bin),
+        ("elf.sha256".to_string(), &manifest.elf),
+        ("text.sha256".to_string(), &manifest.text),
+    ]
+    .into_iter()
+    .chain(
+        manifest
+            .artifacts
+            .iter()
+            .map(|(name, artifact)| (format!("artifacts.{name}.sha256"), artifact)),
+    );
+
+    let mut files = BTreeMap::new();
+    for (field_name, entry) in entries {
+        let contents = match source {
+            DistSource::Dir(dist_dir) => {
+                let path = dist_dir.join(&entry.path);
+                if !path.exists() {
+                    return Err(HostError::InvalidManifest(format!(
+                        "missing artifact: {}",
+                        path.display()
+                    )));
+                }
+                verify_manifest_artifact_sha256(&path, &field_name, &entry.sha256)?
+            }
+            DistSource::Files(_) => {
+                let contents = source.read_required(&entry.path)?;
+                verify_artifact_sha256(&contents, &entry.path, &field_name, &entry.sha256)?;
+                contents
+            }
+        };
+        files.insert(entry.path.clone(), contents);
+    }
+
+    let image_id = ImageId::compute(
+        &files[&manifest.bin.path],
+        &files[&manifest.text.path],
+        MachineConfig::default(),
+    );
+    verify_manifest_image_id(manifest.image_id, image_id)?;
+    Ok((files, image_id))
+}
+
+/// SDK crates shared by guest and host, with the versions this host links.
+///
+/// Guest-only crates (`airbender-sdk`, `airbender-rt`, ...) are not linked by the host, so
+/// there is no version of theirs to compare against; they are released together with these.
+const SHARED_SDK_CRATES: &[(&str, &str)] = &[
+    ("airbender-core", airbender_core::VERSION),
+    ("airbender-codec", airbender_codec::CRATE_VERSION),
+];
+
+/// A guest built against other versions of the shared crates may disagree with the host on the
+/// codec or image-id interfaces.
+fn warn_on_sdk_version_mismatch(manifest: &Manifest) {
+    for (name, expected, version) in sdk_version_mismatches(&manifest.build.sdk_versions) {
+        tracing::warn!(
+            "program `{}` was built against {name} {version}, but this host uses {name} \
+             {expected}; manifest records a {}",
+            manifest.package,
+            manifest.build.provenance()
+        );
     }
 }
 
+fn sdk_version_mismatches(
+    recorded: &BTreeMap<String, String>,
+) -> impl Iterator<Item = (&'static str, &'static str, &String)> {
+    SHARED_SDK_CRATES
+        .iter()
+        .filter_map(move |&(name, expected)| {
+            recorded
+                .get(name)
+                .filter(|version| *version != expected)
+                .map(|version| (name, expected, version))
+        })
+}
+
+/// Reads `path` and checks it against `expected_hex`, returning its contents.
 fn verify_manifest_artifact_sha256(
     path: &Path,
     field_name: &str,
     expected_hex: &str,
+) -> Result<Vec<u8>> {
+    let contents = std::fs::read(path)?;
+    verify_artifact_sha256(
+        &contents,
+        &path.display().to_string(),
+        field_name,
+        expected_hex,
+    )?;
+    Ok(contents)
+}
+
+fn verify_artifact_sha256(
+    contents: &[u8],
+    location: &str,
+    field_name: &str,
+    expected_hex: &str,
 ) -> Result<()> {
     if expected_hex.is_empty() {
         return Err(HostError::InvalidManifest(format!(
@@ -132,33 +428,46 @@ fn verify_manifest_artifact_sha256(
         )));
     }
 
-    let actual_hex = sha256_file_hex(path)?;
+    let actual_hex = sha256_hex(contents);
     if !expected_hex.eq_ignore_ascii_case(&actual_hex) {
         return Err(HostError::InvalidManifest(format!(
-            "`{field_name}` mismatch for {}: expected `{expected_hex}`, got `{actual_hex}`",
-            path.display()
+            "`{field_name}` mismatch for {location}: expected `{expected_hex}`, got `{actual_hex}`"
         )));
     }
 
     Ok(())
 }
 
-fn sha256_file_hex(path: &Path) -> Result<String> {
-    let bytes = std::fs::read(path)?;
-    let digest = sha2::Sha256::digest(bytes);
-    let mut encoded = String::with_capacity(digest.len() * 2);
-    for byte in digest {
-        use std::fmt::Write as _;
-        write!(&mut encoded, "{byte:02x}").expect("writing to string cannot fail");
+/// Manifests written by older tooling carry no image id; those are accepted as-is.
+fn verify_manifest_image_id(expected: Option<ImageId>, actual: ImageId) -> Result<()> {
+    match expected {
+        Some(expected) if expected != actual => Err(HostError::InvalidManifest(format!(
+            "`image_id` mismatch: expected `{expected}`, got `{actual}`"
+        ))),
+        _ => Ok(()),
     }
+}
+
+fn parse_manifest(bytes: &[u8]) -> Result<Manifest> {
+    std::str::from_utf8(bytes)
+        .map_err(|err| HostError::InvalidManifest(format!("manifest is not UTF-8: {err}")))
+        .and_then(|content| {
+            Manifest::parse(content).map_err(|err| HostError::InvalidManifest(err.to_string()))
+        })
+}
 
-    Ok(encoded)
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(&sha2::Sha256::digest(bytes))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn sha256_file_hex(path: &Path) -> Result<String> {
+        Ok(sha256_hex(&std::fs::read(path)?))
+    }
+
     #[test]
     fn verifies_matching_manifest_digest() {
         let temp_file = unique_temp_file_path("matching-digest");
@@ -196,6 +505,218 @@ mod tests {
         std::fs::remove_file(&temp_file).expect("remove test file");
     }
 
+    #[test]
+    fn rejects_mismatching_manifest_image_id() {
+        let actual = ImageId::from_bytes([1; 32]);
+        verify_manifest_image_id(None, actual).expect("legacy manifests must pass");
+        verify_manifest_image_id(Some(actual), actual).expect("matching image id must pass");
+
+        let err = verify_manifest_image_id(Some(ImageId::from_bytes([2; 32])), actual)
+            .expect_err("image id verification must fail for mismatching id");
+        assert!(err.to_string().contains("`image_id` mismatch"));
+    }
+
+    #[test]
+    fn compares_each_shared_crate_with_its_own_version() {
+        let recorded = BTreeMap::from([
+            (
+                "airbender-core".to_string(),
+                airbender_core::VERSION.to_string(),
+            ),
+            ("airbender-codec".to_string(), "0.0.1-other".to_string()),
+            ("airbender-sdk".to_string(), "0.0.1-other".to_string()),
+        ]);
+        assert_eq!(
+            sdk_version_mismatches(&recorded).collect::<Vec<_>>(),
+            [(
+                "airbender-codec",
+                airbender_codec::CRATE_VERSION,
+                &"0.0.1-other".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn load_verified_requires_trusted_manifest_signature() {
+        use airbender_core::host::signature::ManifestSigningKey;
+
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let dist_dir = temp_dir.path().join("dist");
+        write_test_dist(&dist_dir);
+        let key = ManifestSigningKey::from_seed([1; 32]);
+        let other_key = ManifestSigningKey::from_seed([2; 32]).public_key();
+
+        let err = Program::load_verified(&dist_dir, &[key.public_key()])
+            .expect_err("unsigned dist must be rejected");
+        assert!(err.to_string().contains("missing manifest signature"));
+
+        let manifest_path = dist_dir.join("manifest.toml");
+        let signature_path = dist_dir.join(MANIFEST_SIGNATURE_FILE);
+        key.sign(&std::fs::read(&manifest_path).expect("read manifest"))
+            .write_to_file(&signature_path)
+            .expect("write signature");
+        let program = Program::load_verified(&dist_dir, &[other_key, key.public_key()])
+            .expect("signed dist must load");
+        assert_eq!(
+            program.image_id(),
+            Program::load(&dist_dir).unwrap().image_id()
+        );
+
+        let err = Program::load_verified(&dist_dir, &[other_key])
+            .expect_err("untrusted signer must be rejected");
+        assert!(err.to_string().contains("untrusted key"));
+
+        let manifest = std::fs::read_to_string(&manifest_path).expect("read manifest");
+        std::fs::write(&manifest_path, manifest.replace("demo", "evil")).expect("edit manifest");
+        let err = Program::load_verified(&dist_dir, &[key.public_key()])
+            .expect_err("edited manifest must be rejected");
+        assert!(err.to_string().contains("signature is invalid"));
+    }
+
+    #[test]
+    fn verifies_additional_manifest_artifacts() {
+        use airbender_core::host::manifest::ArtifactEntry;
+
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let dist_dir = temp_dir.path().join("dist");
+        write_test_dist(&dist_dir);
+        let manifest_path = dist_dir.join("manifest.toml");
+        let mut manifest = Manifest::read_from_file(&manifest_path).expect("read manifest");
+        std::fs::write(dist_dir.join("app.sym"), b"symbols").expect("write extra artifact");
+        manifest.artifacts.insert(
+            "symbols".to_string(),
+            ArtifactEntry {
+                path: "app.sym".to_string(),
+                sha256: sha256_hex(b"symbols"),
+            },
+        );
+        manifest
+            .write_to_file(&manifest_path)
+            .expect("write manifest");
+        Program::load(&dist_dir).expect("matching extra artifact must load");
+        let bundle = Bundle::from_dist_dir(&dist_dir).expect("pack bundle");
+        let program = Program::from_bundle_bytes(&bundle.to_bytes()).expect("bundle must load");
+        assert_eq!(program.artifact("symbols"), Some(&b"symbols"[..]));
+
+        std::fs::write(dist_dir.join("app.sym"), b"tampered").expect("tamper extra artifact");
+        let err = Program::load(&dist_dir).expect_err("tampered extra artifact must be rejected");
+        assert!(err
+            .to_string()
+            .contains("artifacts.symbols.sha256` mismatch"));
+
+        std::fs::remove_file(dist_dir.join("app.sym")).expect("remove extra artifact");
+        let err = Program::load(&dist_dir).expect_err("missing extra artifact must be rejected");
+        assert!(err.to_string().contains("missing artifact"));
+    }
+
+    #[test]
+    fn loads_single_file_bundles_with_manifest_checks() {
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let dist_dir = temp_dir.path().join("dist");
+        write_test_dist(&dist_dir);
+        let bundle_path = dist_dir.with_extension("airbundle");
+        let bundle = Bundle::from_dist_dir(&dist_dir).expect("pack bundle");
+        bundle.write_to_file(&bundle_path).expect("write bundle");
+
+        let program = Program::load(&bundle_path).expect("bundle must load");
+        assert_eq!(
+            program.image_id(),
+            Program::load(&dist_dir).expect("dist must load").image_id()
+        );
+        assert_eq!(program.bin(), [1; 8]);
+        assert!(program.dist_dir().is_none());
+
+        let mut entries = bundle.entries().to_vec();
+        let bin = entries
+            .iter_mut()
+            .find(|entry| entry.name == "app.bin")
+            .expect("bundle has app.bin");
+        bin.contents[0] ^= 1;
+        Bundle::from_entries(entries)
+            .expect("repack bundle")
+            .write_to_file(&bundle_path)
+            .expect("write tampered bundle");
+        let err = Program::load(&bundle_path).expect_err("tampered bundle must be rejected");
+        assert!(err.to_string().contains("bin.sha256` mismatch"));
+        assert!(err
+            .to_string()
+            .contains("manifest records a release build of commit abc123"));
+
+        std::fs::write(&bundle_path, b"garbage").expect("write garbage bundle");
+        let err = Program::load(&bundle_path).expect_err("garbage must be rejected");
+        assert!(err.to_string().contains("invalid bundle"));
+    }
+
+    #[test]
+    fn loads_programs_from_memory() {
+        let program = crate::include_program!("../tests/fixtures/app").expect("embedded program");
+        let on_disk = Program::load(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/app"))
+            .expect("fixture dist must load");
+        assert_eq!(program.image_id(), on_disk.image_id());
+        assert_eq!(program.manifest(), on_disk.manifest());
+        assert!(program.dist_dir().is_none());
+        assert!(program.app_text().is_none());
+        assert_eq!(program.text(), on_disk.text());
+
+        let dist_dir = on_disk.dist_dir().expect("fixture has a dist dir");
+        let manifest = std::fs::read(dist_dir.join("manifest.toml")).expect("manifest");
+        let bin = std::fs::read(on_disk.app_bin().unwrap()).expect("read app.bin");
+        assert_eq!(bin, on_disk.bin());
+        let err = Program::from_bytes(&manifest, &bin, on_disk.elf(), &bin[..4])
+            .expect_err("mismatching text must be rejected");
+        assert!(err.to_string().contains("text.sha256` mismatch"));
+
+        let bundle = Bundle::from_dist_dir(dist_dir).expect("pack bundle");
+        let from_bundle = Program::from_bundle_bytes(&bundle.to_bytes()).expect("bundle program");
+        assert_eq!(from_bundle.image_id(), on_disk.image_id());
+        assert!(Program::from_bundle_bytes(b"garbage").is_err());
+
+        let clone = from_bundle.clone();
+        drop(from_bundle);
+        assert_eq!(clone.bin(), on_disk.bin());
+    }
+
+    fn write_test_dist(dist_dir: &Path) {
+        use airbender_core::host::manifest::{ArtifactEntry, BuildMetadata, Profile};
+
+        std::fs::create_dir_all(dist_dir).expect("create dist dir");
+        let artifact = |name: &str, contents: &[u8]| {
+            let path = dist_dir.join(name);
+            std::fs::write(&path, contents).expect("write artifact");
+            ArtifactEntry {
+                path: name.to_string(),
+                sha256: sha256_file_hex(&path).expect("hash artifact"),
+            }
+        };
+        let bin = artifact("app.bin", &[1; 8]);
+        let elf = artifact("app.elf", &[2; 8]);
+        let text = artifact("app.text", &[3; 8]);
+        let image_id = ImageId::compute(&[1; 8], &[3; 8], MachineConfig::default());
+        Manifest {
+            package: "demo".to_string(),
+            bin_name: None,
+            manifest: airbender_core::host::manifest::MANIFEST_VERSION_V2.to_string(),
+            codec: format!("v{}", airbender_codec::AIRBENDER_CODEC_V0),
+            target: None,
+            image_id: Some(image_id),
+            bin,
+            elf,
+            text,
+            artifacts: Default::default(),
+            interface: None,
+            build: BuildMetadata {
+                profile: Profile::Release,
+                git_branch: "main".to_string(),
+                git_commit: "abc123".to_string(),
+                is_dirty: false,
+                ..BuildMetadata::new(Profile::Release)
+            },
+            size: None,
+        }
+        .write_to_file(&dist_dir.join("manifest.toml"))
+        .expect("write manifest");
+    }
+
     fn unique_temp_file_path(label: &str) -> PathBuf {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -0,0 +1,50 @@
+use super::{ProveResult, Prover};
+use crate::error::{HostError, Result};
+use crate::worker::{JobFuture, Worker};
+
+/// Async host prover interface.
+///
+/// Proving is offloaded to a dedicated thread, so awaiting the returned future
+/// does not block the async executor.
+pub trait AsyncProver {
+    fn prove(&self, input_words: &[u32]) -> JobFuture<ProveResult>;
+}
+
+/// Prover owned by a dedicated worker thread.
+///
+/// Jobs are executed one at a time in submission order. Dropping a [`JobFuture`]
+/// before its job starts cancels the job.
+///
+/// ## Poisoning
+///
+/// If proving panics, the worker thread is disposed of and the worker becomes
+/// poisoned: all pending and future jobs resolve to an error.
+pub struct ProverWorker {
+    worker: Worker<Box<dyn Prover>>,
+}
+
+impl ProverWorker {
+    /// Spawn a worker thread and initialize the prover on it.
+    pub async fn spawn<P, F>(init: F) -> Result<Self>
+    where
+        P: Prover + 'static,
+        F: FnOnce() -> Result<P> + Send + 'static,
+    {
+        let worker = Worker::spawn("prover", HostError::Prover, move || {
+            init().map(|prover| Box::new(prover) as Box<dyn Prover>)
+        })
+        .await?;
+        Ok(Self { worker })
+    }
+
+    pub fn is_poisoned(&self) -> bool {
+        self.worker.is_poisoned()
+    }
+}
+
+impl AsyncProver for ProverWorker {
+    fn prove(&self, input_words: &[u32]) -> JobFuture<ProveResult> {
+        let input_words = input_words.to_vec();
+        self.worker.submit(move |prover| prover.prove(&input_words))
+    }
+}
@@ -1,32 +1,49 @@
 use super::{
-    receipt_from_real_proof, resolve_app_bin_path, resolve_text_path, resolve_worker_threads,
-    ProveResult, Prover, DEFAULT_CPU_CYCLE_BOUND, DEFAULT_RAM_BOUND_BYTES,
+    load_image, resolve_worker_threads, ProveResult, Prover, ProverLevel, DEFAULT_CPU_CYCLE_BOUND,
+    DEFAULT_RAM_BOUND_BYTES,
 };
+use crate::artifacts::ProgramSource;
 use crate::error::{HostError, Result};
-use crate::proof::{Proof, RealProof};
+use crate::recursion::{LayerProgram, LayerSetup};
 use crate::runner::{Runner, TranspilerRunnerBuilder};
+use airbender_core::image_id::ImageId;
+use airbender_verifier::{Proof, RealProof, Receipt};
+use execution_utils::prover_examples::prover::worker::Worker;
 use execution_utils::setups;
-use execution_utils::unrolled;
+use execution_utils::unrolled::{self, UnrolledProgramProof};
 use risc_v_simulator::abstractions::non_determinism::QuasiUARTSource;
-use risc_v_simulator::cycle::IMStandardIsaConfigWithUnsignedMulDiv;
+use risc_v_simulator::cycle::{
+    IMStandardIsaConfigWithUnsignedMulDiv, IWithoutByteAccessIsaConfigWithDelegation,
+};
 use riscv_transpiler::common_constants::rom::ROM_BYTE_SIZE;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 /// Builder for creating a configured cached CPU prover.
 pub struct CpuProverBuilder {
-    app_bin_path: PathBuf,
+    source: ProgramSource,
     worker_threads: Option<usize>,
     cycles: Option<usize>,
     ram_bound: Option<usize>,
+    level: ProverLevel,
 }
 
 impl CpuProverBuilder {
     pub fn new(app_bin_path: impl AsRef<Path>) -> Self {
+        Self::from_source(ProgramSource::Path(app_bin_path.as_ref().to_path_buf()))
+    }
+
+    /// Prove in-memory `app.bin` and `app.text` contents.
+    pub fn from_bytes(bin: impl Into<Vec<u8>>, text: impl Into<Vec<u8>>) -> Self {
+        Self::from_source(ProgramSource::bytes(bin, text))
+    }
+
+    pub(crate) fn from_source(source: ProgramSource) -> Self {
         Self {
-            app_bin_path: app_bin_path.as_ref().to_path_buf(),
+            source,
             worker_threads: None,
             cycles: None,
             ram_bound: None,
+            level: ProverLevel::Base,
         }
     }
 
@@ -35,6 +52,7 @@ impl CpuProverBuilder {
         self
     }
 
+    /// Bounds the proven run; proofs still report the cycles actually executed.
     pub fn with_cycles(mut self, cycles: usize) -> Self {
         self.cycles = Some(cycles);
         self
@@ -45,33 +63,54 @@ impl CpuProverBuilder {
         self
     }
 
+    /// Proof layer to produce; defaults to [`ProverLevel::Base`].
+    ///
+    /// Recursion layers run on CPU as well, which is considerably slower than the GPU prover.
+    pub fn with_level(mut self, level: ProverLevel) -> Self {
+        self.level = level;
+        self
+    }
+
     pub fn build(self) -> Result<CpuProver> {
         CpuProver::new(
-            &self.app_bin_path,
+            self.source,
             self.worker_threads,
             self.cycles,
             self.ram_bound,
+            self.level,
         )
     }
+
+    /// Build the prover on a dedicated worker thread and expose it through [`AsyncProver`].
+    ///
+    /// [`AsyncProver`]: super::AsyncProver
+    #[cfg(feature = "async")]
+    pub async fn build_async(self) -> Result<super::ProverWorker> {
+        super::ProverWorker::spawn(move || self.build()).await
+    }
 }
 
 /// CPU prover wrapper that caches padded artifacts and worker threads.
 pub struct CpuProver {
-    app_bin_path: PathBuf,
-    app_text_path: PathBuf,
+    bin: Vec<u8>,
+    text: Vec<u8>,
+    image_id: ImageId,
     binary_u32: Vec<u32>,
     text_u32: Vec<u32>,
     cycles: Option<usize>,
     ram_bound: usize,
-    worker: execution_utils::prover_examples::prover::worker::Worker,
+    level: ProverLevel,
+    recursion: Option<CpuRecursion>,
+    worker: Worker,
 }
 
 impl CpuProver {
     fn new(
-        app_bin_path: &Path,
+        source: ProgramSource,
         worker_threads: Option<usize>,
         cycles: Option<usize>,
         ram_bound: Option<usize>,
+        level: ProverLevel,
     ) -> Result<Self> {
         if matches!(worker_threads, Some(0)) {
             return Err(HostError::Prover(
@@ -79,10 +118,10 @@ impl CpuProver {
             ));
         }
 
-        let app_bin_path = resolve_app_bin_path(app_bin_path)?;
-        let app_text_path = resolve_text_path(&app_bin_path)?;
-        let (_, binary_u32) = setups::read_and_pad_binary(&app_bin_path);
-        let (_, text_u32) = setups::read_and_pad_binary(&app_text_path);
+        let image = load_image(source, None)?;
+        let image_id = image.image_id();
+        let (_, binary_u32) = setups::pad_binary(image.bin.clone());
+        let (_, text_u32) = setups::pad_binary(image.text.clone());
 
         let ram_bound = ram_bound.unwrap_or(DEFAULT_RAM_BOUND_BYTES);
         if ram_bound < ROM_BYTE_SIZE {
@@ -92,17 +131,27 @@ impl CpuProver {
             )));
         }
 
+        let recursion = match level {
+            ProverLevel::Base => None,
+            ProverLevel::RecursionUnrolled | ProverLevel::RecursionUnified => {
+                let app_program = LayerProgram::from_bytes(&image.bin, &image.text);
+                Some(CpuRecursion::new(&app_program, level))
+            }
+        };
+
         let threads = resolve_worker_threads(worker_threads);
-        let worker =
-            execution_utils::prover_examples::prover::worker::Worker::new_with_num_threads(threads);
+        let worker = Worker::new_with_num_threads(threads);
 
         Ok(Self {
-            app_bin_path,
-            app_text_path,
+            bin: image.bin,
+            text: image.text,
+            image_id,
             binary_u32,
             text_u32,
             cycles,
             ram_bound,
+            level,
+            recursion,
             worker,
         })
     }
@@ -110,21 +159,20 @@ impl CpuProver {
 
 impl Prover for CpuProver {
     fn prove(&self, input_words: &[u32]) -> Result<ProveResult> {
+        // The base-layer prover runs up to the bound without reporting where the program
+        // stopped, so the executed cycle count comes from a transpiler run.
+        let execution = TranspilerRunnerBuilder::from_bytes(self.bin.clone(), self.text.clone())
+            .with_cycles(self.cycles.unwrap_or(DEFAULT_CPU_CYCLE_BOUND))
+            .build()?
+            .run(input_words)?;
         let cycles_bound = match self.cycles {
             Some(value) => value,
+            None if execution.reached_end => execution.cycles_executed,
             None => {
-                let cycle_estimator = TranspilerRunnerBuilder::new(&self.app_bin_path)
-                    .with_cycles(DEFAULT_CPU_CYCLE_BOUND)
-                    .with_text_path(&self.app_text_path)
-                    .build()?;
-                let outcome = cycle_estimator.run(input_words)?;
-                if !outcome.reached_end {
-                    return Err(HostError::Prover(format!(
-                        "automatic cycle estimation did not reach program end after {} cycles; provide explicit cycles to prove a bounded run",
-                        outcome.cycles_executed
-                    )));
-                }
-                outcome.cycles_executed
+                return Err(HostError::Prover(format!(
+                    "automatic cycle estimation did not reach program end after {} cycles; provide explicit cycles to prove a bounded run",
+                    execution.cycles_executed
+                )));
             }
         };
         if cycles_bound == 0 {
@@ -134,7 +182,7 @@ impl Prover for CpuProver {
         }
 
         let oracle = QuasiUARTSource::new_with_reads(input_words.to_vec());
-        let inner_proof = unrolled::prove_unrolled_for_machine_configuration_into_program_proof::<
+        let base_proof = unrolled::prove_unrolled_for_machine_configuration_into_program_proof::<
             IMStandardIsaConfigWithUnsignedMulDiv,
         >(
             &self.binary_u32,
@@ -144,13 +192,93 @@ impl Prover for CpuProver {
             self.ram_bound,
             &self.worker,
         );
-        let receipt = receipt_from_real_proof(&inner_proof);
-        let proof = Proof::Real(RealProof::new(super::ProverLevel::Base, inner_proof));
+        let inner_proof = match &self.recursion {
+            Some(recursion) => recursion.prove(&base_proof, self.ram_bound, &self.worker),
+            None => base_proof,
+        };
+        let receipt = Receipt::from_unrolled_proof(&inner_proof);
+        let cycles = execution.cycles_executed as u64;
+        let proof = Proof::Real(RealProof::new(
+            self.level,
+            cycles,
+            self.image_id,
+            inner_proof,
+        ));
 
         Ok(ProveResult {
             proof,
-            cycles: cycles_bound as u64,
+            cycles,
             receipt,
         })
     }
 }
+
+/// Upper bound on cycles executed by a recursion verifier program.
+const RECURSION_CYCLE_BOUND: usize = 1 << 30;
+
+/// Recursion layers proven on top of the base layer.
+///
+/// Setups of the layers feeding into recursion are computed once, when the prover is built.
+struct CpuRecursion {
+    base_setup: LayerSetup,
+    unrolled_program: LayerProgram,
+    unified: Option<UnifiedRecursion>,
+}
+
+struct UnifiedRecursion {
+    unrolled_setup: LayerSetup,
+    program: LayerProgram,
+}
+
+impl CpuRecursion {
+    fn new(app_program: &LayerProgram, level: ProverLevel) -> Self {
+        let base_setup = LayerSetup::base(app_program);
+        let unrolled_program = LayerProgram::recursion_unrolled();
+        let unified = (level == ProverLevel::RecursionUnified).then(|| UnifiedRecursion {
+            unrolled_setup: LayerSetup::recursion_unrolled(&unrolled_program),
+            program: LayerProgram::recursion_unified(),
+        });
+
+        Self {
+            base_setup,
+            unrolled_program,
+            unified,
+        }
+    }
+
+    fn prove(
+        &self,
+        base_proof: &UnrolledProgramProof,
+        ram_bound: usize,
+        worker: &Worker,
+    ) -> UnrolledProgramProof {
+        let oracle = self.base_setup.recursion_oracle(base_proof, true);
+        let unrolled_proof = unrolled::prove_unrolled_for_machine_configuration_into_program_proof::<
+            IWithoutByteAccessIsaConfigWithDelegation,
+        >(
+            &self.unrolled_program.binary_u32,
+            &self.unrolled_program.text_u32,
+            RECURSION_CYCLE_BOUND,
+            oracle,
+            ram_bound,
+            worker,
+        );
+
+        let Some(unified) = &self.unified else {
+            return unrolled_proof;
+        };
+        let oracle = unified
+            .unrolled_setup
+            .recursion_oracle(&unrolled_proof, false);
+        execution_utils::unified_circuit::prove_unified_for_machine_configuration_into_program_proof::<
+            IWithoutByteAccessIsaConfigWithDelegation,
+        >(
+            &unified.program.binary_u32,
+            &unified.program.text_u32,
+            RECURSION_CYCLE_BOUND,
+            oracle,
+            ram_bound,
+            worker,
+        )
+    }
+}
@@ -1,22 +1,39 @@
-use super::{resolve_app_bin_path, ProveResult, Prover};
-use crate::error::Result;
-use crate::proof::{hash_app_bin, hash_input_words, DevProof, Proof};
+use super::{load_image, ProveResult, Prover};
+use crate::artifacts::ProgramSource;
+use crate::dev_key::{load_or_create_dev_key, project_dir_for};
+use crate::error::{HostError, Result};
+use crate::proof::hash_input_words;
 use crate::runner::{Runner, TranspilerRunner, TranspilerRunnerBuilder};
+use airbender_core::image_id::ImageId;
+use airbender_verifier::{DevProof, DevPublicKey, DevSigningKey, Proof};
 use std::path::{Path, PathBuf};
 
 /// Builder for creating a configured development prover.
 pub struct DevProverBuilder {
-    app_bin_path: PathBuf,
+    source: ProgramSource,
     cycles: Option<usize>,
     text_path: Option<PathBuf>,
+    dev_key: Option<DevSigningKey>,
 }
 
 impl DevProverBuilder {
     pub fn new(app_bin_path: impl AsRef<Path>) -> Self {
+        Self::from_source(ProgramSource::Path(app_bin_path.as_ref().to_path_buf()))
+    }
+
+    /// Prove in-memory `app.bin` and `app.text` contents.
+    ///
+    /// Such programs have no project dev key, so they need [`Self::with_dev_key`].
+    pub fn from_bytes(bin: impl Into<Vec<u8>>, text: impl Into<Vec<u8>>) -> Self {
+        Self::from_source(ProgramSource::bytes(bin, text))
+    }
+
+    pub(crate) fn from_source(source: ProgramSource) -> Self {
         Self {
-            app_bin_path: app_bin_path.as_ref().to_path_buf(),
+            source,
             cycles: None,
             text_path: None,
+            dev_key: None,
         }
     }
 
@@ -30,37 +47,78 @@ impl DevProverBuilder {
         self
     }
 
+    /// Sign proofs with `dev_key` instead of the project's key under `.airbender/dev-key`.
+    pub fn with_dev_key(mut self, dev_key: DevSigningKey) -> Self {
+        self.dev_key = Some(dev_key);
+        self
+    }
+
     pub fn build(self) -> Result<DevProver> {
-        DevProver::new(&self.app_bin_path, self.cycles, self.text_path.as_deref())
+        DevProver::new(
+            self.source,
+            self.cycles,
+            self.text_path.as_deref(),
+            self.dev_key,
+        )
+    }
+
+    /// Build the prover on a dedicated worker thread and expose it through [`AsyncProver`].
+    ///
+    /// [`AsyncProver`]: super::AsyncProver
+    #[cfg(feature = "async")]
+    pub async fn build_async(self) -> Result<super::ProverWorker> {
+        super::ProverWorker::spawn(move || self.build()).await
     }
 }
 
 /// Development prover that records transpiler execution metadata instead of generating a zk-proof.
+///
+/// Proofs are signed with a local dev key; see [`crate::load_or_create_dev_key`].
 pub struct DevProver {
-    app_bin_hash: [u8; 32],
+    image_id: ImageId,
     runner: TranspilerRunner,
+    dev_key: DevSigningKey,
 }
 
 impl DevProver {
-    fn new(app_bin_path: &Path, cycles: Option<usize>, text_path: Option<&Path>) -> Result<Self> {
-        let app_bin_path = resolve_app_bin_path(app_bin_path)?;
-        let app_bin_hash = hash_app_bin(&app_bin_path)?;
+    fn new(
+        source: ProgramSource,
+        cycles: Option<usize>,
+        text_path: Option<&Path>,
+        dev_key: Option<DevSigningKey>,
+    ) -> Result<Self> {
+        let image = load_image(source, text_path)?;
+        let image_id = image.image_id();
 
-        let mut runner_builder = TranspilerRunnerBuilder::new(&app_bin_path);
+        let mut runner_builder = TranspilerRunnerBuilder::from_bytes(image.bin, image.text);
         if let Some(cycles) = cycles {
             runner_builder = runner_builder.with_cycles(cycles);
         }
-        if let Some(text_path) = text_path {
-            runner_builder = runner_builder.with_text_path(text_path);
-        }
 
         let runner = runner_builder.build()?;
+        let dev_key = match (dev_key, image.location) {
+            (Some(dev_key), _) => dev_key,
+            (None, Some(app_bin_path)) => load_or_create_dev_key(project_dir_for(&app_bin_path))?,
+            (None, None) => {
+                return Err(HostError::Prover(
+                    "in-memory programs have no project dev key; pass the key with \
+                     `DevProverBuilder::with_dev_key`"
+                        .to_string(),
+                ));
+            }
+        };
 
         Ok(Self {
-            app_bin_hash,
+            image_id,
             runner,
+            dev_key,
         })
     }
+
+    /// Public key of the dev key signing this prover's proofs.
+    pub fn dev_key(&self) -> DevPublicKey {
+        self.dev_key.public_key()
+    }
 }
 
 impl Prover for DevProver {
@@ -69,12 +127,13 @@ impl Prover for DevProver {
         let cycles = execution.cycles_executed as u64;
         let receipt = execution.receipt;
 
-        let proof = Proof::Dev(DevProof {
-            app_bin_hash: self.app_bin_hash,
-            input_words_hash: hash_input_words(input_words),
-            receipt: receipt.clone(),
+        let proof = Proof::Dev(DevProof::sign(
+            self.image_id,
+            hash_input_words(input_words),
+            receipt.clone(),
             cycles,
-        });
+            &self.dev_key,
+        )?);
 
         Ok(ProveResult {
             proof,
@@ -1,28 +1,40 @@
-use super::{
-    base_path, receipt_from_real_proof, resolve_app_bin_path, ProveResult, Prover, ProverLevel,
-};
-use crate::error::{HostError, Result};
-use crate::proof::{Proof, RealProof};
-use execution_utils::unrolled_gpu::UnrolledProver;
+use super::{base_path, load_image, ProveResult, Prover, ProverLevel};
+use crate::artifacts::{ProgramImage, ProgramSource};
+use crate::error::{panic_payload_to_string, HostError, Result};
+use airbender_core::image_id::ImageId;
+use airbender_verifier::{Proof, RealProof, Receipt};
+use execution_utils::unrolled_gpu::{UnrolledProver, UnrolledProverLevel};
 use gpu_prover::execution::prover::ExecutionProverConfiguration;
 use risc_v_simulator::abstractions::non_determinism::QuasiUARTSource;
-use std::any::Any;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{mpsc, Mutex};
 use std::thread::JoinHandle;
+use tempfile::TempDir;
 
 /// Builder for creating a configured cached GPU prover.
 pub struct GpuProverBuilder {
-    app_bin_path: PathBuf,
+    source: ProgramSource,
     worker_threads: Option<usize>,
     level: ProverLevel,
 }
 
 impl GpuProverBuilder {
     pub fn new(app_bin_path: impl AsRef<Path>) -> Self {
+        Self::from_source(ProgramSource::Path(app_bin_path.as_ref().to_path_buf()))
+    }
+
+    /// Prove in-memory `app.bin` and `app.text` contents.
+    ///
+    /// The GPU prover reads its program from files, so the contents are written to a private
+    /// temporary directory that lives as long as the prover.
+    pub fn from_bytes(bin: impl Into<Vec<u8>>, text: impl Into<Vec<u8>>) -> Self {
+        Self::from_source(ProgramSource::bytes(bin, text))
+    }
+
+    pub(crate) fn from_source(source: ProgramSource) -> Self {
         Self {
-            app_bin_path: app_bin_path.as_ref().to_path_buf(),
+            source,
             worker_threads: None,
             level: ProverLevel::RecursionUnified,
         }
@@ -39,7 +51,15 @@ impl GpuProverBuilder {
     }
 
     pub fn build(self) -> Result<GpuProver> {
-        GpuProver::new(&self.app_bin_path, self.worker_threads, self.level)
+        GpuProver::new(self.source, self.worker_threads, self.level)
+    }
+
+    /// Build the prover on a dedicated worker thread and expose it through [`AsyncProver`].
+    ///
+    /// [`AsyncProver`]: super::AsyncProver
+    #[cfg(feature = "async")]
+    pub async fn build_async(self) -> Result<super::ProverWorker> {
+        super::ProverWorker::spawn(move || self.build()).await
     }
 }
 
@@ -69,15 +89,20 @@ enum WorkerCommand {
 }
 
 impl GpuProver {
-    fn new(app_bin_path: &Path, worker_threads: Option<usize>, level: ProverLevel) -> Result<Self> {
+    fn new(
+        source: ProgramSource,
+        worker_threads: Option<usize>,
+        level: ProverLevel,
+    ) -> Result<Self> {
         if matches!(worker_threads, Some(0)) {
             return Err(HostError::Prover(
                 "worker thread count must be greater than zero".to_string(),
             ));
         }
 
-        let app_bin_path = resolve_app_bin_path(app_bin_path)?;
-        let (command_tx, worker_handle) = spawn_worker(app_bin_path, worker_threads, level)?;
+        let image = load_image(source, None)?;
+        let image_id = image.image_id();
+        let (command_tx, worker_handle) = spawn_worker(image, image_id, worker_threads, level)?;
 
         Ok(Self {
             command_tx,
@@ -159,7 +184,8 @@ impl Drop for GpuProver {
 }
 
 fn spawn_worker(
-    app_bin_path: PathBuf,
+    image: ProgramImage,
+    image_id: ImageId,
     worker_threads: Option<usize>,
     level: ProverLevel,
 ) -> Result<(mpsc::Sender<WorkerCommand>, JoinHandle<()>)> {
@@ -168,7 +194,7 @@ fn spawn_worker(
 
     let worker_handle = std::thread::Builder::new()
         .name("airbender-gpu-prover".to_string())
-        .spawn(move || gpu_worker_loop(command_rx, init_tx, app_bin_path, worker_threads, level))
+        .spawn(move || gpu_worker_loop(command_rx, init_tx, image, image_id, worker_threads, level))
         .map_err(|err| {
             HostError::Prover(format!("failed to spawn GPU prover worker thread: {err}"))
         })?;
@@ -195,14 +221,15 @@ fn spawn_worker(
 fn gpu_worker_loop(
     command_rx: mpsc::Receiver<WorkerCommand>,
     init_tx: mpsc::Sender<Result<()>>,
-    app_bin_path: PathBuf,
+    image: ProgramImage,
+    image_id: ImageId,
     worker_threads: Option<usize>,
     level: ProverLevel,
 ) {
     // Keep all prover state inside this dedicated thread so a panic does not unwind
     // through host-call boundaries or require `AssertUnwindSafe`.
-    let prover =
-        match create_unrolled_prover(&app_bin_path, worker_threads, level.as_unrolled_level()) {
+    let (prover, _staged) =
+        match create_unrolled_prover(&image, worker_threads, unrolled_level(level)) {
             Ok(prover) => prover,
             Err(err) => {
                 let _ = init_tx.send(Err(err));
@@ -223,8 +250,8 @@ fn gpu_worker_loop(
                 let oracle = QuasiUARTSource::new_with_reads(input_words);
                 // TODO: we use `batch 0` for all the jobs, which can cause issues when generating multiple proofs in parallel.
                 let (inner_proof, cycles) = prover.prove(0, oracle);
-                let receipt = receipt_from_real_proof(&inner_proof);
-                let proof = Proof::Real(RealProof::new(level, inner_proof));
+                let receipt = Receipt::from_unrolled_proof(&inner_proof);
+                let proof = Proof::Real(RealProof::new(level, cycles, image_id, inner_proof));
                 let result = Ok(ProveResult {
                     proof,
                     cycles,
@@ -237,27 +264,41 @@ fn gpu_worker_loop(
     }
 }
 
-fn panic_payload_to_string(payload: Box<dyn Any + Send + 'static>) -> String {
-    if let Some(message) = payload.downcast_ref::<String>() {
-        return message.clone();
-    }
-    if let Some(message) = payload.downcast_ref::<&str>() {
-        return (*message).to_string();
+fn unrolled_level(level: ProverLevel) -> UnrolledProverLevel {
+    match level {
+        ProverLevel::Base => UnrolledProverLevel::Base,
+        ProverLevel::RecursionUnrolled => UnrolledProverLevel::RecursionUnrolled,
+        ProverLevel::RecursionUnified => UnrolledProverLevel::RecursionUnified,
     }
-
-    "unknown panic payload".to_string()
 }
 
+/// Creates the upstream prover, which reads `<base>.bin` and `<base>.text`; in-memory programs
+/// are first written to a private temporary directory, returned so it outlives the prover.
 fn create_unrolled_prover(
-    app_bin_path: &Path,
+    image: &ProgramImage,
     worker_threads: Option<usize>,
-    level: execution_utils::unrolled_gpu::UnrolledProverLevel,
-) -> Result<UnrolledProver> {
-    let base_path = base_path(app_bin_path)?;
+    level: UnrolledProverLevel,
+) -> Result<(UnrolledProver, Option<TempDir>)> {
+    let (app_bin_path, staged) = match &image.location {
+        Some(app_bin_path) => (app_bin_path.clone(), None),
+        None => {
+            let staged = tempfile::Builder::new()
+                .prefix("airbender-gpu-prover-")
+                .tempdir()?;
+            let app_bin_path = staged.path().join("app.bin");
+            std::fs::write(&app_bin_path, &image.bin)?;
+            std::fs::write(app_bin_path.with_extension("text"), &image.text)?;
+            (app_bin_path, Some(staged))
+        }
+    };
+    let base_path = base_path(&app_bin_path)?;
     let mut configuration = ExecutionProverConfiguration::default();
     if let Some(threads) = worker_threads {
         configuration.max_thread_pool_threads = Some(threads);
         configuration.replay_worker_threads_count = threads;
     }
-    Ok(UnrolledProver::new(&base_path, configuration, level))
+    Ok((
+        UnrolledProver::new(&base_path, configuration, level),
+        staged,
+    ))
 }
@@ -1,18 +1,25 @@
+use crate::artifacts::{ProgramImage, ProgramSource};
 use crate::error::{HostError, Result};
-use crate::proof::Proof;
-use crate::receipt::Receipt;
-use execution_utils::unrolled::UnrolledProgramProof;
+use airbender_verifier::{Proof, Receipt};
 use std::path::{Path, PathBuf};
 
+#[cfg(feature = "async")]
+mod async_prover;
 mod cpu_prover;
 mod dev_prover;
 #[cfg(feature = "gpu-prover")]
 mod gpu_prover;
+#[cfg(feature = "remote")]
+mod remote_prover;
 
+#[cfg(feature = "async")]
+pub use self::async_prover::{AsyncProver, ProverWorker};
 pub use self::cpu_prover::{CpuProver, CpuProverBuilder};
 pub use self::dev_prover::{DevProver, DevProverBuilder};
 #[cfg(feature = "gpu-prover")]
 pub use self::gpu_prover::{GpuProver, GpuProverBuilder};
+#[cfg(feature = "remote")]
+pub use self::remote_prover::{RemoteProver, RemoteProverBuilder};
 
 pub(super) const DEFAULT_RAM_BOUND_BYTES: usize = 1 << 30;
 pub(super) const DEFAULT_CPU_CYCLE_BOUND: usize = u32::MAX as usize;
@@ -22,29 +29,7 @@ pub trait Prover {
     fn prove(&self, input_words: &[u32]) -> Result<ProveResult>;
 }
 
-/// Maximum proof layer generated by the prover.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
-pub enum ProverLevel {
-    Base,
-    RecursionUnrolled,
-    #[default]
-    RecursionUnified,
-}
-
-impl ProverLevel {
-    #[cfg(feature = "gpu-prover")]
-    pub fn as_unrolled_level(self) -> execution_utils::unrolled_gpu::UnrolledProverLevel {
-        match self {
-            ProverLevel::Base => execution_utils::unrolled_gpu::UnrolledProverLevel::Base,
-            ProverLevel::RecursionUnrolled => {
-                execution_utils::unrolled_gpu::UnrolledProverLevel::RecursionUnrolled
-            }
-            ProverLevel::RecursionUnified => {
-                execution_utils::unrolled_gpu::UnrolledProverLevel::RecursionUnified
-            }
-        }
-    }
-}
+pub use airbender_verifier::ProverLevel;
 
 /// Proof and receipt produced by `prove`.
 #[derive(Clone, Debug)]
@@ -54,6 +39,33 @@ pub struct ProveResult {
     pub receipt: Receipt,
 }
 
+/// Loads the program a prover builder was created from; `text_path` replaces its `app.text`.
+pub(super) fn load_image(source: ProgramSource, text_path: Option<&Path>) -> Result<ProgramImage> {
+    let mut image = match source {
+        ProgramSource::Path(path) => {
+            let app_bin_path = resolve_app_bin_path(&path)?;
+            let app_text_path = match text_path {
+                Some(text_path) => text_path.to_path_buf(),
+                None => resolve_text_path(&app_bin_path)?,
+            };
+            return Ok(ProgramImage::read(&app_bin_path, &app_text_path)?);
+        }
+        ProgramSource::Bytes {
+            bin,
+            text,
+            location,
+        } => ProgramImage {
+            bin,
+            text,
+            location,
+        },
+    };
+    if let Some(text_path) = text_path {
+        image.text = std::fs::read(text_path)?;
+    }
+    Ok(image)
+}
+
 pub(super) fn resolve_app_bin_path(path: &Path) -> Result<PathBuf> {
     let base_path = base_path(path)?;
     let app_bin_path = PathBuf::from(format!("{base_path}.bin"));
@@ -112,16 +124,3 @@ pub(super) fn resolve_worker_threads(worker_threads: Option<usize>) -> usize {
         })
         .unwrap_or(1)
 }
-
-pub(super) fn receipt_from_real_proof(proof: &UnrolledProgramProof) -> Receipt {
-    let mut registers = [0u32; 32];
-    for (idx, reg) in proof
-        .register_final_values
-        .iter()
-        .take(registers.len())
-        .enumerate()
-    {
-        registers[idx] = reg.value;
-    }
-    Receipt::from_registers(registers)
-}
@@ -0,0 +1,459 @@
+use super::{ProveResult, Prover};
+use crate::error::{HostError, Result};
+use crate::service::{
+    ErrorResponse, JobState, JobStatus, ProgramInfo, SubmitJobRequest, SubmitJobResponse,
+    SERVICE_API_PREFIX,
+};
+use airbender_verifier::Proof;
+use serde::de::DeserializeOwned;
+use std::time::{Duration, Instant};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Large enough for real proofs, small enough that a misbehaving service cannot exhaust memory.
+const DEFAULT_MAX_RESPONSE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Builder for a prover backed by a remote proving service (`cargo airbender serve`).
+pub struct RemoteProverBuilder {
+    endpoint: String,
+    program: String,
+    poll_interval: Duration,
+    request_timeout: Duration,
+    job_timeout: Option<Duration>,
+    max_response_bytes: u64,
+}
+
+impl RemoteProverBuilder {
+    /// `endpoint` is the service root (for example `http://127.0.0.1:3030`), and `program`
+    /// is the name of a program served by it.
+    pub fn new(endpoint: impl Into<String>, program: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            program: program.into(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            job_timeout: None,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+        }
+    }
+
+    /// Delay between job status requests.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Timeout applied to every individual HTTP request.
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Maximum time to wait for a submitted job to finish; unlimited by default.
+    pub fn with_job_timeout(mut self, job_timeout: Duration) -> Self {
+        self.job_timeout = Some(job_timeout);
+        self
+    }
+
+    /// Largest response body, including the encoded proof, accepted from the service;
+    /// 256 MiB by default.
+    pub fn with_max_response_size(mut self, max_response_bytes: u64) -> Self {
+        self.max_response_bytes = max_response_bytes;
+        self
+    }
+
+    /// Connects to the service and checks that it serves the requested program.
+    pub fn build(self) -> Result<RemoteProver> {
+        RemoteProver::new(self)
+    }
+
+    /// Build the prover on a dedicated worker thread and expose it through [`AsyncProver`].
+    ///
+    /// [`AsyncProver`]: super::AsyncProver
+    #[cfg(feature = "async")]
+    pub async fn build_async(self) -> Result<super::ProverWorker> {
+        super::ProverWorker::spawn(move || self.build()).await
+    }
+}
+
+/// Prover that delegates proving to a remote proving service over HTTP.
+pub struct RemoteProver {
+    agent: ureq::Agent,
+    base_url: String,
+    program: ProgramInfo,
+    poll_interval: Duration,
+    job_timeout: Option<Duration>,
+    max_response_bytes: u64,
+}
+
+impl RemoteProver {
+    fn new(builder: RemoteProverBuilder) -> Result<Self> {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .timeout_global(Some(builder.request_timeout))
+            .build()
+            .into();
+        let base_url = format!(
+            "{}{SERVICE_API_PREFIX}",
+            builder.endpoint.trim_end_matches('/')
+        );
+
+        let programs: Vec<ProgramInfo> = read_json(
+            get(&agent, &format!("{base_url}/programs"))?,
+            builder.max_response_bytes,
+        )?;
+        let program = programs
+            .into_iter()
+            .find(|program| program.name == builder.program)
+            .ok_or_else(|| {
+                HostError::Prover(format!(
+                    "program `{}` is not served by {}",
+                    builder.program, builder.endpoint
+                ))
+            })?;
+
+        Ok(Self {
+            agent,
+            base_url,
+            program,
+            poll_interval: builder.poll_interval,
+            job_timeout: builder.job_timeout,
+            max_response_bytes: builder.max_response_bytes,
+        })
+    }
+
+    /// Program description reported by the service.
+    pub fn program(&self) -> &ProgramInfo {
+        &self.program
+    }
+
+    fn submit(&self, input_words: &[u32]) -> Result<u64> {
+        let request = SubmitJobRequest {
+            input_words: input_words.to_vec(),
+        };
+        let body = serde_json::to_vec(&request)
+            .map_err(|err| HostError::Prover(format!("failed to encode job request: {err}")))?;
+        let url = format!("{}/programs/{}/jobs", self.base_url, self.program.name);
+        let response = self
+            .agent
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .send(&body[..])
+            .map_err(|err| request_error(&url, err))?;
+        let response: SubmitJobResponse = read_json(response, self.max_response_bytes)?;
+        Ok(response.job_id)
+    }
+
+    fn wait_for_job(&self, job_id: u64) -> Result<JobStatus> {
+        let started_at = Instant::now();
+        let url = format!("{}/jobs/{job_id}", self.base_url);
+        loop {
+            let status: JobStatus = read_json(get(&self.agent, &url)?, self.max_response_bytes)?;
+            if status.state.is_finished() {
+                return Ok(status);
+            }
+            if let Some(job_timeout) = self.job_timeout {
+                if started_at.elapsed() >= job_timeout {
+                    return Err(HostError::Prover(format!(
+                        "remote job {job_id} did not finish within {job_timeout:?}"
+                    )));
+                }
+            }
+            std::thread::sleep(self.poll_interval);
+        }
+    }
+}
+
+impl Prover for RemoteProver {
+    fn prove(&self, input_words: &[u32]) -> Result<ProveResult> {
+        let job_id = self.submit(input_words)?;
+        tracing::debug!("submitted remote job {job_id} for `{}`", self.program.name);
+
+        let status = self.wait_for_job(job_id)?;
+        if status.state == JobState::Failed {
+            return Err(HostError::Prover(format!(
+                "remote job {job_id} failed: {}",
+                status.error.as_deref().unwrap_or("unknown error")
+            )));
+        }
+        let (Some(cycles), Some(receipt)) = (status.cycles, status.receipt) else {
+            return Err(HostError::Prover(format!(
+                "remote job {job_id} succeeded without cycles or receipt"
+            )));
+        };
+
+        let proof_bytes = read_bytes(
+            get(
+                &self.agent,
+                &format!("{}/jobs/{job_id}/proof", self.base_url),
+            )?,
+            self.max_response_bytes,
+        )?;
+        let proof = Proof::from_bytes(&proof_bytes)
+            .map_err(|err| HostError::Prover(format!("failed to decode remote proof: {err}")))?;
+        if proof.image_id() != self.program.image_id {
+            return Err(HostError::Prover(format!(
+                "remote job {job_id} returned a proof for image id {}, expected {}",
+                proof.image_id(),
+                self.program.image_id
+            )));
+        }
+        if receipt.output != proof.output() {
+            return Err(HostError::Prover(format!(
+                "remote job {job_id} returned a receipt whose output does not match the proof"
+            )));
+        }
+        if let Proof::Dev(dev_proof) = &proof {
+            if dev_proof.input_words_hash != crate::proof::hash_input_words(input_words) {
+                return Err(HostError::Prover(format!(
+                    "remote job {job_id} returned a proof for different input words"
+                )));
+            }
+        }
+
+        Ok(ProveResult {
+            proof,
+            cycles,
+            receipt,
+        })
+    }
+}
+
+type Response = ureq::http::Response<ureq::Body>;
+
+fn get(agent: &ureq::Agent, url: &str) -> Result<Response> {
+    agent.get(url).call().map_err(|err| request_error(url, err))
+}
+
+fn request_error(url: &str, err: ureq::Error) -> HostError {
+    HostError::Prover(format!("request to {url} failed: {err}"))
+}
+
+/// Reads a successful response body of at most `limit` bytes, turning error statuses into
+/// [`HostError::Prover`].
+fn read_bytes(mut response: Response, limit: u64) -> Result<Vec<u8>> {
+    let status = response.status();
+    let body = response
+        .body_mut()
+        .with_config()
+        .limit(limit)
+        .read_to_vec()
+        .map_err(|err| HostError::Prover(format!("failed to read service response: {err}")))?;
+    if status.is_success() {
+        return Ok(body);
+    }
+
+    let message = serde_json::from_slice::<ErrorResponse>(&body)
+        .map(|response| response.error)
+        .unwrap_or_else(|_| String::from_utf8_lossy(&body).into_owned());
+    Err(HostError::Prover(format!(
+        "proving service returned {status}: {message}"
+    )))
+}
+
+fn read_json<T: DeserializeOwned>(response: Response, limit: u64) -> Result<T> {
+    let body = read_bytes(response, limit)?;
+    serde_json::from_slice(&body)
+        .map_err(|err| HostError::Prover(format!("failed to decode service response: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use airbender_core::image_id::ImageId;
+    use airbender_verifier::{DevProof, DevSigningKey, Receipt};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use tiny_http::{Method, Response, Server};
+
+    /// Minimal in-process stand-in for `cargo airbender serve`.
+    ///
+    /// Serves a single program `app`; job status polls report `running` once before
+    /// returning the final state, and jobs fail when the first input word is zero.
+    /// A first input word of 13 makes the proof disagree with the submitted input and
+    /// 99 makes it disagree with the reported receipt.
+    struct MockService {
+        endpoint: String,
+        polls: Arc<AtomicU32>,
+    }
+
+    impl MockService {
+        fn start() -> Self {
+            let server = Server::http("127.0.0.1:0").expect("start mock server");
+            let endpoint = format!("http://{}", server.server_addr());
+            let polls = Arc::new(AtomicU32::new(0));
+            let handler_polls = Arc::clone(&polls);
+            std::thread::spawn(move || {
+                let mut last_input = Vec::new();
+                for mut request in server.incoming_requests() {
+                    let mut body = Vec::new();
+                    request
+                        .as_reader()
+                        .read_to_end(&mut body)
+                        .expect("read request body");
+                    let (status, payload) = match (request.method(), request.url()) {
+                        (Method::Get, "/v1/programs") => (200, programs_json()),
+                        (Method::Post, "/v1/programs/app/jobs") => {
+                            let request: SubmitJobRequest =
+                                serde_json::from_slice(&body).expect("decode job request");
+                            last_input = request.input_words;
+                            (202, br#"{"job_id":7}"#.to_vec())
+                        }
+                        (Method::Get, "/v1/jobs/7") => {
+                            let poll = handler_polls.fetch_add(1, Ordering::SeqCst);
+                            (200, job_status_json(poll, &last_input))
+                        }
+                        (Method::Get, "/v1/jobs/7/proof") => (200, dev_proof_bytes(&last_input)),
+                        _ => (404, br#"{"error":"unknown endpoint"}"#.to_vec()),
+                    };
+                    let _ = request.respond(Response::from_data(payload).with_status_code(status));
+                }
+            });
+
+            Self { endpoint, polls }
+        }
+    }
+
+    #[test]
+    fn proves_through_remote_service() {
+        let service = MockService::start();
+        let prover = RemoteProverBuilder::new(&service.endpoint, "app")
+            .with_poll_interval(Duration::from_millis(1))
+            .build()
+            .expect("build remote prover");
+        assert_eq!(prover.program().backend, "dev");
+
+        let result = prover.prove(&[42, 1]).expect("remote proof");
+        assert_eq!(result.cycles, 100);
+        assert_eq!(result.receipt.output[0], 42);
+        match result.proof {
+            Proof::Dev(proof) => assert_eq!(proof.receipt.output[0], 42),
+            Proof::Real(_) => panic!("mock service returns dev proofs"),
+        }
+        assert!(service.polls.load(Ordering::SeqCst) >= 2);
+    }
+
+    #[test]
+    fn reports_failed_remote_jobs() {
+        let service = MockService::start();
+        let prover = RemoteProverBuilder::new(format!("{}/", service.endpoint), "app")
+            .with_poll_interval(Duration::from_millis(1))
+            .build()
+            .expect("build remote prover");
+
+        let err = match prover.prove(&[0]) {
+            Ok(_) => panic!("failed job must be reported"),
+            Err(err) => err,
+        };
+        assert_eq!(
+            err.to_string(),
+            "prover error: remote job 7 failed: guest panicked"
+        );
+    }
+
+    #[test]
+    fn rejects_proofs_that_disagree_with_the_job() {
+        let service = MockService::start();
+        let prover = RemoteProverBuilder::new(&service.endpoint, "app")
+            .with_poll_interval(Duration::from_millis(1))
+            .build()
+            .expect("build remote prover");
+
+        for (input, expected) in [
+            (13, "returned a proof for different input words"),
+            (
+                99,
+                "returned a receipt whose output does not match the proof",
+            ),
+        ] {
+            let err = match prover.prove(&[input]) {
+                Ok(_) => panic!("inconsistent remote result must be rejected"),
+                Err(err) => err,
+            };
+            assert!(err.to_string().contains(expected), "{err}");
+        }
+    }
+
+    #[test]
+    fn rejects_oversized_responses() {
+        let service = MockService::start();
+        let err = match RemoteProverBuilder::new(&service.endpoint, "app")
+            .with_max_response_size(16)
+            .build()
+        {
+            Ok(_) => panic!("oversized program listing must be rejected"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("failed to read service response"));
+    }
+
+    #[test]
+    fn rejects_unknown_programs() {
+        let service = MockService::start();
+        let err = match RemoteProverBuilder::new(&service.endpoint, "missing").build() {
+            Ok(_) => panic!("unknown program must be rejected"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("program `missing` is not served"));
+    }
+
+    fn programs_json() -> Vec<u8> {
+        let programs = vec![ProgramInfo {
+            name: "app".to_string(),
+            package: "app-guest".to_string(),
+            image_id: ImageId::from_bytes([3; 32]),
+            backend: "dev".to_string(),
+            level: None,
+        }];
+        serde_json::to_vec(&programs).expect("encode programs")
+    }
+
+    fn job_status_json(poll: u32, input_words: &[u32]) -> Vec<u8> {
+        let mut status = JobStatus {
+            id: 7,
+            program: "app".to_string(),
+            image_id: ImageId::from_bytes([3; 32]),
+            state: JobState::Running,
+            cycles: None,
+            receipt: None,
+            error: None,
+        };
+        if poll > 0 {
+            if input_words.first() == Some(&0) {
+                status.state = JobState::Failed;
+                status.error = Some("guest panicked".to_string());
+            } else {
+                status.state = JobState::Succeeded;
+                status.cycles = Some(100);
+                status.receipt = Some(receipt(input_words));
+            }
+        }
+        serde_json::to_vec(&status).expect("encode job status")
+    }
+
+    fn dev_proof_bytes(input_words: &[u32]) -> Vec<u8> {
+        let mut proven_input = input_words.to_vec();
+        match input_words.first() {
+            Some(13) => proven_input.push(0),
+            Some(99) => proven_input[0] = 98,
+            _ => {}
+        }
+        let proof = Proof::Dev(
+            DevProof::sign(
+                ImageId::from_bytes([3; 32]),
+                crate::proof::hash_input_words(&proven_input),
+                receipt(&proven_input),
+                100,
+                &DevSigningKey::from_seed([7; 32]),
+            )
+            .expect("sign dev proof"),
+        );
+        proof.to_bytes().expect("encode proof")
+    }
+
+    fn receipt(input_words: &[u32]) -> Receipt {
+        let mut registers = [0u32; 32];
+        registers[10] = input_words.first().copied().unwrap_or_default();
+        Receipt::from_registers(registers)
+    }
+}
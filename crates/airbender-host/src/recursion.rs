@@ -0,0 +1,124 @@
+//! Recursion verifier programs and per-layer setups.
+//!
+//! Shared by CPU recursion proving and verification key generation; none of this needs a GPU.
+
+use execution_utils::setups::{self, CompiledCircuitsSet};
+use execution_utils::unrolled::{
+    compute_setup_for_machine_configuration, get_unrolled_circuits_artifacts_for_machine_type,
+    UnrolledProgramProof, UnrolledProgramSetup,
+};
+use risc_v_simulator::abstractions::non_determinism::QuasiUARTSource;
+use risc_v_simulator::cycle::{
+    IMStandardIsaConfigWithUnsignedMulDiv, IWithoutByteAccessIsaConfigWithDelegation,
+};
+
+/// Padded program image proven or verified by a single proof layer.
+pub(crate) struct LayerProgram {
+    pub(crate) binary: Vec<u32>,
+    pub(crate) binary_u32: Vec<u32>,
+    pub(crate) text: Vec<u32>,
+    pub(crate) text_u32: Vec<u32>,
+}
+
+impl LayerProgram {
+    /// Application program from in-memory `app.bin` and `app.text` contents.
+    pub(crate) fn from_bytes(binary: &[u8], text: &[u8]) -> Self {
+        let (binary, binary_u32) = setups::pad_binary(binary.to_vec());
+        let (text, text_u32) = setups::pad_binary(text.to_vec());
+        Self {
+            binary,
+            binary_u32,
+            text,
+            text_u32,
+        }
+    }
+
+    /// Verifier program proving the recursion-unrolled layer.
+    pub(crate) fn recursion_unrolled() -> Self {
+        Self::from_bytes(
+            execution_utils::unrolled::RECURSION_UNROLLED_BIN,
+            execution_utils::unrolled::RECURSION_UNROLLED_TXT,
+        )
+    }
+
+    /// Verifier program proving the recursion-unified layer.
+    pub(crate) fn recursion_unified() -> Self {
+        Self::from_bytes(
+            execution_utils::unrolled::RECURSION_UNIFIED_BIN,
+            execution_utils::unrolled::RECURSION_UNIFIED_TXT,
+        )
+    }
+}
+
+/// Setup and circuit layouts of a proof layer, needed to feed its proofs into the next layer.
+pub(crate) struct LayerSetup {
+    pub(crate) setup: UnrolledProgramSetup,
+    pub(crate) layouts: CompiledCircuitsSet,
+}
+
+impl LayerSetup {
+    /// Setup of the base layer, which runs the application itself.
+    pub(crate) fn base(program: &LayerProgram) -> Self {
+        Self {
+            setup: compute_setup_for_machine_configuration::<IMStandardIsaConfigWithUnsignedMulDiv>(
+                &program.binary,
+                &program.text,
+            ),
+            layouts: get_unrolled_circuits_artifacts_for_machine_type::<
+                IMStandardIsaConfigWithUnsignedMulDiv,
+            >(&program.binary_u32),
+        }
+    }
+
+    pub(crate) fn recursion_unrolled(program: &LayerProgram) -> Self {
+        Self {
+            setup: compute_setup_for_machine_configuration::<
+                IWithoutByteAccessIsaConfigWithDelegation,
+            >(&program.binary, &program.text),
+            layouts: get_unrolled_circuits_artifacts_for_machine_type::<
+                IWithoutByteAccessIsaConfigWithDelegation,
+            >(&program.binary_u32),
+        }
+    }
+
+    pub(crate) fn recursion_unified(program: &LayerProgram) -> Self {
+        Self {
+            setup:
+                execution_utils::unified_circuit::compute_unified_setup_for_machine_configuration::<
+                    IWithoutByteAccessIsaConfigWithDelegation,
+                >(&program.binary, &program.text),
+            layouts: setups::get_unified_circuit_artifact_for_machine_type::<
+                IWithoutByteAccessIsaConfigWithDelegation,
+            >(&program.binary_u32),
+        }
+    }
+
+    /// Oracle feeding `proof` (produced by this layer) into the next recursion layer.
+    pub(crate) fn recursion_oracle(
+        &self,
+        proof: &UnrolledProgramProof,
+        is_base_layer: bool,
+    ) -> QuasiUARTSource {
+        let responses = execution_utils::unrolled::flatten_proof_for_recursion(
+            proof,
+            &self.setup,
+            &self.layouts,
+            is_base_layer,
+        );
+        QuasiUARTSource::new_with_reads(responses)
+    }
+}
+
+/// Recursion chain (`x18..x25`) output by a recursion layer whose inner proofs went through
+/// `layers`, innermost first.
+///
+/// Every recursion layer folds the end parameters of the setup it verified into the chain,
+/// so the value identifies the application through its base-layer setup.
+pub(crate) fn recursion_chain(layers: &[&LayerSetup]) -> [u32; 8] {
+    execution_utils::compute_chain_encoding(
+        layers
+            .iter()
+            .map(|layer| layer.setup.end_params())
+            .collect(),
+    )
+}
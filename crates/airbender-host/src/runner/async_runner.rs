@@ -0,0 +1,50 @@
+use super::{ExecutionResult, Runner};
+use crate::error::{HostError, Result};
+use crate::worker::{JobFuture, Worker};
+
+/// Async host runner interface.
+///
+/// Execution is offloaded to a dedicated thread, so awaiting the returned future
+/// does not block the async executor.
+pub trait AsyncRunner {
+    fn run(&self, input_words: &[u32]) -> JobFuture<ExecutionResult>;
+}
+
+/// Runner owned by a dedicated worker thread.
+///
+/// Jobs are executed one at a time in submission order. Dropping a [`JobFuture`]
+/// before its job starts cancels the job.
+///
+/// ## Poisoning
+///
+/// If execution panics, the worker thread is disposed of and the worker becomes
+/// poisoned: all pending and future jobs resolve to an error.
+pub struct RunnerWorker {
+    worker: Worker<Box<dyn Runner>>,
+}
+
+impl RunnerWorker {
+    /// Spawn a worker thread and initialize the runner on it.
+    pub async fn spawn<R, F>(init: F) -> Result<Self>
+    where
+        R: Runner + 'static,
+        F: FnOnce() -> Result<R> + Send + 'static,
+    {
+        let worker = Worker::spawn("runner", HostError::Runner, move || {
+            init().map(|runner| Box::new(runner) as Box<dyn Runner>)
+        })
+        .await?;
+        Ok(Self { worker })
+    }
+
+    pub fn is_poisoned(&self) -> bool {
+        self.worker.is_poisoned()
+    }
+}
+
+impl AsyncRunner for RunnerWorker {
+    fn run(&self, input_words: &[u32]) -> JobFuture<ExecutionResult> {
+        let input_words = input_words.to_vec();
+        self.worker.submit(move |runner| runner.run(&input_words))
+    }
+}
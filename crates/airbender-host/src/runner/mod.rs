@@ -1,10 +1,14 @@
 use crate::error::{HostError, Result};
-use crate::receipt::Receipt;
+use airbender_verifier::Receipt;
 use std::path::PathBuf;
 
+#[cfg(feature = "async")]
+mod async_runner;
 mod simulator_runner;
 mod transpiler_runner;
 
+#[cfg(feature = "async")]
+pub use self::async_runner::{AsyncRunner, RunnerWorker};
 pub use self::simulator_runner::{SimulatorRunner, SimulatorRunnerBuilder};
 pub use self::transpiler_runner::{TranspilerRunner, TranspilerRunnerBuilder};
 
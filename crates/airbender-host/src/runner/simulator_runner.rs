@@ -1,6 +1,7 @@
 use super::{resolve_cycles, ExecutionResult, FlamegraphConfig, Runner};
+use crate::artifacts::{to_u32_words, ProgramSource};
 use crate::error::{HostError, Result};
-use crate::receipt::Receipt;
+use airbender_verifier::Receipt;
 use risc_v_simulator::abstractions::non_determinism::QuasiUARTSource;
 use risc_v_simulator::cycle::IMStandardIsaConfig;
 use risc_v_simulator::runner::CUSTOM_ENTRY_POINT;
@@ -12,15 +13,26 @@ use std::path::{Path, PathBuf};
 
 /// Builder for creating a configured simulator runner.
 pub struct SimulatorRunnerBuilder {
-    app_bin_path: PathBuf,
+    source: ProgramSource,
     cycles: Option<usize>,
     flamegraph: Option<FlamegraphConfig>,
 }
 
 impl SimulatorRunnerBuilder {
     pub fn new(app_bin_path: impl AsRef<Path>) -> Self {
+        Self::from_source(ProgramSource::Path(app_bin_path.as_ref().to_path_buf()))
+    }
+
+    /// Run in-memory `app.bin` contents.
+    ///
+    /// Flamegraphs of such programs need an explicit [`FlamegraphConfig::elf_path`].
+    pub fn from_bytes(bin: impl Into<Vec<u8>>) -> Self {
+        Self::from_source(ProgramSource::bytes(bin, Vec::new()))
+    }
+
+    pub(crate) fn from_source(source: ProgramSource) -> Self {
         Self {
-            app_bin_path: app_bin_path.as_ref().to_path_buf(),
+            source,
             cycles: None,
             flamegraph: None,
         }
@@ -37,24 +49,52 @@ impl SimulatorRunnerBuilder {
     }
 
     pub fn build(self) -> Result<SimulatorRunner> {
-        let app_bin_path = resolve_app_bin_path(&self.app_bin_path)?;
+        let (bin, elf_path) = match self.source {
+            ProgramSource::Path(path) => {
+                let app_bin_path = resolve_app_bin_path(&path)?;
+                let bin = std::fs::read(&app_bin_path).map_err(|err| {
+                    HostError::Simulator(format!(
+                        "failed to read {}: {err}",
+                        app_bin_path.display()
+                    ))
+                })?;
+                (bin, Some(derive_elf_path(&app_bin_path)))
+            }
+            ProgramSource::Bytes { bin, location, .. } => {
+                (bin, location.as_deref().map(derive_elf_path))
+            }
+        };
+        let bin_words = to_u32_words(&bin).ok_or_else(|| {
+            HostError::Simulator("`app.bin` length is not a multiple of 4".to_string())
+        })?;
         let cycles = resolve_cycles(self.cycles)?;
 
         if let Some(flamegraph) = self.flamegraph.as_ref() {
-            profiler_diagnostics(&app_bin_path, flamegraph)?;
+            profiler_diagnostics(elf_path.as_deref(), flamegraph)?;
         }
 
         Ok(SimulatorRunner {
-            app_bin_path,
+            bin_words,
+            elf_path,
             cycles,
             flamegraph: self.flamegraph,
         })
     }
+
+    /// Build the runner on a dedicated worker thread and expose it through [`AsyncRunner`].
+    ///
+    /// [`AsyncRunner`]: super::AsyncRunner
+    #[cfg(feature = "async")]
+    pub async fn build_async(self) -> Result<super::RunnerWorker> {
+        super::RunnerWorker::spawn(move || self.build()).await
+    }
 }
 
 /// Simulator-based execution runner.
 pub struct SimulatorRunner {
-    app_bin_path: PathBuf,
+    bin_words: Vec<u32>,
+    /// `app.elf` next to `app.bin`, for flamegraph symbols.
+    elf_path: Option<PathBuf>,
     cycles: usize,
     flamegraph: Option<FlamegraphConfig>,
 }
@@ -64,20 +104,20 @@ impl Runner for SimulatorRunner {
         let diagnostics = self
             .flamegraph
             .as_ref()
-            .map(|flamegraph| profiler_diagnostics(&self.app_bin_path, flamegraph))
+            .map(|flamegraph| profiler_diagnostics(self.elf_path.as_deref(), flamegraph))
             .transpose()?;
-        run_simulator_with_diagnostics(&self.app_bin_path, input_words, self.cycles, diagnostics)
+        run_simulator_with_diagnostics(&self.bin_words, input_words, self.cycles, diagnostics)
     }
 }
 
 fn run_simulator_with_diagnostics(
-    bin_path: &Path,
+    bin_words: &[u32],
     input_words: &[u32],
     cycles: usize,
     diagnostics: Option<DiagnosticsConfig>,
 ) -> Result<ExecutionResult> {
     let config = SimulatorConfig::new(
-        BinarySource::Path(bin_path.to_path_buf()),
+        BinarySource::Slice(bin_words),
         CUSTOM_ENTRY_POINT,
         cycles,
         diagnostics,
@@ -117,7 +157,7 @@ fn resolve_app_bin_path(path: &Path) -> Result<PathBuf> {
 }
 
 fn profiler_diagnostics(
-    bin_path: &Path,
+    elf_path: Option<&Path>,
     flamegraph: &FlamegraphConfig,
 ) -> Result<DiagnosticsConfig> {
     if flamegraph.sampling_rate == 0 {
@@ -129,7 +169,12 @@ fn profiler_diagnostics(
     let symbols_path = flamegraph
         .elf_path
         .clone()
-        .unwrap_or_else(|| derive_elf_path(bin_path));
+        .or_else(|| elf_path.map(Path::to_path_buf))
+        .ok_or_else(|| {
+            HostError::Simulator(
+                "flamegraphs of in-memory programs need `FlamegraphConfig::elf_path`".to_string(),
+            )
+        })?;
     if !symbols_path.exists() {
         return Err(HostError::Simulator(format!(
             "ELF file not found: {}",
@@ -1,6 +1,7 @@
 use super::{resolve_cycles, ExecutionResult, FlamegraphConfig, Runner};
+use crate::artifacts::{to_u32_words, ProgramImage, ProgramSource};
 use crate::error::{HostError, Result};
-use crate::receipt::Receipt;
+use airbender_verifier::Receipt;
 use risc_v_simulator::abstractions::non_determinism::QuasiUARTSource;
 use riscv_transpiler::common_constants::{
     rom::ROM_SECOND_WORD_BITS, INITIAL_TIMESTAMP, TIMESTAMP_STEP,
@@ -13,12 +14,11 @@ use riscv_transpiler::vm::{
     DelegationsCounters, FlamegraphConfig as VmFlamegraphConfig, RamWithRomRegion, SimpleTape,
     State, VmFlamegraphProfiler, VM,
 };
-use std::io::Read;
 use std::path::{Path, PathBuf};
 
 /// Builder for creating a configured transpiler runner.
 pub struct TranspilerRunnerBuilder {
-    app_bin_path: PathBuf,
+    source: ProgramSource,
     cycles: Option<usize>,
     text_path: Option<PathBuf>,
     flamegraph: Option<FlamegraphConfig>,
@@ -27,8 +27,19 @@ pub struct TranspilerRunnerBuilder {
 
 impl TranspilerRunnerBuilder {
     pub fn new(app_bin_path: impl AsRef<Path>) -> Self {
+        Self::from_source(ProgramSource::Path(app_bin_path.as_ref().to_path_buf()))
+    }
+
+    /// Run in-memory `app.bin` and `app.text` contents.
+    ///
+    /// Flamegraphs of such programs need an explicit [`FlamegraphConfig::elf_path`].
+    pub fn from_bytes(bin: impl Into<Vec<u8>>, text: impl Into<Vec<u8>>) -> Self {
+        Self::from_source(ProgramSource::bytes(bin, text))
+    }
+
+    pub(crate) fn from_source(source: ProgramSource) -> Self {
         Self {
-            app_bin_path: app_bin_path.as_ref().to_path_buf(),
+            source,
             cycles: None,
             text_path: None,
             flamegraph: None,
@@ -63,28 +74,64 @@ impl TranspilerRunnerBuilder {
             ));
         }
 
-        let app_bin_path = resolve_app_bin_path(&self.app_bin_path)?;
-        let app_text_path = self
+        let text_path = self
             .text_path
             .as_deref()
             .map(resolve_text_path)
-            .unwrap_or_else(|| resolve_text_path(&derive_text_path(&app_bin_path)))?;
+            .transpose()?;
+        let image = match self.source {
+            ProgramSource::Path(path) => {
+                let app_bin_path = resolve_app_bin_path(&path)?;
+                let app_text_path = match text_path {
+                    Some(text_path) => text_path,
+                    None => resolve_text_path(&derive_text_path(&app_bin_path))?,
+                };
+                ProgramImage {
+                    bin: read_file(&app_bin_path)?,
+                    text: read_file(&app_text_path)?,
+                    location: Some(app_bin_path),
+                }
+            }
+            ProgramSource::Bytes {
+                bin,
+                text,
+                location,
+            } => ProgramImage {
+                bin,
+                text: match text_path {
+                    Some(text_path) => read_file(&text_path)?,
+                    None => text,
+                },
+                location,
+            },
+        };
         let cycles = resolve_cycles(self.cycles)?;
 
         Ok(TranspilerRunner {
-            app_bin_path,
-            app_text_path,
+            bin_words: words_of(&image.bin, "app.bin")?,
+            text_words: words_of(&image.text, "app.text")?,
+            elf_path: image.elf_path(),
             cycles,
             flamegraph: self.flamegraph,
             use_jit: self.use_jit,
         })
     }
+
+    /// Build the runner on a dedicated worker thread and expose it through [`AsyncRunner`].
+    ///
+    /// [`AsyncRunner`]: super::AsyncRunner
+    #[cfg(feature = "async")]
+    pub async fn build_async(self) -> Result<super::RunnerWorker> {
+        super::RunnerWorker::spawn(move || self.build()).await
+    }
 }
 
 /// Transpiler based execution runner.
 pub struct TranspilerRunner {
-    app_bin_path: PathBuf,
-    app_text_path: PathBuf,
+    bin_words: Vec<u32>,
+    text_words: Vec<u32>,
+    /// `app.elf` next to `app.bin`, for flamegraph symbols.
+    elf_path: Option<PathBuf>,
     cycles: usize,
     flamegraph: Option<FlamegraphConfig>,
     use_jit: bool,
@@ -107,8 +154,6 @@ impl Runner for TranspilerRunner {
 impl TranspilerRunner {
     #[cfg(target_arch = "x86_64")]
     fn run_with_jit(&self, input_words: &[u32]) -> Result<ExecutionResult> {
-        let bin_words = read_u32_words(&self.app_bin_path)?;
-        let text_words = read_u32_words(&self.app_text_path)?;
         let mut non_determinism_source = QuasiUARTSource::new_with_reads(input_words.to_vec());
 
         let cycles_bound = match u32::try_from(self.cycles) {
@@ -123,9 +168,9 @@ impl TranspilerRunner {
         };
 
         let (state, _memory) = JittedCode::run_alternative_simulator(
-            &text_words,
+            &self.text_words,
             &mut non_determinism_source,
-            &bin_words,
+            &self.bin_words,
             cycles_bound,
         );
         let cycles_executed = ((state.timestamp - INITIAL_TIMESTAMP) / TIMESTAMP_STEP) as usize;
@@ -157,7 +202,13 @@ impl TranspilerRunner {
         let symbols_path = flamegraph
             .elf_path
             .clone()
-            .unwrap_or_else(|| derive_elf_path(&self.app_bin_path));
+            .or_else(|| self.elf_path.clone())
+            .ok_or_else(|| {
+                HostError::Transpiler(
+                    "flamegraphs of in-memory programs need `FlamegraphConfig::elf_path`"
+                        .to_string(),
+                )
+            })?;
         let mut profiler_config = VmFlamegraphConfig::new(symbols_path, flamegraph.output.clone());
         profiler_config.frequency_recip = flamegraph.sampling_rate;
         profiler_config.reverse_graph = flamegraph.inverse;
@@ -173,12 +224,13 @@ impl TranspilerRunner {
         input_words: &[u32],
         profiler: Option<&mut VmFlamegraphProfiler>,
     ) -> Result<ExecutionResult> {
-        let bin_words = read_u32_words(&self.app_bin_path)?;
-        let text_words = read_u32_words(&self.app_text_path)?;
-        let instructions = preprocess_bytecode::<FullUnsignedMachineDecoderConfig>(&text_words);
+        let instructions =
+            preprocess_bytecode::<FullUnsignedMachineDecoderConfig>(&self.text_words);
         let instruction_tape = SimpleTape::new(&instructions);
-        let mut ram =
-            RamWithRomRegion::<{ ROM_SECOND_WORD_BITS }>::from_rom_content(&bin_words, RAM_SIZE);
+        let mut ram = RamWithRomRegion::<{ ROM_SECOND_WORD_BITS }>::from_rom_content(
+            &self.bin_words,
+            RAM_SIZE,
+        );
         let mut state = State::initial_with_counters(DelegationsCounters::default());
         let mut non_determinism_source = QuasiUARTSource::new_with_reads(input_words.to_vec());
 
@@ -256,31 +308,25 @@ fn derive_text_path(bin_path: &Path) -> PathBuf {
     text_path
 }
 
-fn derive_elf_path(bin_path: &Path) -> PathBuf {
-    let mut elf_path = bin_path.to_path_buf();
-    elf_path.set_extension("elf");
-    elf_path
+fn read_file(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path)
+        .map_err(|err| HostError::Transpiler(format!("failed to read {}: {err}", path.display())))
 }
 
-fn read_u32_words(path: &Path) -> Result<Vec<u32>> {
-    let mut file = std::fs::File::open(path).map_err(|err| {
-        HostError::Transpiler(format!("failed to open {}: {err}", path.display()))
-    })?;
-    let mut bytes = Vec::new();
-    file.read_to_end(&mut bytes).map_err(|err| {
-        HostError::Transpiler(format!("failed to read {}: {err}", path.display()))
-    })?;
+fn words_of(bytes: &[u8], name: &str) -> Result<Vec<u32>> {
+    to_u32_words(bytes)
+        .ok_or_else(|| HostError::Transpiler(format!("`{name}` length is not a multiple of 4")))
+}
 
-    if bytes.len() % 4 != 0 {
-        return Err(HostError::Transpiler(format!(
-            "file length is not a multiple of 4: {}",
-            path.display()
-        )));
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use riscv_transpiler::common_constants::rom::ROM_BYTE_SIZE;
 
-    let mut words = Vec::with_capacity(bytes.len() / 4);
-    for chunk in bytes.as_chunks::<4>().0 {
-        words.push(u32::from_le_bytes(*chunk));
+    #[test]
+    fn build_memory_map_matches_the_machine() {
+        // `airbender-build` keeps its own copy to avoid depending on the transpiler.
+        assert_eq!(airbender_build::RAM_BYTE_SIZE, RAM_SIZE as u64);
+        assert_eq!(airbender_build::ROM_BYTE_SIZE, ROM_BYTE_SIZE as u64);
     }
-    Ok(words)
 }
@@ -0,0 +1,113 @@
+//! JSON protocol spoken by `cargo airbender serve` and its clients.
+//!
+//! Endpoints (all paths are relative to the service root):
+//! - `GET  /v1/programs`: list loaded programs ([`ProgramInfo`]),
+//! - `POST /v1/programs/{program}/jobs`: submit a proving job ([`SubmitJobRequest`] -> [`SubmitJobResponse`]),
+//! - `GET  /v1/jobs/{id}`: poll job status ([`JobStatus`]),
+//! - `GET  /v1/jobs/{id}/proof`: fetch the encoded [`Proof`](crate::Proof) ([`Proof::to_bytes`](crate::Proof::to_bytes)),
+//! - `GET  /v1/jobs/{id}/receipt`: fetch the [`Receipt`] as JSON,
+//! - `POST /v1/jobs/{id}/verify`: verify a finished job ([`VerifyRequest`] -> [`VerifyResponse`]),
+//! - `GET  /v1/programs/{program}/vk`: fetch the bincode-encoded [`VerificationKey`](crate::VerificationKey),
+//! - `POST /v1/programs/{program}/verify`: verify an encoded proof body,
+//!   with optional `expected_output` query parameter ([`VerifyResponse`]).
+//!
+//! Failed requests return a non-2xx status with an [`ErrorResponse`] body.
+
+use crate::prover::ProverLevel;
+use airbender_core::image_id::ImageId;
+use airbender_verifier::Receipt;
+
+/// Version prefix shared by all service endpoints.
+pub const SERVICE_API_PREFIX: &str = "/v1";
+
+/// Program served by a proving service.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProgramInfo {
+    /// Name used in program endpoints.
+    pub name: String,
+    /// Cargo package the program was built from.
+    pub package: String,
+    /// Image id of the served program; proofs returned for it carry the same id.
+    pub image_id: ImageId,
+    /// Proving backend used for this program (`dev`, `cpu` or `gpu`).
+    pub backend: String,
+    /// Proof level produced by the backend; absent for dev proofs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<ProverLevel>,
+}
+
+/// Proving job submission payload.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SubmitJobRequest {
+    /// Framed input words, as produced by [`Inputs::words`](crate::Inputs::words).
+    pub input_words: Vec<u32>,
+}
+
+/// Response to an accepted job submission.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SubmitJobResponse {
+    pub job_id: u64,
+}
+
+/// Lifecycle state of a proving job.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl JobState {
+    /// Whether the job will not change state anymore.
+    pub fn is_finished(self) -> bool {
+        matches!(self, JobState::Succeeded | JobState::Failed)
+    }
+}
+
+/// Status snapshot of a proving job.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct JobStatus {
+    pub id: u64,
+    pub program: String,
+    /// Image id of the program build the job was submitted for.
+    pub image_id: ImageId,
+    pub state: JobState,
+    /// Proved cycle count, set once the job succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycles: Option<u64>,
+    /// Execution receipt, set once the job succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<Receipt>,
+    /// Failure reason, set once the job failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Optional checks applied when verifying a finished job.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct VerifyRequest {
+    /// Expected public output words (`x10..x17`); missing words are zero-padded.
+    /// Required for dev proofs, whose recorded receipt cannot vouch for itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_output: Option<Vec<u32>>,
+}
+
+/// Verification outcome.
+///
+/// A proof that fails verification is reported with `verified = false` rather than
+/// an error status, so clients can tell invalid proofs apart from request failures.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct VerifyResponse {
+    pub verified: bool,
+    /// Verification failure reason when `verified` is `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Error payload returned for failed requests.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
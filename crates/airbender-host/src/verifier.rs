@@ -1,40 +1,17 @@
+use crate::artifacts::{ProgramImage, ProgramSource};
+use crate::dev_key::{cargo_project_dir, load_dev_key};
 use crate::error::{HostError, Result};
-use crate::proof::{hash_app_bin, hash_input_words, Proof, RealProof};
+use crate::proof::hash_input_words;
 use crate::prover::ProverLevel;
-use crate::vk::{
-    compute_unified_vk, compute_unrolled_vk, verify_proof, verify_unrolled_proof, UnifiedVk,
-    UnrolledVk,
-};
+use crate::vk::{resolve_text_path, unified_vk, unrolled_vk};
 use airbender_core::guest::Commit;
+use airbender_core::image_id::ImageId;
+use airbender_verifier::{
+    verify_real_proof_with_vk, DevPublicKey, DevVerificationKey, Proof, RealUnifiedVerificationKey,
+    RealUnrolledVerificationKey, VerificationKey,
+};
 use std::path::{Path, PathBuf};
 
-/// Wrapper around all verification-key flavors.
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub enum VerificationKey {
-    Dev(DevVerificationKey),
-    RealUnified(RealUnifiedVerificationKey),
-    RealUnrolled(RealUnrolledVerificationKey),
-}
-
-/// Development verification key.
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub struct DevVerificationKey {
-    pub app_bin_hash: [u8; 32],
-}
-
-/// Unified (recursion) verification key wrapper.
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub struct RealUnifiedVerificationKey {
-    pub vk: UnifiedVk,
-}
-
-/// Unrolled (base / recursion-unrolled) verification key wrapper.
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub struct RealUnrolledVerificationKey {
-    pub level: ProverLevel,
-    pub vk: UnrolledVk,
-}
-
 /// Verification checks requested by the caller.
 #[derive(Clone, Copy, Default)]
 pub struct VerificationRequest<'a> {
@@ -90,57 +67,97 @@ pub trait Verifier {
 
 /// Builder for a development verifier.
 pub struct DevVerifierBuilder {
-    app_bin_path: PathBuf,
+    source: ProgramSource,
+    dev_key: Option<DevPublicKey>,
 }
 
 impl DevVerifierBuilder {
     pub fn new(app_bin_path: impl AsRef<Path>) -> Self {
+        Self::from_source(ProgramSource::Path(app_bin_path.as_ref().to_path_buf()))
+    }
+
+    /// Verify proofs of in-memory `app.bin` and `app.text` contents.
+    ///
+    /// Such programs have no project dev key, so they need [`Self::with_dev_key`].
+    pub fn from_bytes(bin: impl Into<Vec<u8>>, text: impl Into<Vec<u8>>) -> Self {
+        Self::from_source(ProgramSource::bytes(bin, text))
+    }
+
+    pub(crate) fn from_source(source: ProgramSource) -> Self {
         Self {
-            app_bin_path: app_bin_path.as_ref().to_path_buf(),
+            source,
+            dev_key: None,
         }
     }
 
+    /// Trust `dev_key` instead of the project's key under `.airbender/dev-key`.
+    pub fn with_dev_key(mut self, dev_key: DevPublicKey) -> Self {
+        self.dev_key = Some(dev_key);
+        self
+    }
+
     pub fn build(self) -> Result<DevVerifier> {
-        DevVerifier::new(&self.app_bin_path)
+        DevVerifier::new(self.source, self.dev_key)
     }
 }
 
 /// Builder for a real verifier.
 pub struct RealVerifierBuilder {
-    app_bin_path: PathBuf,
+    source: ProgramSource,
     level: ProverLevel,
 }
 
 impl RealVerifierBuilder {
     pub fn new(app_bin_path: impl AsRef<Path>, level: ProverLevel) -> Self {
-        Self {
-            app_bin_path: app_bin_path.as_ref().to_path_buf(),
+        Self::from_source(
+            ProgramSource::Path(app_bin_path.as_ref().to_path_buf()),
             level,
-        }
+        )
+    }
+
+    /// Verify proofs of in-memory `app.bin` and `app.text` contents.
+    pub fn from_bytes(
+        bin: impl Into<Vec<u8>>,
+        text: impl Into<Vec<u8>>,
+        level: ProverLevel,
+    ) -> Self {
+        Self::from_source(ProgramSource::bytes(bin, text), level)
+    }
+
+    pub(crate) fn from_source(source: ProgramSource, level: ProverLevel) -> Self {
+        Self { source, level }
     }
 
     pub fn build(self) -> Result<RealVerifier> {
-        RealVerifier::new(&self.app_bin_path, self.level)
+        RealVerifier::new(self.source, self.level)
     }
 }
 
 /// Development verifier implementation.
+///
+/// Accepts only dev proofs signed by its trusted dev key. Without an explicit key it
+/// trusts the existing project dev key that `DevProver` signs with.
 pub struct DevVerifier {
-    app_bin_hash: [u8; 32],
+    image_id: ImageId,
+    dev_key: DevPublicKey,
 }
 
 impl DevVerifier {
-    fn new(app_bin_path: &Path) -> Result<Self> {
-        let app_bin_path = resolve_app_bin_path(app_bin_path)?;
-        let app_bin_hash = hash_app_bin(&app_bin_path)?;
-        Ok(Self { app_bin_hash })
+    fn new(source: ProgramSource, dev_key: Option<DevPublicKey>) -> Result<Self> {
+        let image = load_image(source)?;
+        let image_id = image.image_id();
+        let dev_key = match dev_key {
+            Some(dev_key) => dev_key,
+            None => load_dev_key(project_dir_of(&image)?)?.public_key(),
+        };
+        Ok(Self { image_id, dev_key })
     }
 }
 
 impl Verifier for DevVerifier {
     fn generate_vk(&self) -> Result<VerificationKey> {
         Ok(VerificationKey::Dev(DevVerificationKey {
-            app_bin_hash: self.app_bin_hash,
+            image_id: self.image_id,
         }))
     }
 
@@ -167,17 +184,13 @@ impl Verifier for DevVerifier {
             }
         };
 
-        if vk.app_bin_hash != self.app_bin_hash {
+        if vk.image_id != self.image_id {
             return Err(HostError::Verification(
                 "dev verification key does not match current program".to_string(),
             ));
         }
-
-        if proof.app_bin_hash != self.app_bin_hash {
-            return Err(HostError::Verification(
-                "dev proof was produced for a different program".to_string(),
-            ));
-        }
+        verify_proof_image_id(proof.image_id, self.image_id)?;
+        proof.verify_signature(&self.dev_key)?;
 
         let expected_input_words = request.expected_input_words().ok_or_else(|| {
             HostError::Verification("dev verification requires expected input words".to_string())
@@ -206,18 +219,18 @@ impl Verifier for DevVerifier {
 
 /// Real verifier implementation.
 pub struct RealVerifier {
-    app_bin_path: PathBuf,
-    app_bin_hash: [u8; 32],
+    image: ProgramImage,
+    image_id: ImageId,
     level: ProverLevel,
 }
 
 impl RealVerifier {
-    fn new(app_bin_path: &Path, level: ProverLevel) -> Result<Self> {
-        let app_bin_path = resolve_app_bin_path(app_bin_path)?;
-        let app_bin_hash = hash_app_bin(&app_bin_path)?;
+    fn new(source: ProgramSource, level: ProverLevel) -> Result<Self> {
+        let image = load_image(source)?;
+        let image_id = image.image_id();
         Ok(Self {
-            app_bin_path,
-            app_bin_hash,
+            image,
+            image_id,
             level,
         })
     }
@@ -227,13 +240,13 @@ impl Verifier for RealVerifier {
     fn generate_vk(&self) -> Result<VerificationKey> {
         match self.level {
             ProverLevel::RecursionUnified => {
-                let vk = compute_unified_vk(&self.app_bin_path)?;
+                let vk = unified_vk(&self.image)?;
                 Ok(VerificationKey::RealUnified(RealUnifiedVerificationKey {
                     vk,
                 }))
             }
             ProverLevel::Base | ProverLevel::RecursionUnrolled => {
-                let vk = compute_unrolled_vk(&self.app_bin_path, self.level)?;
+                let vk = unrolled_vk(&self.image, self.level)?;
                 Ok(VerificationKey::RealUnrolled(RealUnrolledVerificationKey {
                     level: self.level,
                     vk,
@@ -262,99 +275,60 @@ impl Verifier for RealVerifier {
                 ));
             }
         };
-
-        match (proof.level(), vk) {
-            (
-                ProverLevel::RecursionUnified,
-                VerificationKey::RealUnified(RealUnifiedVerificationKey { vk }),
-            ) => verify_proof(
-                proof.inner(),
-                vk,
-                Some(self.app_bin_hash),
-                request.expected_output(),
-            ),
-            (
-                ProverLevel::Base | ProverLevel::RecursionUnrolled,
-                VerificationKey::RealUnrolled(RealUnrolledVerificationKey { level, vk }),
-            ) => {
-                if *level != proof.level() {
-                    return Err(HostError::Verification(format!(
-                        "proof level {:?} does not match verification key level {:?}",
-                        proof.level(),
-                        level
-                    )));
-                }
-
-                verify_unrolled_proof(
-                    proof.inner(),
-                    vk,
-                    proof.level(),
-                    Some(self.app_bin_hash),
-                    request.expected_output(),
-                )
-            }
-            (_, VerificationKey::Dev(_)) => Err(HostError::Verification(
-                "real verifier requires a real verification key".to_string(),
-            )),
-            (ProverLevel::RecursionUnified, VerificationKey::RealUnrolled(_)) => {
-                Err(HostError::Verification(
-                    "recursion-unified proofs require unified verification keys".to_string(),
-                ))
-            }
-            (
-                ProverLevel::Base | ProverLevel::RecursionUnrolled,
-                VerificationKey::RealUnified(_),
-            ) => Err(HostError::Verification(
-                "base and recursion-unrolled proofs require unrolled verification keys".to_string(),
-            )),
-        }
+        verify_proof_image_id(proof.image_id(), self.image_id)?;
+        verify_real_proof_with_vk(proof, vk, request.expected_output())?;
+        Ok(())
     }
 }
 
-/// Verify a real proof envelope against a real verification key.
-///
-/// This helper validates proof/VK compatibility and optional expected public output.
-/// It intentionally does not enforce app.bin hash checks.
-pub fn verify_real_proof_with_vk(
-    proof: &RealProof,
-    vk: &VerificationKey,
-    expected_output: Option<&dyn Commit>,
-) -> Result<()> {
-    match (proof.level(), vk) {
-        (
-            ProverLevel::RecursionUnified,
-            VerificationKey::RealUnified(RealUnifiedVerificationKey { vk }),
-        ) => verify_proof(proof.inner(), vk, None, expected_output),
-        (
-            ProverLevel::Base | ProverLevel::RecursionUnrolled,
-            VerificationKey::RealUnrolled(RealUnrolledVerificationKey { level, vk }),
-        ) => {
-            if *level != proof.level() {
-                return Err(HostError::Verification(format!(
-                    "proof level {:?} does not match verification key level {:?}",
-                    proof.level(),
-                    level
-                )));
-            }
+fn verify_proof_image_id(proof_image_id: ImageId, expected_image_id: ImageId) -> Result<()> {
+    if proof_image_id != expected_image_id {
+        return Err(HostError::Verification(format!(
+            "proof was produced for a different program: expected image id {expected_image_id}, got {proof_image_id}"
+        )));
+    }
+    Ok(())
+}
 
-            verify_unrolled_proof(proof.inner(), vk, proof.level(), None, expected_output)
-        }
-        (_, VerificationKey::Dev(_)) => Err(HostError::Verification(
-            "real proofs require real verification keys".to_string(),
-        )),
-        (ProverLevel::RecursionUnified, VerificationKey::RealUnrolled(_)) => {
-            Err(HostError::Verification(
-                "recursion-unified proof requires a unified verification key".to_string(),
-            ))
-        }
-        (ProverLevel::Base | ProverLevel::RecursionUnrolled, VerificationKey::RealUnified(_)) => {
-            Err(HostError::Verification(
-                "base/recursion-unrolled proof requires an unrolled verification key".to_string(),
-            ))
+fn load_image(source: ProgramSource) -> Result<ProgramImage> {
+    match source {
+        ProgramSource::Path(path) => {
+            let app_bin_path = resolve_app_bin_path(&path)?;
+            Ok(ProgramImage::read(
+                &app_bin_path,
+                &resolve_text_path(&app_bin_path)?,
+            )?)
         }
+        ProgramSource::Bytes {
+            bin,
+            text,
+            location,
+        } => Ok(ProgramImage {
+            bin,
+            text,
+            location,
+        }),
     }
 }
 
+/// Project directory holding the dev key trusted by default.
+fn project_dir_of(image: &ProgramImage) -> Result<PathBuf> {
+    let Some(app_bin_path) = &image.location else {
+        return Err(HostError::Verification(
+            "in-memory programs have no project dev key; pass the key with \
+             `DevVerifierBuilder::with_dev_key`"
+                .to_string(),
+        ));
+    };
+    cargo_project_dir(app_bin_path).ok_or_else(|| {
+        HostError::Verification(format!(
+            "{} is not inside a Cargo project, so there is no project dev key to trust; pass \
+             the key with `DevVerifierBuilder::with_dev_key`",
+            app_bin_path.display()
+        ))
+    })
+}
+
 fn resolve_app_bin_path(path: &Path) -> Result<PathBuf> {
     if path.exists() {
         return path.canonicalize().map_err(|err| {
@@ -381,3 +355,108 @@ fn resolve_app_bin_path(path: &Path) -> Result<PathBuf> {
         path.display()
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use airbender_verifier::{DevProof, DevSigningKey, Receipt};
+
+    #[test]
+    fn dev_verifier_binds_text_section() {
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let dir = temp_dir.path();
+        let app_bin = dir.join("app.bin");
+        std::fs::write(&app_bin, [1u8; 8]).expect("write app.bin");
+        std::fs::write(dir.join("app.text"), [2u8; 8]).expect("write app.text");
+
+        let dev_key = DevSigningKey::from_seed([5; 32]);
+        let verifier = DevVerifierBuilder::new(&app_bin)
+            .with_dev_key(dev_key.public_key())
+            .build()
+            .expect("build verifier");
+        let vk = verifier.generate_vk().expect("generate vk");
+        let proof_for = |image_id| {
+            Proof::Dev(
+                DevProof::sign(
+                    image_id,
+                    hash_input_words(&[]),
+                    Receipt::from_registers([0; 32]),
+                    1,
+                    &dev_key,
+                )
+                .expect("sign dev proof"),
+            )
+        };
+        let request = VerificationRequest::dev(&[], &0u32);
+
+        verifier
+            .verify(&proof_for(verifier.image_id), &vk, request)
+            .expect("matching proof must verify");
+
+        // Same `app.bin` with a different text section is a different program.
+        std::fs::write(dir.join("app.text"), [3u8; 8]).expect("rewrite app.text");
+        let other_image_id = DevVerifierBuilder::new(&app_bin)
+            .with_dev_key(dev_key.public_key())
+            .build()
+            .expect("build verifier")
+            .image_id;
+        assert_ne!(other_image_id, verifier.image_id);
+        let err = verifier
+            .verify(&proof_for(other_image_id), &vk, request)
+            .expect_err("proof for another image must be rejected");
+        assert!(err.to_string().contains("different program"));
+    }
+
+    #[test]
+    fn dev_verifier_trusts_project_dev_key() {
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let dir = temp_dir.path();
+        let app_bin = dir.join("app.bin");
+        std::fs::write(&app_bin, [1u8; 8]).expect("write app.bin");
+        std::fs::write(dir.join("app.text"), [2u8; 8]).expect("write app.text");
+
+        let err = match DevVerifierBuilder::new(&app_bin).build() {
+            Ok(_) => panic!("a program outside a Cargo project has no project dev key"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("with_dev_key"), "{err}");
+
+        std::fs::write(dir.join("Cargo.toml"), "").expect("write Cargo.toml");
+        let err = match DevVerifierBuilder::new(&app_bin).build() {
+            Ok(_) => panic!("the verifier must not create the project dev key"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("dev key not found"), "{err}");
+
+        let trusted_key = crate::load_or_create_dev_key(dir).expect("create project dev key");
+        let verifier = DevVerifierBuilder::new(&app_bin)
+            .build()
+            .expect("build verifier trusting the project dev key");
+        let vk = verifier.generate_vk().expect("generate vk");
+        let request = VerificationRequest::dev(&[], &0u32);
+        let proof_signed_by = |key: &DevSigningKey| {
+            Proof::Dev(
+                DevProof::sign(
+                    verifier.image_id,
+                    hash_input_words(&[]),
+                    Receipt::from_registers([0; 32]),
+                    1,
+                    key,
+                )
+                .expect("sign dev proof"),
+            )
+        };
+
+        verifier
+            .verify(&proof_signed_by(&trusted_key), &vk, request)
+            .expect("proof signed by the project key must verify");
+        let err = verifier
+            .verify(
+                &proof_signed_by(&DevSigningKey::from_seed([9; 32])),
+                &vk,
+                request,
+            )
+            .expect_err("proof signed by another key must be rejected");
+        assert!(err.to_string().contains("untrusted dev key"));
+    }
+}
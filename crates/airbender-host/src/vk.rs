@@ -1,134 +1,45 @@
+use crate::artifacts::ProgramImage;
 use crate::error::{HostError, Result};
 use crate::prover::ProverLevel;
-use airbender_core::guest::Commit;
-use execution_utils::setups;
-use execution_utils::unified_circuit::verify_proof_in_unified_layer;
-use execution_utils::unrolled::{
-    compute_setup_for_machine_configuration, get_unrolled_circuits_artifacts_for_machine_type,
-    verify_unrolled_layer_proof, UnrolledProgramProof, UnrolledProgramSetup,
-};
-use risc_v_simulator::cycle::{
-    IMStandardIsaConfigWithUnsignedMulDiv, IWithoutByteAccessIsaConfigWithDelegation,
-};
-use sha3::Digest;
-use std::fs;
+use crate::recursion::{recursion_chain, LayerProgram, LayerSetup};
+use airbender_verifier::{UnifiedVk, UnrolledVk};
 use std::path::{Path, PathBuf};
 
-/// Unified verification key bundle for recursion.
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub struct UnifiedVk {
-    pub app_bin_hash: [u8; 32],
-    pub unified_setup: UnrolledProgramSetup,
-    pub unified_layouts: setups::CompiledCircuitsSet,
-}
-
-/// Unrolled verification key bundle for base or recursion-unrolled layers.
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub struct UnrolledVk {
-    pub app_bin_hash: [u8; 32],
-    pub setup: UnrolledProgramSetup,
-    pub compiled_layouts: setups::CompiledCircuitsSet,
-}
-
 pub fn compute_unified_vk(app_bin_path: &Path) -> Result<UnifiedVk> {
-    #[cfg(not(feature = "gpu-prover"))]
-    {
-        let _ = app_bin_path;
-        return Err(HostError::Verification(
-            "recursion-unified verification key generation requires the `gpu-prover` feature"
-                .to_string(),
-        ));
-    }
-
-    #[cfg(feature = "gpu-prover")]
-    {
-        let app_bin_hash = hash_app_bin(app_bin_path)?;
-
-        // TODO: cache unified setup/layout artifacts on disk to avoid recomputing on every run.
-        let (binary, binary_u32) =
-            setups::pad_binary(execution_utils::unrolled_gpu::RECURSION_UNIFIED_BIN.to_vec());
-        let (text, _) =
-            setups::pad_binary(execution_utils::unrolled_gpu::RECURSION_UNIFIED_TXT.to_vec());
-
-        let unified_setup =
-            execution_utils::unified_circuit::compute_unified_setup_for_machine_configuration::<
-                IWithoutByteAccessIsaConfigWithDelegation,
-            >(&binary, &text);
-        let unified_layouts =
-            execution_utils::setups::get_unified_circuit_artifact_for_machine_type::<
-                IWithoutByteAccessIsaConfigWithDelegation,
-            >(&binary_u32);
-
-        Ok(UnifiedVk {
-            app_bin_hash,
-            unified_setup,
-            unified_layouts,
-        })
-    }
+    unified_vk(&read_image(app_bin_path)?)
 }
 
 pub fn compute_unrolled_vk(app_bin_path: &Path, level: ProverLevel) -> Result<UnrolledVk> {
-    if level == ProverLevel::RecursionUnified {
-        return Err(HostError::Verification(
-            "unified verification keys must be generated with compute_unified_vk".to_string(),
-        ));
-    }
-
-    let resolved_bin_path = resolve_bin_path(app_bin_path)?;
-    let app_bin_hash = hash_app_bin(&resolved_bin_path)?;
-
-    let (binary, binary_u32, text) = match level {
-        ProverLevel::Base => {
-            let app_text_path = resolve_text_path(&resolved_bin_path)?;
-            let (binary, binary_u32) = setups::read_and_pad_binary(&resolved_bin_path);
-            let (text, _) = setups::read_and_pad_binary(&app_text_path);
-            (binary, binary_u32, text)
-        }
-        ProverLevel::RecursionUnrolled => {
-            #[cfg(not(feature = "gpu-prover"))]
-            {
-                return Err(HostError::Verification(
-                    "recursion-unrolled verification key generation requires the `gpu-prover` feature"
-                        .to_string(),
-                ));
-            }
+    unrolled_vk(&read_image(app_bin_path)?, level)
+}
 
-            #[cfg(feature = "gpu-prover")]
-            {
-                let (binary, binary_u32) = setups::pad_binary(
-                    execution_utils::unrolled_gpu::RECURSION_UNROLLED_BIN.to_vec(),
-                );
-                let (text, _) = setups::pad_binary(
-                    execution_utils::unrolled_gpu::RECURSION_UNROLLED_TXT.to_vec(),
-                );
-                (binary, binary_u32, text)
-            }
-        }
-        ProverLevel::RecursionUnified => {
-            return Err(HostError::Verification(
-                "unified verification keys must be generated with compute_unified_vk".to_string(),
-            ));
-        }
-    };
+pub(crate) fn unified_vk(image: &ProgramImage) -> Result<UnifiedVk> {
+    let image_id = image.image_id();
+    let base = LayerSetup::base(&LayerProgram::from_bytes(&image.bin, &image.text));
+    // TODO: cache unified setup/layout artifacts on disk to avoid recomputing on every run.
+    let recursion_unrolled = LayerSetup::recursion_unrolled(&LayerProgram::recursion_unrolled());
+    let unified = LayerSetup::recursion_unified(&LayerProgram::recursion_unified());
+
+    Ok(UnifiedVk {
+        image_id,
+        program_commitment: recursion_chain(&[&base, &recursion_unrolled]),
+        unified_setup: unified.setup,
+        unified_layouts: unified.layouts,
+    })
+}
 
-    let (setup, compiled_layouts) = match level {
-        ProverLevel::Base => {
-            let setup = compute_setup_for_machine_configuration::<
-                IMStandardIsaConfigWithUnsignedMulDiv,
-            >(&binary, &text);
-            let compiled_layouts = get_unrolled_circuits_artifacts_for_machine_type::<
-                IMStandardIsaConfigWithUnsignedMulDiv,
-            >(&binary_u32);
-            (setup, compiled_layouts)
-        }
+pub(crate) fn unrolled_vk(image: &ProgramImage, level: ProverLevel) -> Result<UnrolledVk> {
+    let image_id = image.image_id();
+    let base = LayerSetup::base(&LayerProgram::from_bytes(&image.bin, &image.text));
+    let (layer, program_commitment) = match level {
+        // The base-layer setup is computed from the program itself, so it binds the program.
+        ProverLevel::Base => (base, None),
         ProverLevel::RecursionUnrolled => {
-            let setup = compute_setup_for_machine_configuration::<
-                IWithoutByteAccessIsaConfigWithDelegation,
-            >(&binary, &text);
-            let compiled_layouts = get_unrolled_circuits_artifacts_for_machine_type::<
-                IWithoutByteAccessIsaConfigWithDelegation,
-            >(&binary_u32);
-            (setup, compiled_layouts)
+            let program_commitment = recursion_chain(&[&base]);
+            (
+                LayerSetup::recursion_unrolled(&LayerProgram::recursion_unrolled()),
+                Some(program_commitment),
+            )
         }
         ProverLevel::RecursionUnified => {
             return Err(HostError::Verification(
@@ -138,92 +49,17 @@ pub fn compute_unrolled_vk(app_bin_path: &Path, level: ProverLevel) -> Result<Un
     };
 
     Ok(UnrolledVk {
-        app_bin_hash,
-        setup,
-        compiled_layouts,
+        image_id,
+        program_commitment,
+        setup: layer.setup,
+        compiled_layouts: layer.layouts,
     })
 }
 
-pub fn verify_proof(
-    proof: &UnrolledProgramProof,
-    vk: &UnifiedVk,
-    expected_app_bin_hash: Option<[u8; 32]>,
-    expected_output: Option<&dyn Commit>,
-) -> Result<()> {
-    verify_app_bin_hash(expected_app_bin_hash, vk.app_bin_hash)?;
-
-    let verifier_output =
-        verify_proof_in_unified_layer(proof, &vk.unified_setup, &vk.unified_layouts, false)
-            .map_err(|_| HostError::Verification("proof verification failed".to_string()))?;
-    verify_expected_output(expected_output, verifier_output)?;
-    Ok(())
-}
-
-pub fn verify_unrolled_proof(
-    proof: &UnrolledProgramProof,
-    vk: &UnrolledVk,
-    level: ProverLevel,
-    expected_app_bin_hash: Option<[u8; 32]>,
-    expected_output: Option<&dyn Commit>,
-) -> Result<()> {
-    verify_app_bin_hash(expected_app_bin_hash, vk.app_bin_hash)?;
-
-    let is_base_layer = match level {
-        ProverLevel::Base => true,
-        ProverLevel::RecursionUnrolled => false,
-        ProverLevel::RecursionUnified => {
-            return Err(HostError::Verification(
-                "recursion-unified proofs must be verified with unified verification keys"
-                    .to_string(),
-            ));
-        }
-    };
-
-    let verifier_output =
-        verify_unrolled_layer_proof(proof, &vk.setup, &vk.compiled_layouts, is_base_layer)
-            .map_err(|_| HostError::Verification("proof verification failed".to_string()))?;
-    verify_expected_output(expected_output, verifier_output)?;
-    Ok(())
-}
-
-fn verify_expected_output(
-    expected_output: Option<&dyn Commit>,
-    verifier_output: [u32; 16],
-) -> Result<()> {
-    let Some(expected_output) = expected_output else {
-        return Ok(());
-    };
-
-    let expected_words = expected_output.commit_words();
-    let mut actual_words = [0u32; 8];
-    actual_words.copy_from_slice(&verifier_output[..8]);
-
-    if expected_words != actual_words {
-        return Err(HostError::Verification(format!(
-            "public output mismatch: expected {expected_words:?}, got {actual_words:?}"
-        )));
-    }
-
-    Ok(())
-}
-
-fn verify_app_bin_hash(
-    expected_app_bin_hash: Option<[u8; 32]>,
-    actual_app_bin_hash: [u8; 32],
-) -> Result<()> {
-    if let Some(expected) = expected_app_bin_hash {
-        if expected != actual_app_bin_hash {
-            return Err(HostError::Verification(
-                "app.bin hash does not match verification key".to_string(),
-            ));
-        }
-    }
-    Ok(())
-}
-
-fn hash_app_bin(path: &Path) -> Result<[u8; 32]> {
-    let app_bin_bytes = fs::read(path)?;
-    Ok(sha3::Keccak256::digest(&app_bin_bytes).into())
+fn read_image(app_bin_path: &Path) -> Result<ProgramImage> {
+    let resolved_bin_path = resolve_bin_path(app_bin_path)?;
+    let app_text_path = resolve_text_path(&resolved_bin_path)?;
+    Ok(ProgramImage::read(&resolved_bin_path, &app_text_path)?)
 }
 
 fn resolve_bin_path(path: &Path) -> Result<PathBuf> {
@@ -240,7 +76,7 @@ fn resolve_bin_path(path: &Path) -> Result<PathBuf> {
     Ok(app_bin_path)
 }
 
-fn resolve_text_path(app_bin_path: &Path) -> Result<PathBuf> {
+pub(crate) fn resolve_text_path(app_bin_path: &Path) -> Result<PathBuf> {
     let mut app_text_path = app_bin_path.to_path_buf();
     app_text_path.set_extension("text");
 
@@ -265,24 +101,50 @@ fn base_path(app_bin_path: &Path) -> Result<String> {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "aggregator"))]
 mod tests {
-    use super::verify_expected_output;
+    use super::*;
+    use crate::Verifier;
+    use airbender_verifier::VerificationKey;
 
     #[test]
-    fn verify_expected_output_accepts_matching_words() {
-        let mut verifier_output = [0u32; 16];
-        verifier_output[0] = 42;
-
-        verify_expected_output(Some(&42u32), verifier_output).expect("matching output must verify");
-    }
-
-    #[test]
-    fn verify_expected_output_rejects_mismatch() {
-        let verifier_output = [0u32; 16];
-
-        let err = verify_expected_output(Some(&1u32), verifier_output)
-            .expect_err("mismatching output must fail verification");
-        assert!(err.to_string().contains("public output mismatch"));
+    #[ignore = "computes real setups on the CPU; run by the aggregator CI job"]
+    fn computes_vks_for_a_real_guest() {
+        let program = crate::Aggregator::program().expect("load embedded aggregation guest");
+        let image = program.image();
+
+        let base = unrolled_vk(&image, ProverLevel::Base).expect("base vk");
+        assert_eq!(base.image_id, program.image_id());
+        assert_eq!(base.program_commitment, None);
+
+        let unrolled = unrolled_vk(&image, ProverLevel::RecursionUnrolled).expect("unrolled vk");
+        assert_eq!(unrolled.image_id, program.image_id());
+        let unrolled_commitment = unrolled
+            .program_commitment
+            .expect("recursion keys commit to the program");
+
+        let unified = unified_vk(&image).expect("unified vk");
+        assert_eq!(unified.image_id, program.image_id());
+        assert_ne!(unified.program_commitment, unrolled_commitment);
+
+        let verifier = program
+            .real_verifier(ProverLevel::RecursionUnified)
+            .build()
+            .expect("build verifier");
+        let VerificationKey::RealUnified(generated) = verifier.generate_vk().expect("generate vk")
+        else {
+            panic!("recursion-unified verifier must generate a unified key");
+        };
+        assert_eq!(
+            generated.vk.program_commitment, unified.program_commitment,
+            "program commitment must be deterministic"
+        );
+        let decoded = VerificationKey::from_bytes(
+            &VerificationKey::RealUnified(generated)
+                .to_bytes()
+                .expect("encode vk"),
+        )
+        .expect("decode vk");
+        assert_eq!(decoded.image_id(), program.image_id());
     }
 }
@@ -0,0 +1,298 @@
+//! Dedicated worker threads backing the async host APIs.
+
+use crate::error::{panic_payload_to_string, HostError, Result};
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::task::{Context, Poll};
+use tokio::sync::oneshot;
+
+type Job<S> = Box<dyn FnOnce(&S) + Send + 'static>;
+
+enum WorkerCommand<S> {
+    Run(Job<S>),
+    Shutdown,
+}
+
+/// Owns a blocking service on a dedicated thread and executes submitted jobs sequentially.
+///
+/// The service is created on the worker thread itself, so it does not need to be `Send`.
+/// Panics inside a job are not caught: the thread is torn down and the worker becomes
+/// poisoned, mirroring the GPU prover behavior.
+///
+/// Dropping the worker does not wait for the thread: it finishes the job it is running, skips
+/// jobs whose futures were dropped, and exits.
+pub(crate) struct Worker<S> {
+    label: &'static str,
+    make_error: fn(String) -> HostError,
+    command_tx: mpsc::Sender<WorkerCommand<S>>,
+    poisoned: Arc<AtomicBool>,
+}
+
+impl<S: 'static> Worker<S> {
+    /// Spawn the worker thread and wait, without blocking the executor, for `init` to finish.
+    pub(crate) async fn spawn<F>(
+        label: &'static str,
+        make_error: fn(String) -> HostError,
+        init: F,
+    ) -> Result<Self>
+    where
+        F: FnOnce() -> Result<S> + Send + 'static,
+    {
+        let (command_tx, command_rx) = mpsc::channel();
+        let (init_tx, init_rx) = oneshot::channel();
+
+        std::thread::Builder::new()
+            .name(format!("airbender-async-{label}"))
+            .spawn(move || worker_loop(label, make_error, command_rx, init_tx, init))
+            .map_err(|err| make_error(format!("failed to spawn {label} worker thread: {err}")))?;
+
+        match init_rx.await {
+            Ok(Ok(())) => Ok(Self {
+                label,
+                make_error,
+                command_tx,
+                poisoned: Arc::new(AtomicBool::new(false)),
+            }),
+            Ok(Err(err)) => Err(err),
+            Err(_) => Err(make_error(format!(
+                "{label} worker exited during initialization"
+            ))),
+        }
+    }
+
+    pub(crate) fn is_poisoned(&self) -> bool {
+        self.poisoned.load(Ordering::SeqCst)
+    }
+
+    /// Queue a job and return a future resolving to its result.
+    pub(crate) fn submit<T, F>(&self, job: F) -> JobFuture<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&S) -> Result<T> + Send + 'static,
+    {
+        let (response_tx, response_rx) = oneshot::channel();
+        let poisoned = Arc::clone(&self.poisoned);
+        let command = WorkerCommand::Run(Box::new(move |service: &S| {
+            // The receiver is gone when the caller dropped the future before the job started.
+            if response_tx.is_closed() {
+                return;
+            }
+            // Locals are dropped before captures, so the worker is marked as poisoned
+            // before the response sender is released during unwinding.
+            let _poison_guard = PoisonOnPanic(poisoned);
+            let result = job(service);
+            let _ = response_tx.send(result);
+        }));
+
+        // If the worker is gone, the command (and its response sender) is dropped here,
+        // and the returned future resolves to a poisoned-worker error.
+        let _ = self.command_tx.send(command);
+
+        JobFuture {
+            response_rx,
+            label: self.label,
+            make_error: self.make_error,
+        }
+    }
+}
+
+impl<S> Drop for Worker<S> {
+    fn drop(&mut self) {
+        // Joining here would block the dropping (possibly async) thread until the running job
+        // finishes, so the thread is detached instead.
+        let _ = self.command_tx.send(WorkerCommand::Shutdown);
+    }
+}
+
+fn worker_loop<S, F>(
+    label: &'static str,
+    make_error: fn(String) -> HostError,
+    command_rx: mpsc::Receiver<WorkerCommand<S>>,
+    init_tx: oneshot::Sender<Result<()>>,
+    init: F,
+) where
+    F: FnOnce() -> Result<S>,
+{
+    let service = match std::panic::catch_unwind(AssertUnwindSafe(init)) {
+        Ok(Ok(service)) => service,
+        Ok(Err(err)) => {
+            let _ = init_tx.send(Err(err));
+            return;
+        }
+        Err(payload) => {
+            let _ = init_tx.send(Err(make_error(format!(
+                "{label} worker panicked during initialization: {}",
+                panic_payload_to_string(payload)
+            ))));
+            return;
+        }
+    };
+
+    if init_tx.send(Ok(())).is_err() {
+        return;
+    }
+
+    while let Ok(command) = command_rx.recv() {
+        match command {
+            WorkerCommand::Run(job) => job(&service),
+            WorkerCommand::Shutdown => break,
+        }
+    }
+}
+
+struct PoisonOnPanic(Arc<AtomicBool>);
+
+impl Drop for PoisonOnPanic {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Future resolving to the result of a job offloaded to a worker thread.
+///
+/// Dropping the future cancels the job if the worker has not started it yet.
+/// A job that is already running completes, and its result is discarded.
+#[must_use = "futures do nothing unless polled, and dropping this future cancels the job"]
+pub struct JobFuture<T> {
+    response_rx: oneshot::Receiver<Result<T>>,
+    label: &'static str,
+    make_error: fn(String) -> HostError,
+}
+
+impl<T> Future for JobFuture<T> {
+    type Output = Result<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let label = self.label;
+        let make_error = self.make_error;
+        Pin::new(&mut self.response_rx).poll(cx).map(|response| {
+            response.unwrap_or_else(|_| {
+                Err(make_error(format!(
+                    "{label} worker stopped due to a panic and is now poisoned"
+                )))
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Worker;
+    use crate::error::HostError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{mpsc, Arc};
+
+    #[tokio::test]
+    async fn runs_jobs_on_worker_thread() {
+        let worker = Worker::spawn("test", HostError::Runner, || Ok(40u32))
+            .await
+            .expect("spawn worker");
+
+        let value = worker
+            .submit(|base: &u32| Ok(*base + 2))
+            .await
+            .expect("job result");
+        assert_eq!(value, 42);
+    }
+
+    #[tokio::test]
+    async fn propagates_initialization_errors() {
+        let err = match Worker::<u32>::spawn("test", HostError::Runner, || {
+            Err(HostError::Runner("init failed".to_string()))
+        })
+        .await
+        {
+            Ok(_) => panic!("initialization error must be propagated"),
+            Err(err) => err,
+        };
+        assert_eq!(err.to_string(), "runner error: init failed");
+    }
+
+    #[tokio::test]
+    async fn dropped_future_cancels_pending_job() {
+        let worker = Worker::spawn("test", HostError::Runner, || Ok(()))
+            .await
+            .expect("spawn worker");
+        let executed = Arc::new(AtomicUsize::new(0));
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        // Block the worker so the second job stays queued while its future is dropped.
+        let blocking = worker.submit(move |_: &()| {
+            let _ = release_rx.recv();
+            Ok(())
+        });
+        let cancelled = {
+            let executed = Arc::clone(&executed);
+            worker.submit(move |_: &()| {
+                executed.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+        };
+        drop(cancelled);
+        release_tx.send(()).expect("release worker");
+        blocking.await.expect("blocking job result");
+
+        let executed_after = {
+            let executed = Arc::clone(&executed);
+            worker.submit(move |_: &()| Ok(executed.load(Ordering::SeqCst)))
+        };
+        assert_eq!(executed_after.await.expect("job result"), 0);
+    }
+
+    #[tokio::test]
+    async fn panicking_job_poisons_worker() {
+        let worker = Worker::spawn("test", HostError::Runner, || Ok(()))
+            .await
+            .expect("spawn worker");
+
+        let err = worker
+            .submit(|_: &()| -> crate::error::Result<()> { panic!("job panic") })
+            .await
+            .expect_err("panicking job must fail");
+        assert!(err.to_string().contains("poisoned"));
+        assert!(worker.is_poisoned());
+
+        let err = worker
+            .submit(|_: &()| Ok(()))
+            .await
+            .expect_err("poisoned worker must reject jobs");
+        assert!(err.to_string().contains("poisoned"));
+    }
+
+    #[tokio::test]
+    async fn reports_initialization_panics() {
+        let err =
+            match Worker::<u32>::spawn("test", HostError::Runner, || panic!("init panic")).await {
+                Ok(_) => panic!("initialization panic must be reported"),
+                Err(err) => err,
+            };
+        assert!(err.to_string().contains("init panic"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn drop_does_not_wait_for_running_job() {
+        let worker = Worker::spawn("test", HostError::Runner, || Ok(()))
+            .await
+            .expect("spawn worker");
+        let (started_tx, started_rx) = mpsc::channel::<()>();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let running = worker.submit(move |_: &()| {
+            let _ = started_tx.send(());
+            let _ = release_rx.recv();
+            Ok(())
+        });
+        started_rx.recv().expect("job started");
+        // Would deadlock if dropping joined the worker thread, since the job waits for us.
+        drop(worker);
+        release_tx.send(()).expect("release worker");
+        running
+            .await
+            .expect("running job completes after the worker is dropped");
+    }
+}
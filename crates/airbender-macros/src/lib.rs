@@ -1,4 +1,7 @@
-//! Procedural macros for Airbender guest programs.
+//! Procedural macros for Airbender guest programs, and for embedding them in host binaries.
+
+#[cfg(feature = "program")]
+mod program;
 
 use proc_macro::TokenStream;
 use quote::quote;
@@ -109,3 +112,15 @@ pub fn main(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     expanded.into()
 }
+
+/// Embed a dist directory in the binary, verifying it at compile time.
+///
+/// Re-exported by `airbender-host`, which documents its usage.
+#[cfg(feature = "program")]
+#[proc_macro]
+pub fn include_program(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as program::DistPath);
+    program::expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
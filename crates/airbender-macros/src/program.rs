@@ -0,0 +1,160 @@
+//! Compile-time embedding of verified dist directories.
+
+use airbender_core::hex;
+use airbender_core::host::bundle::MANIFEST_FILE;
+use airbender_core::host::manifest::Manifest;
+use airbender_core::image_id::{ImageId, MachineConfig};
+use proc_macro2::TokenStream;
+use quote::quote;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Error, Expr, Lit, LitStr, Token,
+};
+
+/// Dist directory argument: a string literal or `concat!` of literals and `env!` lookups.
+pub(crate) struct DistPath {
+    value: String,
+    span: proc_macro2::Span,
+}
+
+impl Parse for DistPath {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let expr: Expr = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        let span = syn::spanned::Spanned::span(&expr);
+        Ok(Self {
+            value: eval_path_expr(&expr)?,
+            span,
+        })
+    }
+}
+
+fn eval_path_expr(expr: &Expr) -> syn::Result<String> {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(value) => Ok(value.value()),
+            other => Err(Error::new(other.span(), "expected a string literal")),
+        },
+        Expr::Macro(mac) if mac.mac.path.is_ident("concat") => {
+            let parts = mac
+                .mac
+                .parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)?;
+            parts.iter().map(eval_path_expr).collect()
+        }
+        Expr::Macro(mac) if mac.mac.path.is_ident("env") => {
+            let name: LitStr = mac.mac.parse_body()?;
+            std::env::var(name.value()).map_err(|_| {
+                Error::new(
+                    name.span(),
+                    format!("environment variable `{}` not defined", name.value()),
+                )
+            })
+        }
+        other => Err(Error::new(
+            syn::spanned::Spanned::span(other),
+            "expected a string literal, `concat!` or `env!`",
+        )),
+    }
+}
+
+pub(crate) fn expand(input: DistPath) -> syn::Result<TokenStream> {
+    let dist_dir = resolve_dist_dir(&input.value);
+    let manifest = verify_dist(&dist_dir).map_err(|message| {
+        Error::new(
+            input.span,
+            format!(
+                "cannot embed program from {}: {message}",
+                dist_dir.display()
+            ),
+        )
+    })?;
+
+    let files = std::iter::once(MANIFEST_FILE)
+        .chain(manifest.artifact_entries().map(|entry| entry.path.as_str()))
+        .map(|name| {
+            let path = dist_dir.join(name).display().to_string();
+            quote! { (#name, ::core::include_bytes!(#path).as_slice()) }
+        });
+    Ok(quote! {
+        ::airbender_host::Program::from_embedded(&[#(#files),*])
+    })
+}
+
+/// Relative paths resolve against the invoking file, like `include_bytes!`.
+fn resolve_dist_dir(value: &str) -> PathBuf {
+    let path = Path::new(value);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+
+    let source_dir = proc_macro::Span::call_site()
+        .local_file()
+        .and_then(|file| file.parent().map(Path::to_path_buf));
+    let base = match source_dir {
+        Some(dir) if dir.is_absolute() => dir,
+        Some(dir) => std::env::current_dir().unwrap_or_default().join(dir),
+        None => PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default()),
+    };
+    base.join(path)
+}
+
+/// Applies the checks of `Program::load` to the dist directory at compile time.
+fn verify_dist(dist_dir: &Path) -> Result<Manifest, String> {
+    let manifest =
+        Manifest::read_from_file(&dist_dir.join(MANIFEST_FILE)).map_err(|err| err.to_string())?;
+    for entry in manifest.artifact_entries() {
+        let path = dist_dir.join(&entry.path);
+        let contents =
+            std::fs::read(&path).map_err(|err| format!("cannot read {}: {err}", entry.path))?;
+        let actual = hex::encode(&Sha256::digest(&contents));
+        if !entry.sha256.eq_ignore_ascii_case(&actual) {
+            return Err(format!(
+                "sha256 mismatch for {}: manifest records `{}`, got `{actual}`",
+                entry.path, entry.sha256
+            ));
+        }
+    }
+
+    if let Some(expected) = manifest.image_id {
+        let read = |name: &str| std::fs::read(dist_dir.join(name)).map_err(|err| err.to_string());
+        let actual = ImageId::compute(
+            &read(&manifest.bin.path)?,
+            &read(&manifest.text.path)?,
+            MachineConfig::default(),
+        );
+        if actual != expected {
+            return Err(format!(
+                "`image_id` mismatch: expected `{expected}`, got `{actual}`"
+            ));
+        }
+    }
+
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_embedded_dist_digests() {
+        let fixture =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../airbender-host/tests/fixtures/app");
+        let dist = tempfile::tempdir().expect("create temp dir");
+        for entry in std::fs::read_dir(&fixture).expect("read fixture") {
+            let entry = entry.expect("fixture entry");
+            std::fs::copy(entry.path(), dist.path().join(entry.file_name())).expect("copy fixture");
+        }
+        let manifest = verify_dist(dist.path()).expect("fixture dist must verify");
+
+        let bin_path = dist.path().join(&manifest.bin.path);
+        let mut bin = std::fs::read(&bin_path).expect("read app.bin");
+        bin[0] ^= 1;
+        std::fs::write(&bin_path, bin).expect("tamper app.bin");
+        let err = verify_dist(dist.path()).expect_err("tampered dist must be rejected");
+        assert!(err.contains("sha256 mismatch for app.bin"), "{err}");
+    }
+}
@@ -0,0 +1,72 @@
+#ifndef AIRBENDER_VERIFIER_H
+#define AIRBENDER_VERIFIER_H
+
+/* Generated by cbindgen from crates/airbender-verifier-ffi/src/lib.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The proof verified.
+ */
+#define AIRBENDER_OK 0
+
+/**
+ * A required pointer argument was null.
+ */
+#define AIRBENDER_ERR_INVALID_ARGUMENT 1
+
+/**
+ * The proof or verification key bytes could not be decoded.
+ */
+#define AIRBENDER_ERR_DECODE 2
+
+/**
+ * The proof did not verify against the verification key or expected output.
+ */
+#define AIRBENDER_ERR_VERIFICATION 3
+
+/**
+ * The verifier panicked.
+ */
+#define AIRBENDER_ERR_INTERNAL 4
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Verify proof bytes against verification key bytes, as written by `cargo airbender prove`
+ * and `cargo airbender generate-vk`.
+ *
+ * `expected_output_ptr` is either null or points to 8 words compared against `x10..x17`.
+ * Returns `AIRBENDER_OK` on success, otherwise one of the `AIRBENDER_ERR_*` codes; the
+ * message is available from `airbender_last_error`.
+ *
+ * # Safety
+ *
+ * `proof_ptr` and `vk_ptr` must be valid for reads of `proof_len` and `vk_len` bytes.
+ * `expected_output_ptr` must be null or valid for reads of 8 `uint32_t` words.
+ */
+int32_t airbender_verify(const uint8_t *proof_ptr,
+                         size_t proof_len,
+                         const uint8_t *vk_ptr,
+                         size_t vk_len,
+                         const uint32_t *expected_output_ptr);
+
+/**
+ * Message of the last failed `airbender_verify` call on the calling thread, or null.
+ *
+ * The string is owned by the library and stays valid until the next `airbender_verify`
+ * call on the same thread.
+ */
+const char *airbender_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AIRBENDER_VERIFIER_H */
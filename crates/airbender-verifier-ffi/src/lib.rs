@@ -0,0 +1,195 @@
+//! C ABI for `airbender-verifier`.
+//!
+//! The matching header is `include/airbender_verifier.h`, generated by `cbindgen` from this file.
+//! Build the shared library with `cargo build -p airbender-verifier-ffi --release`.
+
+use airbender_verifier::{Commit, VerifierError};
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// The proof verified.
+pub const AIRBENDER_OK: i32 = 0;
+/// A required pointer argument was null.
+pub const AIRBENDER_ERR_INVALID_ARGUMENT: i32 = 1;
+/// The proof or verification key bytes could not be decoded.
+pub const AIRBENDER_ERR_DECODE: i32 = 2;
+/// The proof did not verify against the verification key or expected output.
+pub const AIRBENDER_ERR_VERIFICATION: i32 = 3;
+/// The verifier panicked.
+pub const AIRBENDER_ERR_INTERNAL: i32 = 4;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Verify proof bytes against verification key bytes, as written by `cargo airbender prove`
+/// and `cargo airbender generate-vk`.
+///
+/// `expected_output_ptr` is either null or points to 8 words compared against `x10..x17`.
+/// Returns `AIRBENDER_OK` on success, otherwise one of the `AIRBENDER_ERR_*` codes; the
+/// message is available from `airbender_last_error`.
+///
+/// # Safety
+///
+/// `proof_ptr` and `vk_ptr` must be valid for reads of `proof_len` and `vk_len` bytes.
+/// `expected_output_ptr` must be null or valid for reads of 8 `uint32_t` words.
+#[no_mangle]
+pub unsafe extern "C" fn airbender_verify(
+    proof_ptr: *const u8,
+    proof_len: usize,
+    vk_ptr: *const u8,
+    vk_len: usize,
+    expected_output_ptr: *const u32,
+) -> i32 {
+    clear_last_error();
+    if proof_ptr.is_null() || vk_ptr.is_null() {
+        return fail(
+            AIRBENDER_ERR_INVALID_ARGUMENT,
+            "proof and verification key pointers must not be null".to_string(),
+        );
+    }
+
+    let proof = std::slice::from_raw_parts(proof_ptr, proof_len);
+    let vk = std::slice::from_raw_parts(vk_ptr, vk_len);
+    let expected_output = (!expected_output_ptr.is_null()).then(|| {
+        let mut words = [0u32; 8];
+        words.copy_from_slice(std::slice::from_raw_parts(expected_output_ptr, 8));
+        words
+    });
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        airbender_verifier::verify_proof_bytes(
+            proof,
+            vk,
+            expected_output.as_ref().map(|words| words as &dyn Commit),
+        )
+    }));
+    match result {
+        Ok(Ok(_)) => AIRBENDER_OK,
+        Ok(Err(err @ VerifierError::Codec(_))) => fail(AIRBENDER_ERR_DECODE, err.to_string()),
+        Ok(Err(err)) => fail(AIRBENDER_ERR_VERIFICATION, err.to_string()),
+        Err(_) => fail(AIRBENDER_ERR_INTERNAL, "verifier panicked".to_string()),
+    }
+}
+
+/// Message of the last failed `airbender_verify` call on the calling thread, or null.
+///
+/// The string is owned by the library and stays valid until the next `airbender_verify`
+/// call on the same thread.
+#[no_mangle]
+pub extern "C" fn airbender_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+fn fail(status: i32, message: String) -> i32 {
+    // Error messages never contain NUL bytes, but do not let one turn into a panic.
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+    status
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use airbender_verifier::{
+        DevProof, DevSigningKey, DevVerificationKey, ImageId, Proof, Receipt, VerificationKey,
+    };
+    use std::ffi::CStr;
+    use std::path::Path;
+
+    fn last_error() -> Option<String> {
+        let message = airbender_last_error();
+        (!message.is_null()).then(|| {
+            unsafe { CStr::from_ptr(message) }
+                .to_string_lossy()
+                .into_owned()
+        })
+    }
+
+    #[test]
+    fn airbender_verify_reports_status_and_last_error() {
+        let image_id = ImageId::from_bytes([1; 32]);
+        let proof = Proof::Dev(
+            DevProof::sign(
+                image_id,
+                [0; 32],
+                Receipt::from_registers([0; 32]),
+                1,
+                &DevSigningKey::from_seed([7; 32]),
+            )
+            .expect("sign dev proof"),
+        )
+        .to_bytes()
+        .expect("encode proof");
+        let vk = VerificationKey::Dev(DevVerificationKey { image_id })
+            .to_bytes()
+            .expect("encode vk");
+        let garbage = [0xffu8; 3];
+        let expected_output = [0u32; 8];
+
+        let status =
+            unsafe { airbender_verify(ptr::null(), 0, vk.as_ptr(), vk.len(), ptr::null()) };
+        assert_eq!(status, AIRBENDER_ERR_INVALID_ARGUMENT);
+        assert!(last_error()
+            .expect("error message")
+            .contains("must not be null"));
+
+        let status = unsafe {
+            airbender_verify(
+                garbage.as_ptr(),
+                garbage.len(),
+                vk.as_ptr(),
+                vk.len(),
+                ptr::null(),
+            )
+        };
+        assert_eq!(status, AIRBENDER_ERR_DECODE);
+        assert!(last_error().expect("error message").contains("codec error"));
+
+        let status = unsafe {
+            airbender_verify(
+                proof.as_ptr(),
+                proof.len(),
+                vk.as_ptr(),
+                vk.len(),
+                expected_output.as_ptr(),
+            )
+        };
+        assert_eq!(status, AIRBENDER_ERR_VERIFICATION);
+        assert!(last_error().expect("error message").contains("dev proofs"));
+    }
+
+    #[test]
+    fn header_is_up_to_date() {
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+            .expect("read cbindgen.toml");
+        let mut generated = Vec::new();
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src(crate_dir.join("src/lib.rs"))
+            .generate()
+            .expect("generate header")
+            .write(&mut generated);
+
+        let header_path = crate_dir.join("include/airbender_verifier.h");
+        if std::env::var_os("AIRBENDER_BLESS").is_some() {
+            std::fs::write(&header_path, &generated).expect("write header");
+        }
+        let committed = std::fs::read(&header_path).expect("read header");
+        assert!(
+            committed == generated,
+            "include/airbender_verifier.h is stale; rerun this test with AIRBENDER_BLESS=1"
+        );
+    }
+}
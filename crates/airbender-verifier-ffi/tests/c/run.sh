@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+# Builds the verifier shared library, then compiles and runs verify_test.c against it.
+# Extra arguments (`<proof.bin> <vk.bin>`) are forwarded to the test program.
+set -euo pipefail
+
+crate_dir="$(cd "$(dirname "${BASH_SOURCE[0]}")/../.." && pwd)"
+cargo build --release -p airbender-verifier-ffi --manifest-path "${crate_dir}/Cargo.toml"
+target_dir="$(cargo metadata --format-version 1 --no-deps --manifest-path "${crate_dir}/Cargo.toml" | jq -r .target_directory)"
+lib_dir="${target_dir}/release"
+
+out_dir="$(mktemp -d)"
+trap 'rm -rf "${out_dir}"' EXIT
+"${CC:-cc}" -std=c99 -Wall -Wextra -Werror \
+    -I "${crate_dir}/include" \
+    "${crate_dir}/tests/c/verify_test.c" \
+    -L "${lib_dir}" -lairbender_verifier_ffi \
+    -Wl,-rpath,"${lib_dir}" \
+    -o "${out_dir}/verify_test"
+"${out_dir}/verify_test" "$@"
@@ -0,0 +1,86 @@
+/*
+ * Exercises the C ABI of airbender-verifier-ffi. Run through `run.sh`.
+ *
+ * With no arguments it checks argument validation and error reporting. Pass
+ * `<proof.bin> <vk.bin>` to additionally verify a real proof.
+ */
+#include "airbender_verifier.h"
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+static int failures = 0;
+
+#define CHECK(cond)                                                 \
+    do {                                                            \
+        if (!(cond)) {                                              \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, \
+                    __LINE__, #cond);                               \
+            failures++;                                             \
+        }                                                           \
+    } while (0)
+
+static int read_file(const char *path, uint8_t **data, size_t *len) {
+    FILE *file = fopen(path, "rb");
+    if (file == NULL) {
+        return -1;
+    }
+    fseek(file, 0, SEEK_END);
+    long size = ftell(file);
+    fseek(file, 0, SEEK_SET);
+    *data = malloc(size > 0 ? (size_t)size : 1);
+    *len = fread(*data, 1, (size_t)size, file);
+    fclose(file);
+    return *len == (size_t)size ? 0 : -1;
+}
+
+static void check_invalid_input(void) {
+    const uint8_t garbage[] = {0xff, 0xff, 0xff};
+
+    CHECK(airbender_last_error() == NULL);
+
+    CHECK(airbender_verify(NULL, 0, garbage, sizeof(garbage), NULL) ==
+          AIRBENDER_ERR_INVALID_ARGUMENT);
+    CHECK(airbender_last_error() != NULL);
+    CHECK(strstr(airbender_last_error(), "must not be null") != NULL);
+
+    CHECK(airbender_verify(garbage, sizeof(garbage), garbage, sizeof(garbage),
+                           NULL) == AIRBENDER_ERR_DECODE);
+    CHECK(strstr(airbender_last_error(), "codec error") != NULL);
+}
+
+static void check_real_proof(const char *proof_path, const char *vk_path) {
+    uint8_t *proof = NULL;
+    uint8_t *vk = NULL;
+    size_t proof_len = 0;
+    size_t vk_len = 0;
+
+    CHECK(read_file(proof_path, &proof, &proof_len) == 0);
+    CHECK(read_file(vk_path, &vk, &vk_len) == 0);
+    if (proof != NULL && vk != NULL) {
+        int32_t status = airbender_verify(proof, proof_len, vk, vk_len, NULL);
+        if (status != AIRBENDER_OK) {
+            fprintf(stderr, "verification failed (%d): %s\n", status,
+                    airbender_last_error());
+        }
+        CHECK(status == AIRBENDER_OK);
+        CHECK(airbender_last_error() == NULL);
+    }
+    free(proof);
+    free(vk);
+}
+
+int main(int argc, char **argv) {
+    check_invalid_input();
+    if (argc == 3) {
+        check_real_proof(argv[1], argv[2]);
+    }
+
+    if (failures != 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return 1;
+    }
+    printf("airbender-verifier-ffi C tests passed\n");
+    return 0;
+}
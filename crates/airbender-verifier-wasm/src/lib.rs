@@ -0,0 +1,122 @@
+//! WebAssembly bindings for `airbender-verifier`.
+//!
+//! Build for browsers or node with `wasm-pack build crates/airbender-verifier-wasm`.
+
+use airbender_verifier::{Commit, ProverLevel, VerifiedOutput};
+use wasm_bindgen::prelude::*;
+
+/// Public output of a successfully verified proof.
+#[wasm_bindgen]
+pub struct VerifiedProof {
+    inner: VerifiedOutput,
+}
+
+#[wasm_bindgen]
+impl VerifiedProof {
+    /// Proof level: `base`, `recursion-unrolled` or `recursion-unified`.
+    #[wasm_bindgen(getter)]
+    pub fn level(&self) -> String {
+        level_name(self.inner.level).to_string()
+    }
+
+    /// Hex-encoded image id of the proven program.
+    #[wasm_bindgen(getter, js_name = imageId)]
+    pub fn image_id(&self) -> String {
+        self.inner.image_id.to_hex()
+    }
+
+    /// Verified public output (`x10..x17`).
+    #[wasm_bindgen(getter)]
+    pub fn output(&self) -> Vec<u32> {
+        self.inner.output.to_vec()
+    }
+
+    /// Verified extended public output (`x10..x25`).
+    #[wasm_bindgen(getter, js_name = outputExtended)]
+    pub fn output_extended(&self) -> Vec<u32> {
+        self.inner.output_extended.to_vec()
+    }
+}
+
+/// Verify proof bytes against verification key bytes, as written by `cargo airbender prove`
+/// and `cargo airbender generate-vk`.
+///
+/// `expectedOutput` holds up to 8 words compared against `x10..x17`; missing words are zero.
+#[wasm_bindgen(js_name = verifyProof)]
+pub fn verify_proof(
+    proof: &[u8],
+    vk: &[u8],
+    expected_output: Option<Vec<u32>>,
+) -> Result<VerifiedProof, JsError> {
+    let expected_output = expected_output
+        .map(|words| output_words(&words))
+        .transpose()
+        .map_err(|err| JsError::new(&err))?;
+    airbender_verifier::verify_proof_bytes(
+        proof,
+        vk,
+        expected_output.as_ref().map(|words| words as &dyn Commit),
+    )
+    .map(|inner| VerifiedProof { inner })
+    .map_err(|err| JsError::new(&err.to_string()))
+}
+
+fn output_words(words: &[u32]) -> Result<[u32; 8], String> {
+    if words.len() > 8 {
+        return Err(format!(
+            "expected output supports at most 8 words, got {}",
+            words.len()
+        ));
+    }
+    let mut padded = [0u32; 8];
+    padded[..words.len()].copy_from_slice(words);
+    Ok(padded)
+}
+
+fn level_name(level: ProverLevel) -> &'static str {
+    match level {
+        ProverLevel::Base => "base",
+        ProverLevel::RecursionUnrolled => "recursion-unrolled",
+        ProverLevel::RecursionUnified => "recursion-unified",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use airbender_verifier::{
+        DevProof, DevSigningKey, DevVerificationKey, ImageId, Proof, Receipt, VerificationKey,
+    };
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[test]
+    fn output_words_pads_and_bounds_expected_output() {
+        assert_eq!(output_words(&[7, 8]), Ok([7, 8, 0, 0, 0, 0, 0, 0]));
+        assert!(output_words(&[0; 9]).is_err());
+    }
+
+    // `JsError` needs a JS host, so this only runs on wasm32 under `wasm-bindgen-test-runner`.
+    #[wasm_bindgen_test]
+    fn verify_proof_rejects_dev_proofs_and_malformed_input() {
+        let image_id = ImageId::from_bytes([1; 32]);
+        let proof = Proof::Dev(
+            DevProof::sign(
+                image_id,
+                [0; 32],
+                Receipt::from_registers([0; 32]),
+                1,
+                &DevSigningKey::from_seed([7; 32]),
+            )
+            .expect("sign dev proof"),
+        )
+        .to_bytes()
+        .expect("encode proof");
+        let vk = VerificationKey::Dev(DevVerificationKey { image_id })
+            .to_bytes()
+            .expect("encode vk");
+
+        assert!(verify_proof(&proof, &vk, None).is_err());
+        assert!(verify_proof(&[0xff; 3], &vk, None).is_err());
+        assert!(verify_proof(&proof, &vk, Some(vec![0; 9])).is_err());
+    }
+}
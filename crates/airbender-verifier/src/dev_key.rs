@@ -0,0 +1,144 @@
+//! Local ed25519 keys that sign dev proofs.
+//!
+//! Dev proofs carry no cryptographic proof of execution. Signing them with a key generated on the
+//! developer's machine keeps them from being fabricated by anyone who can write a file, and the
+//! embedded [`DevProofMarker`] keeps them from being mistaken for real proofs.
+
+use crate::error::{Result, VerifierError};
+use airbender_core::hex;
+use ed25519_dalek::{Signer, SigningKey, Verifier, VerifyingKey};
+use std::fmt;
+
+/// Marker string embedded in every serialized dev proof.
+pub const DEV_PROOF_MARKER: &str = "AIRBENDER DEV PROOF - NOT A VALID ATTESTATION";
+
+/// Serializes as [`DEV_PROOF_MARKER`] and refuses to deserialize from anything else.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DevProofMarker;
+
+impl serde::Serialize for DevProofMarker {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(DEV_PROOF_MARKER)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for DevProofMarker {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let marker = String::deserialize(deserializer)?;
+        if marker != DEV_PROOF_MARKER {
+            return Err(serde::de::Error::custom(format!(
+                "invalid dev proof marker `{marker}`"
+            )));
+        }
+        Ok(Self)
+    }
+}
+
+/// Secret key signing dev proofs.
+#[derive(Clone)]
+pub struct DevSigningKey(SigningKey);
+
+impl DevSigningKey {
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self(SigningKey::from_bytes(&seed))
+    }
+
+    /// Secret seed, as stored in the dev key file.
+    pub fn seed(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    pub fn public_key(&self) -> DevPublicKey {
+        DevPublicKey(self.0.verifying_key().to_bytes())
+    }
+
+    pub(crate) fn sign(&self, message: &[u8]) -> DevSignature {
+        DevSignature(self.0.sign(message))
+    }
+}
+
+impl fmt::Debug for DevSigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DevSigningKey({})", self.public_key())
+    }
+}
+
+/// Public key of a dev key, trusted by dev verifiers.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct DevPublicKey([u8; 32]);
+
+impl DevPublicKey {
+    pub const fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    pub(crate) fn verify(&self, message: &[u8], signature: &DevSignature) -> Result<()> {
+        let key = VerifyingKey::from_bytes(&self.0)
+            .map_err(|_| VerifierError::Verification(format!("invalid dev public key {self}")))?;
+        key.verify(message, &signature.0)
+            .map_err(|_| VerifierError::Verification("dev proof signature is invalid".to_string()))
+    }
+}
+
+impl fmt::Display for DevPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(&self.0))
+    }
+}
+
+impl fmt::Debug for DevPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DevPublicKey({self})")
+    }
+}
+
+/// Ed25519 signature of a dev proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DevSignature(ed25519_dalek::Signature);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use airbender_codec::{AirbenderCodec, AirbenderCodecV0};
+
+    #[test]
+    fn marker_roundtrips_and_rejects_other_strings() {
+        let encoded = AirbenderCodecV0::encode(&DevProofMarker).expect("encode marker");
+        assert!(encoded
+            .windows(DEV_PROOF_MARKER.len())
+            .any(|window| window == DEV_PROOF_MARKER.as_bytes()));
+        let decoded: DevProofMarker = AirbenderCodecV0::decode(&encoded).expect("decode marker");
+        assert_eq!(decoded, DevProofMarker);
+
+        let other = AirbenderCodecV0::encode(&"real proof").expect("encode string");
+        assert!(AirbenderCodecV0::decode::<DevProofMarker>(&other).is_err());
+    }
+
+    #[test]
+    fn signatures_verify_only_with_the_signing_key() {
+        let key = DevSigningKey::from_seed([7; 32]);
+        let signature = key.sign(b"message");
+
+        key.public_key()
+            .verify(b"message", &signature)
+            .expect("signature must verify");
+        assert!(key.public_key().verify(b"other", &signature).is_err());
+        assert!(DevSigningKey::from_seed([8; 32])
+            .public_key()
+            .verify(b"message", &signature)
+            .is_err());
+        assert_eq!(
+            DevSigningKey::from_seed(key.seed()).public_key(),
+            key.public_key()
+        );
+    }
+}
@@ -0,0 +1,15 @@
+#[derive(Debug, thiserror::Error)]
+pub enum VerifierError {
+    #[error("codec error: {0}")]
+    Codec(airbender_codec::CodecError),
+    #[error("verification error: {0}")]
+    Verification(String),
+}
+
+pub type Result<T> = std::result::Result<T, VerifierError>;
+
+impl From<airbender_codec::CodecError> for VerifierError {
+    fn from(err: airbender_codec::CodecError) -> Self {
+        Self::Codec(err)
+    }
+}
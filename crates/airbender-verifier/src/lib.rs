@@ -0,0 +1,24 @@
+//! Standalone verification of Airbender proofs.
+//!
+//! This crate owns the proof and verification-key formats produced by `airbender-host` and
+//! verifies real proofs without the simulator, transpiler or prover dependencies.
+
+mod dev_key;
+mod error;
+mod proof;
+mod receipt;
+mod vk;
+
+pub use airbender_core::aggregation::{claims_commitment, AggregatedClaim};
+pub use airbender_core::guest::Commit;
+pub use airbender_core::image_id::ImageId;
+pub use dev_key::{DevProofMarker, DevPublicKey, DevSignature, DevSigningKey, DEV_PROOF_MARKER};
+pub use error::{Result, VerifierError};
+pub use proof::{CircuitCounts, DevProof, Proof, ProverLevel, RealProof};
+pub use receipt::Receipt;
+pub use vk::{
+    decode_proof, verify_aggregated_proof, verify_proof, verify_proof_bytes,
+    verify_real_proof_with_vk, verify_unrolled_proof, DevVerificationKey,
+    RealUnifiedVerificationKey, RealUnrolledVerificationKey, UnifiedVk, UnrolledVk,
+    VerificationKey, VerifiedOutput,
+};
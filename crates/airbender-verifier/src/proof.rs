@@ -0,0 +1,411 @@
+use crate::dev_key::{DevProofMarker, DevPublicKey, DevSignature, DevSigningKey};
+use crate::error::{Result, VerifierError};
+use crate::receipt::Receipt;
+use airbender_codec::{AirbenderCodec, AirbenderCodecV0, AirbenderCodecV1};
+use airbender_core::image_id::ImageId;
+use std::collections::BTreeMap;
+
+/// Maximum proof layer generated by the prover.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum ProverLevel {
+    Base,
+    RecursionUnrolled,
+    #[default]
+    RecursionUnified,
+}
+
+/// Wrapper around all proof flavors produced by host provers.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[allow(clippy::large_enum_variant)] // We don't want to optimize for the efficiency of the dev proof.
+pub enum Proof {
+    Dev(DevProof),
+    Real(RealProof),
+}
+
+impl Proof {
+    /// Whether this is a dev proof, which attests nothing beyond the dev key that signed it.
+    pub fn is_dev(&self) -> bool {
+        matches!(self, Self::Dev(_))
+    }
+
+    pub fn debug_info(&self) -> String {
+        match self {
+            Self::Dev(proof) => format!(
+                "dev proof (not a valid attestation): cycles={}, output={:?}",
+                proof.cycles, proof.receipt.output
+            ),
+            Self::Real(proof) => proof.inner.debug_info(),
+        }
+    }
+
+    /// Public output (`x10..x17`) claimed by the proof.
+    ///
+    /// The value is read from the proof without verifying it.
+    pub fn output(&self) -> [u32; 8] {
+        match self {
+            Self::Dev(proof) => proof.receipt.output,
+            Self::Real(proof) => proof.output(),
+        }
+    }
+
+    /// Extended public output (`x10..x25`) claimed by the proof.
+    pub fn output_extended(&self) -> [u32; 16] {
+        match self {
+            Self::Dev(proof) => proof.receipt.output_extended,
+            Self::Real(proof) => proof.output_extended(),
+        }
+    }
+
+    /// Image id of the program the proof was produced for.
+    pub fn image_id(&self) -> ImageId {
+        match self {
+            Self::Dev(proof) => proof.image_id,
+            Self::Real(proof) => proof.image_id(),
+        }
+    }
+
+    /// Number of proven execution cycles.
+    pub fn cycles(&self) -> u64 {
+        match self {
+            Self::Dev(proof) => proof.cycles,
+            Self::Real(proof) => proof.cycles(),
+        }
+    }
+
+    /// Size of the proof encoded with the Airbender codec, as written by `cargo airbender prove`.
+    pub fn serialized_size(&self) -> Result<usize> {
+        Ok(self.to_bytes()?.len())
+    }
+
+    /// Encodes the proof as written by `cargo airbender prove`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(AirbenderCodecV1::encode(self)?)
+    }
+
+    /// Decodes a proof written by [`Proof::to_bytes`].
+    ///
+    /// Proofs encoded with codec v0 are rejected; decode them with [`Proof::from_v0_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(AirbenderCodecV1::decode(bytes)?)
+    }
+
+    /// Decodes a real proof encoded with codec v0, before proofs recorded their image id
+    /// and cycle count.
+    ///
+    /// The decoded proof reports `image_id` and zero cycles. v0 dev proofs are unsigned, so
+    /// they are rejected.
+    pub fn from_v0_bytes(bytes: &[u8], image_id: ImageId) -> Result<Self> {
+        match AirbenderCodecV0::decode::<ProofV0>(bytes)? {
+            ProofV0::Real(proof) => Ok(Self::Real(RealProof::new(
+                proof.level,
+                0,
+                image_id,
+                proof.inner,
+            ))),
+            ProofV0::Dev(_) => Err(VerifierError::Verification(
+                "v0 dev proofs are unsigned and no longer accepted; prove again".to_string(),
+            )),
+        }
+    }
+}
+
+/// Proof layout of codec v0.
+#[derive(serde::Deserialize)]
+#[allow(clippy::large_enum_variant)]
+enum ProofV0 {
+    Dev(#[allow(dead_code)] DevProofV0),
+    Real(RealProofV0),
+}
+
+#[derive(serde::Deserialize)]
+#[allow(dead_code)]
+struct DevProofV0 {
+    app_bin_hash: [u8; 32],
+    input_words_hash: [u8; 32],
+    receipt: Receipt,
+    cycles: u64,
+}
+
+#[derive(serde::Deserialize)]
+struct RealProofV0 {
+    level: ProverLevel,
+    inner: execution_utils::unrolled::UnrolledProgramProof,
+}
+
+const DEV_PROOF_DOMAIN_TAG: &[u8] = b"airbender-dev-proof-v1";
+
+/// Development proof emitted by the transpiler-based prover.
+///
+/// It records an execution instead of proving it. The signature only shows which dev key
+/// produced it, so verifiers must trust that key explicitly.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct DevProof {
+    /// Serialized as [`crate::DEV_PROOF_MARKER`], so dev proofs are recognizable in any encoding.
+    pub marker: DevProofMarker,
+    pub image_id: ImageId,
+    pub input_words_hash: [u8; 32],
+    pub receipt: Receipt,
+    pub cycles: u64,
+    pub signer: DevPublicKey,
+    pub signature: DevSignature,
+}
+
+impl DevProof {
+    /// Record an execution and sign it with `key`.
+    pub fn sign(
+        image_id: ImageId,
+        input_words_hash: [u8; 32],
+        receipt: Receipt,
+        cycles: u64,
+        key: &DevSigningKey,
+    ) -> Result<Self> {
+        let message = dev_proof_message(image_id, input_words_hash, &receipt, cycles)?;
+        Ok(Self {
+            marker: DevProofMarker,
+            image_id,
+            input_words_hash,
+            receipt,
+            cycles,
+            signer: key.public_key(),
+            signature: key.sign(&message),
+        })
+    }
+
+    /// Check that the proof was signed by `trusted_key` and has not been modified since.
+    pub fn verify_signature(&self, trusted_key: &DevPublicKey) -> Result<()> {
+        if &self.signer != trusted_key {
+            return Err(VerifierError::Verification(format!(
+                "dev proof was signed by untrusted dev key {}, expected {trusted_key}",
+                self.signer
+            )));
+        }
+        let message = dev_proof_message(
+            self.image_id,
+            self.input_words_hash,
+            &self.receipt,
+            self.cycles,
+        )?;
+        trusted_key.verify(&message, &self.signature)
+    }
+}
+
+fn dev_proof_message(
+    image_id: ImageId,
+    input_words_hash: [u8; 32],
+    receipt: &Receipt,
+    cycles: u64,
+) -> Result<Vec<u8>> {
+    let mut message = DEV_PROOF_DOMAIN_TAG.to_vec();
+    message.extend(AirbenderCodecV0::encode(&(
+        image_id,
+        input_words_hash,
+        receipt,
+        cycles,
+    ))?);
+    Ok(message)
+}
+
+/// Real cryptographic proof emitted by CPU/GPU provers.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct RealProof {
+    level: ProverLevel,
+    cycles: u64,
+    image_id: ImageId,
+    inner: execution_utils::unrolled::UnrolledProgramProof,
+}
+
+impl RealProof {
+    /// Wraps a proof produced by a prover for the program identified by `image_id`.
+    pub fn new(
+        level: ProverLevel,
+        cycles: u64,
+        image_id: ImageId,
+        inner: execution_utils::unrolled::UnrolledProgramProof,
+    ) -> Self {
+        Self {
+            level,
+            cycles,
+            image_id,
+            inner,
+        }
+    }
+
+    pub fn level(&self) -> ProverLevel {
+        self.level
+    }
+
+    /// Image id of the application program proven by the base layer.
+    pub fn image_id(&self) -> ImageId {
+        self.image_id
+    }
+
+    /// Public output (`x10..x17`) claimed by the proof, read without verification.
+    pub fn output(&self) -> [u32; 8] {
+        Receipt::from_unrolled_proof(&self.inner).output
+    }
+
+    /// Extended public output (`x10..x25`) claimed by the proof, read without verification.
+    pub fn output_extended(&self) -> [u32; 16] {
+        Receipt::from_unrolled_proof(&self.inner).output_extended
+    }
+
+    /// Commitment to the proven program chain (`x18..x25`) carried by recursion proofs.
+    ///
+    /// Returns `None` for base-layer proofs, which bind the program only through the
+    /// verification key.
+    pub fn program_commitment(&self) -> Option<[u32; 8]> {
+        match self.level {
+            ProverLevel::Base => None,
+            ProverLevel::RecursionUnrolled | ProverLevel::RecursionUnified => {
+                let mut commitment = [0u32; 8];
+                commitment.copy_from_slice(&self.output_extended()[8..]);
+                Some(commitment)
+            }
+        }
+    }
+
+    /// Number of application cycles proven by the base layer.
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    /// Number of circuit proofs contained in the final layer of this proof.
+    pub fn circuit_counts(&self) -> CircuitCounts {
+        CircuitCounts {
+            families: self
+                .inner
+                .circuit_families_proofs
+                .iter()
+                .map(|(family, proofs)| (*family, proofs.len()))
+                .collect(),
+            inits_and_teardowns: self.inner.inits_and_teardowns_proofs.len(),
+            delegations: self
+                .inner
+                .delegation_proofs
+                .iter()
+                .map(|(delegation_type, proofs)| (*delegation_type, proofs.len()))
+                .collect(),
+        }
+    }
+
+    /// Underlying unrolled proof of the final layer.
+    pub fn inner(&self) -> &execution_utils::unrolled::UnrolledProgramProof {
+        &self.inner
+    }
+}
+
+/// Circuit proof counts of a real proof layer.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CircuitCounts {
+    /// Main circuit proofs, keyed by circuit family.
+    pub families: BTreeMap<u8, usize>,
+    /// Memory init/teardown circuit proofs.
+    pub inits_and_teardowns: usize,
+    /// Delegation circuit proofs, keyed by delegation type.
+    pub delegations: BTreeMap<u32, usize>,
+}
+
+impl CircuitCounts {
+    pub fn total(&self) -> usize {
+        self.families.values().sum::<usize>()
+            + self.inits_and_teardowns
+            + self.delegations.values().sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dev_proof_exposes_claimed_output_and_cycles() {
+        let mut registers = [0u32; 32];
+        registers[10] = 42;
+        registers[25] = 7;
+        let proof = Proof::Dev(
+            DevProof::sign(
+                ImageId::from_bytes([1; 32]),
+                [2; 32],
+                Receipt::from_registers(registers),
+                1234,
+                &DevSigningKey::from_seed([3; 32]),
+            )
+            .expect("sign dev proof"),
+        );
+
+        assert_eq!(proof.output(), [42, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(proof.output_extended()[15], 7);
+        assert_eq!(proof.cycles(), 1234);
+        assert_eq!(proof.image_id(), ImageId::from_bytes([1; 32]));
+
+        let encoded = proof.to_bytes().expect("encode proof");
+        assert_eq!(proof.serialized_size().expect("proof size"), encoded.len());
+        assert!(proof.is_dev());
+        let decoded = Proof::from_bytes(&encoded).expect("decode proof");
+        assert_eq!(decoded.cycles(), 1234);
+    }
+
+    #[test]
+    fn v0_proofs_need_the_v0_decoder() {
+        // v0 `Proof::Dev` layout: variant tag, app bin hash, input hash, receipt, cycles.
+        let legacy = AirbenderCodecV0::encode(&(
+            0u32,
+            [1u8; 32],
+            [2u8; 32],
+            Receipt::from_registers([0; 32]),
+            5u64,
+        ))
+        .expect("encode v0 proof");
+
+        let err = Proof::from_bytes(&legacy).expect_err("v0 payload must be rejected");
+        assert!(err.to_string().contains("codec v0"));
+        let err = Proof::from_v0_bytes(&legacy, ImageId::from_bytes([1; 32]))
+            .expect_err("v0 dev proof must be rejected");
+        assert!(err.to_string().contains("unsigned"));
+    }
+
+    #[test]
+    fn dev_proof_signature_binds_key_and_contents() {
+        let key = DevSigningKey::from_seed([3; 32]);
+        let proof = DevProof::sign(
+            ImageId::from_bytes([1; 32]),
+            [2; 32],
+            Receipt::from_registers([0; 32]),
+            10,
+            &key,
+        )
+        .expect("sign dev proof");
+        proof
+            .verify_signature(&key.public_key())
+            .expect("signature must verify");
+
+        let other_key = DevSigningKey::from_seed([4; 32]).public_key();
+        let err = proof
+            .verify_signature(&other_key)
+            .expect_err("untrusted key must be rejected");
+        assert!(err.to_string().contains("untrusted dev key"));
+
+        let mut tampered = proof.clone();
+        tampered.receipt.output[0] = 1;
+        let err = tampered
+            .verify_signature(&key.public_key())
+            .expect_err("tampered proof must be rejected");
+        assert!(err.to_string().contains("signature is invalid"));
+
+        let mut forged = proof;
+        forged.cycles = 11;
+        forged.signer = other_key;
+        assert!(forged.verify_signature(&other_key).is_err());
+    }
+
+    #[test]
+    fn circuit_counts_total_sums_all_components() {
+        let counts = CircuitCounts {
+            families: BTreeMap::from([(1, 3), (2, 1)]),
+            inits_and_teardowns: 2,
+            delegations: BTreeMap::from([(1991, 4)]),
+        };
+        assert_eq!(counts.total(), 10);
+        assert_eq!(CircuitCounts::default().total(), 0);
+    }
+}
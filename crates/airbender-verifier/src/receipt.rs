@@ -1,3 +1,5 @@
+use execution_utils::unrolled::UnrolledProgramProof;
+
 /// Execution output captured from simulator or prover results.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct Receipt {
@@ -18,4 +20,18 @@ impl Receipt {
             output_extended,
         }
     }
+
+    /// Final register values claimed by an unrolled proof.
+    pub fn from_unrolled_proof(proof: &UnrolledProgramProof) -> Self {
+        let mut registers = [0u32; 32];
+        for (idx, reg) in proof
+            .register_final_values
+            .iter()
+            .take(registers.len())
+            .enumerate()
+        {
+            registers[idx] = reg.value;
+        }
+        Self::from_registers(registers)
+    }
 }
@@ -0,0 +1,415 @@
+use crate::error::{Result, VerifierError};
+use crate::proof::{Proof, ProverLevel, RealProof};
+use airbender_codec::{AirbenderCodec, AirbenderCodecV1, AIRBENDER_CODEC_V0};
+use airbender_core::aggregation::{claims_commitment, AggregatedClaim};
+use airbender_core::guest::Commit;
+use airbender_core::image_id::ImageId;
+use execution_utils::setups;
+use execution_utils::unified_circuit::verify_proof_in_unified_layer;
+use execution_utils::unrolled::{
+    verify_unrolled_layer_proof, UnrolledProgramProof, UnrolledProgramSetup,
+};
+
+/// Wrapper around all verification-key flavors.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum VerificationKey {
+    Dev(DevVerificationKey),
+    RealUnified(RealUnifiedVerificationKey),
+    RealUnrolled(RealUnrolledVerificationKey),
+}
+
+impl VerificationKey {
+    /// Image id of the program this key verifies proofs for.
+    pub fn image_id(&self) -> ImageId {
+        match self {
+            Self::Dev(vk) => vk.image_id,
+            Self::RealUnified(vk) => vk.vk.image_id,
+            Self::RealUnrolled(vk) => vk.vk.image_id,
+        }
+    }
+
+    /// Encodes the key as written by `cargo airbender generate-vk`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(AirbenderCodecV1::encode(self)?)
+    }
+
+    /// Decodes a key written by [`VerificationKey::to_bytes`].
+    ///
+    /// Keys encoded with codec v0 do not bind recursion proofs to their program and are
+    /// rejected; generate them again instead.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if airbender_codec::encoded_version(bytes) == AIRBENDER_CODEC_V0 {
+            return Err(VerifierError::Verification(
+                "verification key uses codec v0, which is no longer supported; generate it again"
+                    .to_string(),
+            ));
+        }
+        Ok(AirbenderCodecV1::decode(bytes)?)
+    }
+}
+
+/// Development verification key.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct DevVerificationKey {
+    pub image_id: ImageId,
+}
+
+/// Unified (recursion) verification key wrapper.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct RealUnifiedVerificationKey {
+    pub vk: UnifiedVk,
+}
+
+/// Unrolled (base / recursion-unrolled) verification key wrapper.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct RealUnrolledVerificationKey {
+    pub level: ProverLevel,
+    pub vk: UnrolledVk,
+}
+
+/// Unified verification key bundle for recursion.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct UnifiedVk {
+    pub image_id: ImageId,
+    /// Recursion chain (`x18..x25`) that proofs of `image_id` carry, derived from the
+    /// program's base-layer setup. The unified setup is shared by all programs, so this is
+    /// what binds a proof to its program.
+    pub program_commitment: [u32; 8],
+    pub unified_setup: UnrolledProgramSetup,
+    pub unified_layouts: setups::CompiledCircuitsSet,
+}
+
+/// Unrolled verification key bundle for base or recursion-unrolled layers.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct UnrolledVk {
+    pub image_id: ImageId,
+    /// Recursion chain (`x18..x25`) that recursion-unrolled proofs of `image_id` carry.
+    /// `None` for base-layer keys, whose setup is computed from the program itself.
+    pub program_commitment: Option<[u32; 8]>,
+    pub setup: UnrolledProgramSetup,
+    pub compiled_layouts: setups::CompiledCircuitsSet,
+}
+
+/// Public output of a successfully verified real proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifiedOutput {
+    pub level: ProverLevel,
+    pub image_id: ImageId,
+    /// Verified public output (`x10..x17`).
+    pub output: [u32; 8],
+    /// Verified extended public output (`x10..x25`), including recursion-chain fields.
+    pub output_extended: [u32; 16],
+}
+
+impl VerifiedOutput {
+    /// Recursion-chain commitment (`x18..x25`) to the verification keys behind the proof.
+    ///
+    /// Guests verifying this program's recursion-unified proofs compare against this value.
+    pub fn recursion_chain(&self) -> [u32; 8] {
+        let mut recursion_chain = [0u32; 8];
+        recursion_chain.copy_from_slice(&self.output_extended[8..]);
+        recursion_chain
+    }
+}
+
+/// Verify a unified-layer proof and return the verified registers `x10..x25`.
+pub fn verify_proof(
+    proof: &UnrolledProgramProof,
+    vk: &UnifiedVk,
+    expected_image_id: Option<ImageId>,
+    expected_output: Option<&dyn Commit>,
+) -> Result<[u32; 16]> {
+    verify_image_id(expected_image_id, vk.image_id)?;
+
+    let verifier_output =
+        verify_proof_in_unified_layer(proof, &vk.unified_setup, &vk.unified_layouts, false)
+            .map_err(|_| VerifierError::Verification("proof verification failed".to_string()))?;
+    verify_program_commitment(Some(vk.program_commitment), verifier_output)?;
+    verify_expected_output(expected_output, verifier_output)?;
+    Ok(verifier_output)
+}
+
+/// Verify a base or recursion-unrolled proof and return the verified registers `x10..x25`.
+pub fn verify_unrolled_proof(
+    proof: &UnrolledProgramProof,
+    vk: &UnrolledVk,
+    level: ProverLevel,
+    expected_image_id: Option<ImageId>,
+    expected_output: Option<&dyn Commit>,
+) -> Result<[u32; 16]> {
+    verify_image_id(expected_image_id, vk.image_id)?;
+
+    let is_base_layer = match level {
+        ProverLevel::Base => true,
+        ProverLevel::RecursionUnrolled => false,
+        ProverLevel::RecursionUnified => {
+            return Err(VerifierError::Verification(
+                "recursion-unified proofs must be verified with unified verification keys"
+                    .to_string(),
+            ));
+        }
+    };
+
+    let verifier_output =
+        verify_unrolled_layer_proof(proof, &vk.setup, &vk.compiled_layouts, is_base_layer)
+            .map_err(|_| VerifierError::Verification("proof verification failed".to_string()))?;
+    if !is_base_layer && vk.program_commitment.is_none() {
+        return Err(VerifierError::Verification(
+            "recursion-unrolled verification key has no program commitment".to_string(),
+        ));
+    }
+    verify_program_commitment(vk.program_commitment, verifier_output)?;
+    verify_expected_output(expected_output, verifier_output)?;
+    Ok(verifier_output)
+}
+
+/// Verify a real proof envelope against a real verification key.
+///
+/// This helper validates proof/VK compatibility and optional expected public output. The
+/// proof is bound to the key's program by the key's setup (base layer) or by its program
+/// commitment (recursion layers); the image ids of both must match as well. It does not
+/// require the program artifacts.
+pub fn verify_real_proof_with_vk(
+    proof: &RealProof,
+    vk: &VerificationKey,
+    expected_output: Option<&dyn Commit>,
+) -> Result<VerifiedOutput> {
+    let verifier_output = match (proof.level(), vk) {
+        (
+            ProverLevel::RecursionUnified,
+            VerificationKey::RealUnified(RealUnifiedVerificationKey { vk }),
+        ) => verify_proof(proof.inner(), vk, Some(proof.image_id()), expected_output)?,
+        (
+            ProverLevel::Base | ProverLevel::RecursionUnrolled,
+            VerificationKey::RealUnrolled(RealUnrolledVerificationKey { level, vk }),
+        ) => {
+            if *level != proof.level() {
+                return Err(VerifierError::Verification(format!(
+                    "proof level {:?} does not match verification key level {:?}",
+                    proof.level(),
+                    level
+                )));
+            }
+
+            verify_unrolled_proof(
+                proof.inner(),
+                vk,
+                proof.level(),
+                Some(proof.image_id()),
+                expected_output,
+            )?
+        }
+        (_, VerificationKey::Dev(_)) => {
+            return Err(VerifierError::Verification(
+                "real proofs require real verification keys".to_string(),
+            ));
+        }
+        (ProverLevel::RecursionUnified, VerificationKey::RealUnrolled(_)) => {
+            return Err(VerifierError::Verification(
+                "recursion-unified proof requires a unified verification key".to_string(),
+            ));
+        }
+        (ProverLevel::Base | ProverLevel::RecursionUnrolled, VerificationKey::RealUnified(_)) => {
+            return Err(VerifierError::Verification(
+                "base/recursion-unrolled proof requires an unrolled verification key".to_string(),
+            ));
+        }
+    };
+
+    let mut output = [0u32; 8];
+    output.copy_from_slice(&verifier_output[..8]);
+    Ok(VerifiedOutput {
+        level: proof.level(),
+        image_id: proof.image_id(),
+        output,
+        output_extended: verifier_output,
+    })
+}
+
+/// Decode a proof and a verification key, as written by `cargo airbender prove` and
+/// `cargo airbender generate-vk`, and verify the proof.
+pub fn verify_proof_bytes(
+    proof: &[u8],
+    vk: &[u8],
+    expected_output: Option<&dyn Commit>,
+) -> Result<VerifiedOutput> {
+    let vk = VerificationKey::from_bytes(vk)?;
+    let proof = decode_proof(proof, &vk)?;
+    match &proof {
+        Proof::Real(proof) => verify_real_proof_with_vk(proof, &vk, expected_output),
+        Proof::Dev(_) => Err(VerifierError::Verification(
+            "dev proofs carry no cryptographic proof and cannot be verified standalone".to_string(),
+        )),
+    }
+}
+
+/// Decode a proof written by `cargo airbender prove` for verification with `vk`.
+///
+/// Proofs encoded with codec v0 do not record their image id; they are attributed to the
+/// program of `vk`.
+pub fn decode_proof(bytes: &[u8], vk: &VerificationKey) -> Result<Proof> {
+    if airbender_codec::encoded_version(bytes) == AIRBENDER_CODEC_V0 {
+        Proof::from_v0_bytes(bytes, vk.image_id())
+    } else {
+        Proof::from_bytes(bytes)
+    }
+}
+
+/// Verify an aggregation proof and check that it commits to exactly `claims`, in order.
+///
+/// `vk` is the verification key of the aggregation guest, not of the aggregated programs.
+pub fn verify_aggregated_proof(
+    proof: &RealProof,
+    vk: &VerificationKey,
+    claims: &[AggregatedClaim],
+) -> Result<VerifiedOutput> {
+    let expected_output = claims_commitment(claims);
+    verify_real_proof_with_vk(proof, vk, Some(&expected_output))
+}
+
+fn verify_expected_output(
+    expected_output: Option<&dyn Commit>,
+    verifier_output: [u32; 16],
+) -> Result<()> {
+    let Some(expected_output) = expected_output else {
+        return Ok(());
+    };
+
+    let expected_words = expected_output.commit_words();
+    let mut actual_words = [0u32; 8];
+    actual_words.copy_from_slice(&verifier_output[..8]);
+
+    if expected_words != actual_words {
+        return Err(VerifierError::Verification(format!(
+            "public output mismatch: expected {expected_words:?}, got {actual_words:?}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Check the recursion chain (`x18..x25`) of a verified proof against the key's program.
+fn verify_program_commitment(expected: Option<[u32; 8]>, verifier_output: [u32; 16]) -> Result<()> {
+    let Some(expected) = expected else {
+        return Ok(());
+    };
+    let mut actual = [0u32; 8];
+    actual.copy_from_slice(&verifier_output[8..]);
+    if actual != expected {
+        return Err(VerifierError::Verification(format!(
+            "proof is for a different program: recursion chain {actual:?} does not match the verification key commitment {expected:?}"
+        )));
+    }
+
+    Ok(())
+}
+
+fn verify_image_id(expected_image_id: Option<ImageId>, vk_image_id: ImageId) -> Result<()> {
+    if let Some(expected) = expected_image_id {
+        if expected != vk_image_id {
+            return Err(VerifierError::Verification(format!(
+                "image id mismatch: expected {expected}, verification key has {vk_image_id}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dev_key::DevSigningKey;
+    use crate::proof::DevProof;
+    use crate::receipt::Receipt;
+    use airbender_codec::AirbenderCodecV0;
+
+    #[test]
+    fn verify_expected_output_accepts_matching_words() {
+        let mut verifier_output = [0u32; 16];
+        verifier_output[0] = 42;
+
+        verify_expected_output(Some(&42u32), verifier_output).expect("matching output must verify");
+    }
+
+    #[test]
+    fn verify_expected_output_rejects_mismatch() {
+        let verifier_output = [0u32; 16];
+
+        let err = verify_expected_output(Some(&1u32), verifier_output)
+            .expect_err("mismatching output must fail verification");
+        assert!(err.to_string().contains("public output mismatch"));
+    }
+
+    #[test]
+    fn recursion_chain_is_upper_half_of_extended_output() {
+        let mut output_extended = [0u32; 16];
+        output_extended[8] = 7;
+        output_extended[15] = 9;
+        let verified = VerifiedOutput {
+            level: ProverLevel::RecursionUnified,
+            image_id: ImageId::from_bytes([1; 32]),
+            output: [0; 8],
+            output_extended,
+        };
+
+        assert_eq!(verified.recursion_chain(), [7, 0, 0, 0, 0, 0, 0, 9]);
+    }
+
+    #[test]
+    fn verify_program_commitment_compares_recursion_chain() {
+        let mut verifier_output = [0u32; 16];
+        verifier_output[8] = 5;
+        let mut commitment = [0u32; 8];
+        commitment[0] = 5;
+
+        verify_program_commitment(None, verifier_output).expect("base layer has no commitment");
+        verify_program_commitment(Some(commitment), verifier_output)
+            .expect("matching recursion chain must pass");
+        let err = verify_program_commitment(Some([6; 8]), verifier_output)
+            .expect_err("another program's chain must fail");
+        assert!(err.to_string().contains("different program"));
+    }
+
+    #[test]
+    fn verify_image_id_rejects_mismatch() {
+        let vk_image_id = ImageId::from_bytes([1; 32]);
+        verify_image_id(None, vk_image_id).expect("no expectation must pass");
+        verify_image_id(Some(vk_image_id), vk_image_id).expect("matching image id must pass");
+
+        let err = verify_image_id(Some(ImageId::from_bytes([2; 32])), vk_image_id)
+            .expect_err("mismatching image id must fail");
+        assert!(err.to_string().contains("image id mismatch"));
+    }
+
+    #[test]
+    fn verify_proof_bytes_rejects_dev_proofs_and_malformed_input() {
+        let image_id = ImageId::from_bytes([1; 32]);
+        let proof = Proof::Dev(
+            DevProof::sign(
+                image_id,
+                [0; 32],
+                Receipt::from_registers([0; 32]),
+                1,
+                &DevSigningKey::from_seed([7; 32]),
+            )
+            .expect("sign dev proof"),
+        )
+        .to_bytes()
+        .expect("encode proof");
+        let vk = VerificationKey::Dev(DevVerificationKey { image_id })
+            .to_bytes()
+            .expect("encode vk");
+
+        let err = verify_proof_bytes(&proof, &vk, None).expect_err("dev proofs must be rejected");
+        assert!(err.to_string().contains("dev proofs"));
+
+        let err = verify_proof_bytes(&[0xff; 3], &vk, None).expect_err("garbage must be rejected");
+        assert!(matches!(err, VerifierError::Codec(_)));
+
+        let legacy_vk =
+            AirbenderCodecV0::encode(&VerificationKey::Dev(DevVerificationKey { image_id }))
+                .expect("encode v0 vk");
+        let err = verify_proof_bytes(&proof, &legacy_vk, None).expect_err("v0 keys are rejected");
+        assert!(err.to_string().contains("generate it again"));
+    }
+}
@@ -44,6 +44,12 @@ pub enum Commands {
     GenerateVk(GenerateVkArgs),
     /// Verify a proof against verification keys.
     VerifyProof(VerifyProofArgs),
+    /// Run a local proving service exposing an HTTP/JSON API.
+    Serve(ServeArgs),
+    /// Rebuild a guest from source and check it reproduces an existing dist.
+    VerifyBuild(VerifyBuildArgs),
+    /// Upgrade a dist manifest to the latest schema version.
+    MigrateManifest(MigrateManifestArgs),
 }
 
 #[derive(Args, Debug)]
@@ -58,12 +64,38 @@ pub struct BuildArgs {
     pub dist: Option<PathBuf>,
     #[arg(long)]
     pub project: Option<PathBuf>,
+    /// Build every guest of the workspace into `<dist>/<bin-name>/` and write `index.toml`.
+    #[arg(long, conflicts_with_all = ["app_name", "bin"])]
+    pub workspace: bool,
     #[arg(long, value_enum, conflicts_with_all = ["debug", "release"])]
     pub profile: Option<BuildProfile>,
     #[arg(long, conflicts_with = "release")]
     pub debug: bool,
     #[arg(long, conflicts_with = "debug")]
     pub release: bool,
+    /// Sign `manifest.toml` with the ed25519 key in this file (64 hex characters).
+    #[arg(long, value_name = "PATH")]
+    pub sign_key: Option<PathBuf>,
+    /// Also write the dist directory as a single-file bundle (`<app-name>.airbundle`).
+    #[arg(long)]
+    pub bundle: bool,
+    /// Fail the build if `app.bin` is larger than this many bytes.
+    #[arg(long, value_name = "BYTES")]
+    pub rom_budget: Option<u64>,
+    /// Cargo features to enable, comma-separated or repeated.
+    #[arg(long, value_delimiter = ',')]
+    pub features: Vec<String>,
+    #[arg(long)]
+    pub no_default_features: bool,
+    /// Flags appended to the guest's `build.rustflags`, space-separated like `RUSTFLAGS`.
+    #[arg(long, value_name = "FLAGS", allow_hyphen_values = true)]
+    pub rustflags: Option<String>,
+    #[arg(long, value_enum)]
+    pub opt_level: Option<OptLevelArg>,
+    #[arg(long, value_enum)]
+    pub lto: Option<LtoArg>,
+    #[arg(long, value_name = "N")]
+    pub codegen_units: Option<u32>,
     #[arg(last = true, value_name = "CARGO_ARGS")]
     pub cargo_args: Vec<String>,
 }
@@ -74,6 +106,29 @@ pub enum BuildProfile {
     Release,
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptLevelArg {
+    #[value(name = "0")]
+    O0,
+    #[value(name = "1")]
+    O1,
+    #[value(name = "2")]
+    O2,
+    #[value(name = "3")]
+    O3,
+    #[value(name = "s")]
+    Size,
+    #[value(name = "z")]
+    MinSize,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LtoArg {
+    Off,
+    Thin,
+    Fat,
+}
+
 #[derive(Args, Debug)]
 pub struct NewArgs {
     pub path: Option<PathBuf>,
@@ -166,7 +221,7 @@ pub struct ProveArgs {
         long,
         value_enum,
         default_value_t = ProverBackendArg::Dev,
-        long_help = "Select proving backend.\n- dev: mock proof envelope for development (no cryptographic proving).\n- cpu: real CPU proving (base level only).\n- gpu: real GPU proving; requires GPU-enabled `cargo-airbender` (enabled by default)."
+        long_help = "Select proving backend.\n- dev: mock proof envelope for development (no cryptographic proving).\n- cpu: real CPU proving; all levels, but recursion levels are slow.\n- gpu: real GPU proving; requires GPU-enabled `cargo-airbender` (enabled by default)."
     )]
     pub backend: ProverBackendArg,
     #[arg(short, long)]
@@ -201,6 +256,51 @@ pub struct VerifyProofArgs {
     pub expected_output: Option<String>,
 }
 
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    /// Dist app directories to serve (for example `dist/app`).
+    #[arg(required = true, value_name = "DIST")]
+    pub dist: Vec<PathBuf>,
+    #[arg(long, default_value = "127.0.0.1:3030")]
+    pub listen: String,
+    #[arg(
+        long,
+        default_value = ".airbender-serve",
+        help = "Directory where job state and proofs are persisted across restarts"
+    )]
+    pub state_dir: PathBuf,
+    #[arg(long, value_enum, default_value_t = ProverBackendArg::Dev)]
+    pub backend: ProverBackendArg,
+    #[arg(short, long)]
+    pub threads: Option<usize>,
+    #[arg(long)]
+    pub cycles: Option<usize>,
+    #[arg(long)]
+    pub ram_bound: Option<usize>,
+    #[arg(long, value_enum, default_value_t = ProverLevelArg::RecursionUnified)]
+    pub level: ProverLevelArg,
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyBuildArgs {
+    /// Dist app directory or `.airbundle` to verify (for example `dist/app`).
+    #[arg(value_name = "DIST")]
+    pub dist: PathBuf,
+    /// Guest project to rebuild; defaults to the current directory.
+    #[arg(long)]
+    pub project: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct MigrateManifestArgs {
+    /// Dist app directory holding `manifest.toml` (for example `dist/app`).
+    #[arg(value_name = "DIST")]
+    pub dist: PathBuf,
+    /// Re-sign the migrated manifest with the ed25519 key in this file (64 hex characters).
+    #[arg(long, value_name = "PATH")]
+    pub sign_key: Option<PathBuf>,
+}
+
 #[derive(ValueEnum, Debug, Clone, Copy)]
 pub enum ProverBackendArg {
     Dev,
@@ -274,6 +374,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_build_workspace() {
+        let cli = Cli::parse_from(["cargo-airbender", "build", "--workspace"]);
+        match cli.command {
+            Commands::Build(args) => assert!(args.workspace),
+            other => panic!("unexpected command: {other:?}"),
+        }
+
+        let err = Cli::try_parse_from(["cargo-airbender", "build", "--workspace", "--bin", "a"])
+            .expect_err("workspace builds package every binary");
+        assert!(err.to_string().contains("--bin"));
+    }
+
     #[test]
     fn parse_build_custom_app_name() {
         let cli = Cli::parse_from(["cargo-airbender", "build", "--app-name", "gpu-profile"]);
@@ -287,9 +400,78 @@ mod tests {
 
     #[test]
     fn parse_build_rejects_top_level_cargo_flags() {
-        let err = Cli::try_parse_from(["cargo-airbender", "build", "--features", "gpu"])
+        let err = Cli::try_parse_from(["cargo-airbender", "build", "--locked"])
             .expect_err("parse should fail without -- forwarding separator");
-        assert!(err.to_string().contains("--features"));
+        assert!(err.to_string().contains("--locked"));
+    }
+
+    #[test]
+    fn parse_build_typed_cargo_settings() {
+        let cli = Cli::parse_from([
+            "cargo-airbender",
+            "build",
+            "--features",
+            "gpu,extra",
+            "--features",
+            "more",
+            "--no-default-features",
+            "--rustflags",
+            "-C debuginfo=0",
+            "--opt-level",
+            "z",
+            "--lto",
+            "fat",
+            "--codegen-units",
+            "1",
+        ]);
+        match cli.command {
+            Commands::Build(args) => {
+                assert_eq!(args.features, vec!["gpu", "extra", "more"]);
+                assert!(args.no_default_features);
+                assert_eq!(args.rustflags.as_deref(), Some("-C debuginfo=0"));
+                assert_eq!(args.opt_level, Some(OptLevelArg::MinSize));
+                assert_eq!(args.lto, Some(LtoArg::Fat));
+                assert_eq!(args.codegen_units, Some(1));
+                assert!(args.cargo_args.is_empty());
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_verify_build_command() {
+        let cli = Cli::parse_from([
+            "cargo-airbender",
+            "verify-build",
+            "dist/app",
+            "--project",
+            "guest",
+        ]);
+        match cli.command {
+            Commands::VerifyBuild(args) => {
+                assert_eq!(args.dist, PathBuf::from("dist/app"));
+                assert_eq!(args.project, Some(PathBuf::from("guest")));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_migrate_manifest_command() {
+        let cli = Cli::parse_from([
+            "cargo-airbender",
+            "migrate-manifest",
+            "dist/app",
+            "--sign-key",
+            "release.key",
+        ]);
+        match cli.command {
+            Commands::MigrateManifest(args) => {
+                assert_eq!(args.dist, PathBuf::from("dist/app"));
+                assert_eq!(args.sign_key, Some(PathBuf::from("release.key")));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
     }
 
     #[test]
@@ -394,6 +576,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_serve_multiple_dists() {
+        let cli = Cli::parse_from([
+            "cargo-airbender",
+            "serve",
+            "dist/app",
+            "dist/other",
+            "--listen",
+            "0.0.0.0:8080",
+        ]);
+        match cli.command {
+            Commands::Serve(args) => {
+                assert_eq!(
+                    args.dist,
+                    vec![PathBuf::from("dist/app"), PathBuf::from("dist/other")]
+                );
+                assert_eq!(args.listen, "0.0.0.0:8080");
+                assert_eq!(args.state_dir, PathBuf::from(".airbender-serve"));
+            }
+            other => panic!("unexpected command: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_serve_requires_dist() {
+        let err = Cli::try_parse_from(["cargo-airbender", "serve"])
+            .expect_err("serve without dist directories should fail");
+        assert!(err.to_string().contains("<DIST>"));
+    }
+
     #[test]
     fn parse_verify_proof_rejects_repeated_expected_output() {
         let err = Cli::try_parse_from([
@@ -1,7 +1,10 @@
-use crate::cli::{BuildArgs, BuildProfile};
+use crate::cli::{BuildArgs, BuildProfile, LtoArg, OptLevelArg};
 use crate::error::{CliError, Result};
 use crate::ui;
-use airbender_build::{build_dist, BuildConfig, Profile};
+use airbender_build::{
+    build_dist, build_workspace, BuildConfig, Lto, OptLevel, Profile, SizeReport,
+    WorkspaceArtifacts,
+};
 
 pub fn run(args: BuildArgs) -> Result<()> {
     let BuildArgs {
@@ -10,9 +13,19 @@ pub fn run(args: BuildArgs) -> Result<()> {
         target,
         dist,
         project,
+        workspace,
         profile,
         debug,
         release,
+        sign_key,
+        bundle,
+        rom_budget,
+        features,
+        no_default_features,
+        rustflags,
+        opt_level,
+        lto,
+        codegen_units,
         cargo_args,
     } = args;
 
@@ -39,18 +52,52 @@ pub fn run(args: BuildArgs) -> Result<()> {
     config.dist_dir = dist;
     config.profile = resolve_profile(profile, debug, release);
     config.cargo_args = cargo_args;
+    config.features = features;
+    config.no_default_features = no_default_features;
+    config.rustflags = rustflags
+        .as_deref()
+        .map(|flags| flags.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default();
+    config.opt_level = opt_level.map(resolve_opt_level);
+    config.lto = lto.map(resolve_lto);
+    config.codegen_units = codegen_units;
+    config.sign_key = sign_key;
+    config.bundle = bundle;
+    config.rom_budget = rom_budget;
+
+    if workspace {
+        let artifacts = build_workspace(&config).map_err(|err| {
+            CliError::with_source("failed to build workspace guests", err)
+                .with_hint("set `RUST_LOG=info` if you need backend diagnostic logs")
+        })?;
+        print_workspace_artifacts(&artifacts);
+        return Ok(());
+    }
 
     let artifacts = build_dist(&config).map_err(|err| {
         CliError::with_source("failed to build guest artifacts", err)
             .with_hint("set `RUST_LOG=info` if you need backend diagnostic logs")
     })?;
 
-    ui::success("built guest artifacts");
+    if artifacts.up_to_date {
+        ui::success("guest artifacts up to date");
+    } else {
+        ui::success("built guest artifacts");
+    }
     ui::field("dist", artifacts.dist_dir.display());
     ui::field("app.bin", artifacts.app_bin.display());
     ui::field("app.elf", artifacts.app_elf.display());
     ui::field("app.text", artifacts.app_text.display());
     ui::field("manifest", artifacts.manifest.display());
+    ui::field("image_id", artifacts.image_id);
+    if let Some(signature) = &artifacts.signature {
+        ui::field("signature", signature.path.display());
+        ui::field("signed_by", signature.public_key);
+    }
+    if let Some(bundle) = &artifacts.bundle {
+        ui::field("bundle", bundle.display());
+    }
+    print_size_report(&artifacts.size);
     ui::blank_line();
     ui::info("next step");
     ui::command(format!(
@@ -61,6 +108,109 @@ pub fn run(args: BuildArgs) -> Result<()> {
     Ok(())
 }
 
+fn print_workspace_artifacts(artifacts: &WorkspaceArtifacts) {
+    let built = artifacts
+        .guests
+        .iter()
+        .filter(|guest| !guest.up_to_date)
+        .count();
+    let total = artifacts.guests.len();
+    if built == 0 {
+        ui::success("workspace guests up to date");
+    } else if built == total {
+        ui::success(format!("built {total} workspace guests"));
+    } else {
+        ui::success(format!(
+            "built {built} of {total} workspace guests, the rest are up to date"
+        ));
+    }
+    ui::field("dist", artifacts.dist_root.display());
+    ui::field("index", artifacts.index.display());
+    for guest in &artifacts.guests {
+        let app_name = guest
+            .dist_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        ui::blank_line();
+        ui::info(app_name);
+        ui::field("manifest", guest.manifest.display());
+        ui::field("image_id", guest.image_id);
+        ui::field(
+            "rom",
+            format!(
+                "{} / {} bytes ({:.1}%)",
+                guest.size.summary.rom_bytes,
+                guest.size.summary.rom_capacity,
+                guest.size.summary.rom_percent()
+            ),
+        );
+        if let Some(signature) = &guest.signature {
+            ui::field("signed_by", signature.public_key);
+        }
+        if let Some(bundle) = &guest.bundle {
+            ui::field("bundle", bundle.display());
+        }
+    }
+}
+
+fn print_size_report(report: &SizeReport) {
+    let summary = &report.summary;
+    ui::blank_line();
+    ui::info("program size");
+    ui::field(
+        "rom",
+        format!(
+            "{} / {} bytes ({:.1}%)",
+            summary.rom_bytes,
+            summary.rom_capacity,
+            summary.rom_percent()
+        ),
+    );
+    ui::field("text", format!("{} bytes", summary.text_bytes));
+    ui::field("static_ram", format!("{} bytes", summary.static_ram_bytes));
+    ui::field("heap_free", format!("{} bytes", summary.heap_bytes));
+
+    ui::blank_line();
+    ui::info("sections");
+    for section in &report.sections {
+        ui::field(
+            &section.name,
+            format!(
+                "{} bytes at {:#010x} ({})",
+                section.size, section.address, section.region
+            ),
+        );
+    }
+
+    if !report.top_symbols.is_empty() {
+        ui::blank_line();
+        ui::info("largest symbols");
+        for symbol in &report.top_symbols {
+            ui::field(&format!("{:>8} bytes", symbol.size), &symbol.name);
+        }
+    }
+}
+
+fn resolve_opt_level(opt_level: OptLevelArg) -> OptLevel {
+    match opt_level {
+        OptLevelArg::O0 => OptLevel::O0,
+        OptLevelArg::O1 => OptLevel::O1,
+        OptLevelArg::O2 => OptLevel::O2,
+        OptLevelArg::O3 => OptLevel::O3,
+        OptLevelArg::Size => OptLevel::Size,
+        OptLevelArg::MinSize => OptLevel::MinSize,
+    }
+}
+
+fn resolve_lto(lto: LtoArg) -> Lto {
+    match lto {
+        LtoArg::Off => Lto::Off,
+        LtoArg::Thin => Lto::Thin,
+        LtoArg::Fat => Lto::Fat,
+    }
+}
+
 fn resolve_profile(profile: Option<BuildProfile>, debug: bool, release: bool) -> Profile {
     if debug {
         return Profile::Debug;
@@ -0,0 +1,39 @@
+use crate::cli::MigrateManifestArgs;
+use crate::error::{CliError, Result};
+use crate::ui;
+use airbender_build::migrate_manifest;
+
+pub fn run(args: MigrateManifestArgs) -> Result<()> {
+    if !args.dist.join("manifest.toml").is_file() {
+        return Err(CliError::new(format!(
+            "`{}` is not a dist directory with a manifest.toml",
+            args.dist.display()
+        ))
+        .with_hint("pass a dist app directory such as `dist/app`; rebuild bundles with `cargo airbender build --bundle`"));
+    }
+
+    let migration = migrate_manifest(&args.dist, args.sign_key.as_deref()).map_err(|err| {
+        CliError::with_source(format!("failed to migrate `{}`", args.dist.display()), err)
+    })?;
+
+    if !migration.changed() {
+        ui::success(format!("manifest is already {}", migration.to_version));
+        ui::field("manifest", migration.manifest.display());
+        return Ok(());
+    }
+
+    ui::success(format!(
+        "migrated manifest from {} to {}",
+        migration.from_version, migration.to_version
+    ));
+    ui::field("manifest", migration.manifest.display());
+    if let Some(image_id) = migration.computed_image_id {
+        ui::field("image_id", image_id);
+    }
+    if let Some(signature) = &migration.signature {
+        ui::field("signature", signature.path.display());
+        ui::field("signed_by", signature.public_key);
+    }
+
+    Ok(())
+}
@@ -1,7 +1,10 @@
 mod build;
+mod migrate_manifest;
 mod new;
 mod prove;
 mod run;
+mod serve;
+mod verify_build;
 mod vk;
 
 use crate::cli::{Cli, Commands};
@@ -17,5 +20,8 @@ pub fn run(cli: Cli) -> Result<()> {
         Commands::Prove(args) => prove::run(args),
         Commands::GenerateVk(args) => vk::generate(args),
         Commands::VerifyProof(args) => vk::verify(args),
+        Commands::Serve(args) => serve::run(args),
+        Commands::VerifyBuild(args) => verify_build::run(args),
+        Commands::MigrateManifest(args) => migrate_manifest::run(args),
     }
 }
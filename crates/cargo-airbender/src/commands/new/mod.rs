@@ -222,12 +222,12 @@ mod tests {
         assert!(root_readme.contains("Default prover backend: `dev`"));
         assert!(root_readme.contains("mock proof envelope"));
         assert!(root_gitignore.contains("target/"));
+        assert!(root_gitignore.contains(".airbender/"));
         assert!(guest_cargo.contains("name = \"hello-airbender-guest\""));
         assert!(guest_cargo.contains("airbender-sdk"));
         assert!(guest_main.contains("#![no_std]"));
         assert!(guest_toolchain.contains(&format!("channel = \"{}\"", DEFAULT_GUEST_TOOLCHAIN)));
-        assert!(guest_toolchain
-            .contains("components = [\"clippy\", \"rust-src\", \"llvm-tools-preview\"]"));
+        assert!(guest_toolchain.contains("components = [\"clippy\", \"rust-src\"]"));
         assert!(guest_cargo_config.contains("target = \"riscv32im-risc0-zkvm-elf\""));
         assert!(guest_cargo_config
             .contains("build-std = [\"alloc\", \"core\", \"panic_abort\", \"compiler_builtins\", \"std\", \"proc_macro\"]"));
@@ -3,112 +3,20 @@ use crate::error::{CliError, Result};
 use crate::input;
 use crate::ui;
 use airbender_host::Prover;
+use std::path::Path;
 
 pub fn run(args: ProveArgs) -> Result<()> {
     let input_words = input::parse_input_words(&args.input)?;
 
-    let prove_result = match args.backend {
-        ProverBackendArg::Dev => {
-            if args.threads.is_some() {
-                tracing::warn!("ignoring `--threads` for dev backend");
-            }
-            if args.ram_bound.is_some() {
-                tracing::warn!("ignoring `--ram-bound` for dev backend");
-            }
-            if args.level != ProverLevelArg::RecursionUnified {
-                tracing::warn!("ignoring `--level` for dev backend");
-            }
-
-            let mut builder = airbender_host::DevProverBuilder::new(&args.app_bin);
-            if let Some(cycles) = args.cycles {
-                builder = builder.with_cycles(cycles);
-            }
-
-            let prover = builder.build().map_err(|err| {
-                CliError::with_source(
-                    format!(
-                        "failed to initialize dev prover for `{}`",
-                        args.app_bin.display()
-                    ),
-                    err,
-                )
-            })?;
-
-            prover.prove(&input_words)
-        }
-        ProverBackendArg::Gpu => {
-            if args.cycles.is_some() {
-                tracing::warn!("ignoring `--cycles` for gpu backend");
-            }
-            if args.ram_bound.is_some() {
-                tracing::warn!("ignoring `--ram-bound` for gpu backend");
-            }
-
-            #[cfg(feature = "gpu-prover")]
-            {
-                let level = as_host_level(args.level);
-                let mut builder =
-                    airbender_host::GpuProverBuilder::new(&args.app_bin).with_level(level);
-                if let Some(threads) = args.threads {
-                    builder = builder.with_worker_threads(threads);
-                }
-                let prover = builder.build().map_err(|err| {
-                    CliError::with_source(
-                        format!(
-                            "failed to initialize GPU prover for `{}`",
-                            args.app_bin.display()
-                        ),
-                        err,
-                    )
-                })?;
-
-                prover.prove(&input_words)
-            }
-
-            #[cfg(not(feature = "gpu-prover"))]
-            {
-                return Err(CliError::new(
-                    "GPU backend requires GPU support in `cargo-airbender`",
-                )
-                .with_hint(
-                    "rebuild `cargo-airbender` with default features or pass `--features gpu-prover` to use `--backend gpu`",
-                ));
-            }
-        }
-        ProverBackendArg::Cpu => {
-            let level = as_host_level(args.level);
-            if level != airbender_host::ProverLevel::Base {
-                return Err(
-                    CliError::new("CPU backend currently supports only `--level base`")
-                        .with_hint("use `--backend gpu` for recursion levels"),
-                );
-            }
-
-            let mut builder = airbender_host::CpuProverBuilder::new(&args.app_bin);
-            if let Some(threads) = args.threads {
-                builder = builder.with_worker_threads(threads);
-            }
-            if let Some(cycles) = args.cycles {
-                builder = builder.with_cycles(cycles);
-            }
-            if let Some(ram_bound) = args.ram_bound {
-                builder = builder.with_ram_bound(ram_bound);
-            }
-
-            let prover = builder.build().map_err(|err| {
-                CliError::with_source(
-                    format!(
-                        "failed to initialize CPU prover for `{}`",
-                        args.app_bin.display()
-                    ),
-                    err,
-                )
-            })?;
-
-            prover.prove(&input_words)
-        }
-    }
-    .map_err(|err| {
+    let settings = ProverSettings {
+        backend: args.backend,
+        threads: args.threads,
+        cycles: args.cycles,
+        ram_bound: args.ram_bound,
+        level: args.level,
+    };
+    let prover = settings.build(&args.app_bin)?;
+    let prove_result = prover.prove(&input_words).map_err(|err| {
         CliError::with_source(
             format!("failed to generate proof for `{}`", args.app_bin.display()),
             err,
@@ -118,7 +26,9 @@ pub fn run(args: ProveArgs) -> Result<()> {
 
     tracing::info!("{}", prove_result.proof.debug_info());
 
-    let encoded = bincode::serde::encode_to_vec(&prove_result.proof, bincode::config::standard())
+    let encoded = prove_result
+        .proof
+        .to_bytes()
         .map_err(|err| CliError::with_source("failed to encode proof", err))?;
     std::fs::write(&args.output, encoded).map_err(|err| {
         CliError::with_source(
@@ -131,11 +41,138 @@ pub fn run(args: ProveArgs) -> Result<()> {
     ui::field("backend", backend_name(args.backend));
     ui::field("level", proof_level(args.backend, args.level));
     ui::field("cycles", prove_result.cycles);
+    ui::field("image_id", prove_result.proof.image_id());
     ui::field("output", args.output.display());
+    if let airbender_host::Proof::Dev(proof) = &prove_result.proof {
+        ui::field("dev_key", proof.signer);
+        ui::info("dev proofs are signed with the local dev key and are not a valid attestation");
+    }
 
     Ok(())
 }
 
+/// Prover backend configuration shared by `prove` and `serve`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ProverSettings {
+    pub(crate) backend: ProverBackendArg,
+    pub(crate) threads: Option<usize>,
+    pub(crate) cycles: Option<usize>,
+    pub(crate) ram_bound: Option<usize>,
+    pub(crate) level: ProverLevelArg,
+}
+
+impl ProverSettings {
+    /// Proof level produced by this configuration; `None` for dev proofs.
+    pub(crate) fn proof_level(&self) -> Option<airbender_host::ProverLevel> {
+        match self.backend {
+            ProverBackendArg::Dev => None,
+            ProverBackendArg::Cpu | ProverBackendArg::Gpu => Some(as_host_level(self.level)),
+        }
+    }
+
+    pub(crate) fn backend_name(&self) -> &'static str {
+        backend_name(self.backend)
+    }
+
+    /// Validates backend options and builds a prover for `app_bin`.
+    pub(crate) fn build(&self, app_bin: &Path) -> Result<Box<dyn Prover>> {
+        match self.backend {
+            ProverBackendArg::Dev => {
+                if self.threads.is_some() {
+                    tracing::warn!("ignoring `--threads` for dev backend");
+                }
+                if self.ram_bound.is_some() {
+                    tracing::warn!("ignoring `--ram-bound` for dev backend");
+                }
+                if self.level != ProverLevelArg::RecursionUnified {
+                    tracing::warn!("ignoring `--level` for dev backend");
+                }
+
+                let mut builder = airbender_host::DevProverBuilder::new(app_bin);
+                if let Some(cycles) = self.cycles {
+                    builder = builder.with_cycles(cycles);
+                }
+
+                let prover = builder.build().map_err(|err| {
+                    CliError::with_source(
+                        format!(
+                            "failed to initialize dev prover for `{}`",
+                            app_bin.display()
+                        ),
+                        err,
+                    )
+                })?;
+
+                Ok(Box::new(prover))
+            }
+            ProverBackendArg::Gpu => {
+                if self.cycles.is_some() {
+                    tracing::warn!("ignoring `--cycles` for gpu backend");
+                }
+                if self.ram_bound.is_some() {
+                    tracing::warn!("ignoring `--ram-bound` for gpu backend");
+                }
+
+                #[cfg(feature = "gpu-prover")]
+                {
+                    let level = as_host_level(self.level);
+                    let mut builder =
+                        airbender_host::GpuProverBuilder::new(app_bin).with_level(level);
+                    if let Some(threads) = self.threads {
+                        builder = builder.with_worker_threads(threads);
+                    }
+                    let prover = builder.build().map_err(|err| {
+                        CliError::with_source(
+                            format!(
+                                "failed to initialize GPU prover for `{}`",
+                                app_bin.display()
+                            ),
+                            err,
+                        )
+                    })?;
+
+                    Ok(Box::new(prover))
+                }
+
+                #[cfg(not(feature = "gpu-prover"))]
+                {
+                    Err(CliError::new(
+                        "GPU backend requires GPU support in `cargo-airbender`",
+                    )
+                    .with_hint(
+                        "rebuild `cargo-airbender` with default features or pass `--features gpu-prover` to use `--backend gpu`",
+                    ))
+                }
+            }
+            ProverBackendArg::Cpu => {
+                let level = as_host_level(self.level);
+                let mut builder = airbender_host::CpuProverBuilder::new(app_bin).with_level(level);
+                if let Some(threads) = self.threads {
+                    builder = builder.with_worker_threads(threads);
+                }
+                if let Some(cycles) = self.cycles {
+                    builder = builder.with_cycles(cycles);
+                }
+                if let Some(ram_bound) = self.ram_bound {
+                    builder = builder.with_ram_bound(ram_bound);
+                }
+
+                let prover = builder.build().map_err(|err| {
+                    CliError::with_source(
+                        format!(
+                            "failed to initialize CPU prover for `{}`",
+                            app_bin.display()
+                        ),
+                        err,
+                    )
+                })?;
+
+                Ok(Box::new(prover))
+            }
+        }
+    }
+}
+
 fn backend_name(backend: ProverBackendArg) -> &'static str {
     match backend {
         ProverBackendArg::Dev => "dev",
@@ -159,7 +196,7 @@ fn level_name(level: ProverLevelArg) -> &'static str {
     }
 }
 
-fn as_host_level(level: ProverLevelArg) -> airbender_host::ProverLevel {
+pub(crate) fn as_host_level(level: ProverLevelArg) -> airbender_host::ProverLevel {
     match level {
         ProverLevelArg::Base => airbender_host::ProverLevel::Base,
         ProverLevelArg::RecursionUnrolled => airbender_host::ProverLevel::RecursionUnrolled,
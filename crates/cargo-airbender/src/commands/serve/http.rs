@@ -0,0 +1,524 @@
+//! HTTP routing for the proving service; see `airbender_host::service` for the protocol.
+
+use super::{ServeState, ServedProgram};
+use crate::commands::vk::parse_expected_output_words;
+use airbender_host::service::{
+    ErrorResponse, JobState, ProgramInfo, SubmitJobRequest, SubmitJobResponse, VerifyRequest,
+    VerifyResponse, SERVICE_API_PREFIX,
+};
+use airbender_host::{Proof, VerificationKey, VerificationRequest, Verifier};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::Read;
+use tiny_http::{Header, Method, Request, Response};
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+const BINARY_CONTENT_TYPE: &str = "application/octet-stream";
+/// Largest request body the service reads; larger requests are answered with 413.
+const MAX_REQUEST_BODY_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Serves a single request; errors are reported to the client, never propagated.
+pub(super) fn handle(state: &ServeState, mut request: Request) {
+    let body_length = request.body_length();
+    let reply = read_body(request.as_reader(), body_length, MAX_REQUEST_BODY_BYTES)
+        .and_then(|body| route(state, request.method(), request.url(), &body))
+        .unwrap_or_else(ApiError::into_reply);
+
+    tracing::debug!("{} {} -> {}", request.method(), request.url(), reply.status);
+    let content_type = Header::from_bytes(&b"Content-Type"[..], reply.content_type.as_bytes())
+        .expect("content type header must be valid");
+    let response = Response::from_data(reply.body)
+        .with_status_code(reply.status)
+        .with_header(content_type);
+    if let Err(err) = request.respond(response) {
+        tracing::warn!("failed to send response: {err}");
+    }
+}
+
+#[derive(Debug)]
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn json<T: Serialize>(status: u16, value: &T) -> Self {
+        Self {
+            status,
+            content_type: JSON_CONTENT_TYPE,
+            body: serde_json::to_vec(value).expect("service payloads must serialize to JSON"),
+        }
+    }
+
+    fn bytes(body: Vec<u8>) -> Self {
+        Self {
+            status: 200,
+            content_type: BINARY_CONTENT_TYPE,
+            body,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: 400,
+            message: message.into(),
+        }
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self {
+            status: 404,
+            message: message.into(),
+        }
+    }
+
+    fn conflict(message: impl Into<String>) -> Self {
+        Self {
+            status: 409,
+            message: message.into(),
+        }
+    }
+
+    fn payload_too_large(limit: u64) -> Self {
+        Self {
+            status: 413,
+            message: format!("request body exceeds {limit} bytes"),
+        }
+    }
+
+    fn internal(message: impl Into<String>) -> Self {
+        Self {
+            status: 500,
+            message: message.into(),
+        }
+    }
+
+    fn into_reply(self) -> Reply {
+        Reply::json(
+            self.status,
+            &ErrorResponse {
+                error: self.message,
+            },
+        )
+    }
+}
+
+type ApiResult<T> = std::result::Result<T, ApiError>;
+
+/// Reads a request body of at most `limit` bytes, rejecting a larger `Content-Length` up front.
+fn read_body(
+    reader: &mut dyn Read,
+    content_length: Option<usize>,
+    limit: u64,
+) -> ApiResult<Vec<u8>> {
+    if content_length.is_some_and(|length| length as u64 > limit) {
+        return Err(ApiError::payload_too_large(limit));
+    }
+    let mut body = Vec::new();
+    reader
+        .take(limit + 1)
+        .read_to_end(&mut body)
+        .map_err(|err| ApiError::bad_request(format!("failed to read request body: {err}")))?;
+    if body.len() as u64 > limit {
+        return Err(ApiError::payload_too_large(limit));
+    }
+    Ok(body)
+}
+
+fn route(state: &ServeState, method: &Method, url: &str, body: &[u8]) -> ApiResult<Reply> {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let Some(path) = path.strip_prefix(SERVICE_API_PREFIX) else {
+        return Err(ApiError::not_found(format!("unknown endpoint `{path}`")));
+    };
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    match (method, segments.as_slice()) {
+        (Method::Get, ["programs"]) => {
+            let programs: Vec<&ProgramInfo> =
+                state.programs.values().map(|served| &served.info).collect();
+            Ok(Reply::json(200, &programs))
+        }
+        (Method::Post, ["programs", program, "jobs"]) => {
+            submit_job(state, program, parse_json(body)?)
+        }
+        (Method::Get, ["programs", program, "vk"]) => {
+            let vk = served_vk(find_program(state, program)?)?;
+            let encoded = vk.to_bytes().map_err(|err| {
+                ApiError::internal(format!("failed to encode verification key: {err}"))
+            })?;
+            Ok(Reply::bytes(encoded))
+        }
+        (Method::Post, ["programs", program, "verify"]) => {
+            let expected_output = query_param(query, "expected_output")
+                .map(|raw| parse_expected_output_words(Some(&raw)))
+                .transpose()
+                .map_err(|err| ApiError::bad_request(err.to_string()))?
+                .flatten();
+            verify_program_proof(find_program(state, program)?, body, expected_output)
+        }
+        (Method::Get, ["jobs", id]) => {
+            let id = parse_job_id(id)?;
+            let status = state.store.status(id).ok_or_else(|| job_not_found(id))?;
+            Ok(Reply::json(200, &status))
+        }
+        (Method::Get, ["jobs", id, "proof"]) => {
+            let id = parse_job_id(id)?;
+            ensure_succeeded(state, id)?;
+            let proof = state.store.proof_bytes(id).map_err(|err| {
+                ApiError::internal(format!("failed to read proof of job {id}: {err}"))
+            })?;
+            Ok(Reply::bytes(proof))
+        }
+        (Method::Get, ["jobs", id, "receipt"]) => {
+            let id = parse_job_id(id)?;
+            let status = ensure_succeeded(state, id)?;
+            Ok(Reply::json(200, &status.receipt))
+        }
+        (Method::Post, ["jobs", id, "verify"]) => {
+            let request = if body.is_empty() {
+                VerifyRequest::default()
+            } else {
+                parse_json(body)?
+            };
+            verify_job(state, parse_job_id(id)?, request)
+        }
+        _ => Err(ApiError::not_found(format!(
+            "unknown endpoint `{method} {SERVICE_API_PREFIX}{path}`"
+        ))),
+    }
+}
+
+fn submit_job(state: &ServeState, program: &str, request: SubmitJobRequest) -> ApiResult<Reply> {
+    let served = find_program(state, program)?;
+    let job_id = state
+        .store
+        .create(program, served.info.image_id, request.input_words)
+        .map_err(|err| ApiError::internal(format!("failed to persist job: {err}")))?;
+    served
+        .queue
+        .send(job_id)
+        .map_err(|_| ApiError::internal(format!("prover for `{program}` is not running")))?;
+
+    tracing::info!("queued job {job_id} for `{program}`");
+    Ok(Reply::json(202, &SubmitJobResponse { job_id }))
+}
+
+fn verify_job(state: &ServeState, id: u64, request: VerifyRequest) -> ApiResult<Reply> {
+    let status = ensure_succeeded(state, id)?;
+    let served = find_program(state, &status.program)?;
+    if status.image_id != served.info.image_id {
+        return Err(ApiError::conflict(format!(
+            "job {id} was proved for another build of `{}`",
+            status.program
+        )));
+    }
+    let expected_output = request
+        .expected_output
+        .map(|words| pad_output_words(&words))
+        .transpose()?;
+    let proof: Proof = state
+        .store
+        .proof_bytes(id)
+        .map_err(|err| ApiError::internal(format!("failed to read proof of job {id}: {err}")))
+        .and_then(|bytes| decode_proof(&bytes))?;
+
+    let outcome = match &proof {
+        Proof::Dev(_) => {
+            let input_words = state
+                .store
+                .input_words(id)
+                .ok_or_else(|| job_not_found(id))?;
+            // The recorded receipt comes from the proof itself, so the caller has to say
+            // which output it expects.
+            let output = expected_output.ok_or_else(|| {
+                ApiError::bad_request("verifying a dev proof requires `expected_output`")
+            })?;
+            let verifier = served
+                .program
+                .dev_verifier()
+                .build()
+                .map_err(|err| ApiError::internal(err.to_string()))?;
+            let vk = served_vk(served)?;
+            verifier.verify(&proof, &vk, VerificationRequest::dev(&input_words, &output))
+        }
+        Proof::Real(_) => verify_real(served, &proof, expected_output.as_ref())?,
+    };
+
+    Ok(Reply::json(200, &verify_response(outcome)))
+}
+
+fn verify_program_proof(
+    served: &ServedProgram,
+    body: &[u8],
+    expected_output: Option<[u32; 8]>,
+) -> ApiResult<Reply> {
+    let proof = decode_proof(body)?;
+    if matches!(proof, Proof::Dev(_)) {
+        return Err(ApiError::bad_request(
+            "dev proofs bind their input words; verify them through `POST /v1/jobs/{id}/verify`",
+        ));
+    }
+    let outcome = verify_real(served, &proof, expected_output.as_ref())?;
+    Ok(Reply::json(200, &verify_response(outcome)))
+}
+
+fn verify_real(
+    served: &ServedProgram,
+    proof: &Proof,
+    expected_output: Option<&[u32; 8]>,
+) -> ApiResult<airbender_host::Result<()>> {
+    let Some(level) = served.info.level else {
+        return Err(ApiError::bad_request(format!(
+            "program `{}` is served by the dev backend and cannot verify real proofs",
+            served.info.name
+        )));
+    };
+    let verifier = served
+        .program
+        .real_verifier(level)
+        .build()
+        .map_err(|err| ApiError::internal(err.to_string()))?;
+    let vk = served_vk(served)?;
+    let request = match expected_output {
+        Some(words) => VerificationRequest::real(words),
+        None => VerificationRequest::empty(),
+    };
+    Ok(verifier.verify(proof, &vk, request))
+}
+
+/// Returns the verification key of the served backend, generating it on first use.
+fn served_vk(served: &ServedProgram) -> ApiResult<VerificationKey> {
+    let mut cached = match served.vk.lock() {
+        Ok(cached) => cached,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if let Some(vk) = cached.as_ref() {
+        return Ok(vk.clone());
+    }
+
+    let vk = match served.info.level {
+        None => served
+            .program
+            .dev_verifier()
+            .build()
+            .and_then(|verifier| verifier.generate_vk()),
+        Some(level) => served
+            .program
+            .real_verifier(level)
+            .build()
+            .and_then(|verifier| verifier.generate_vk()),
+    }
+    .map_err(|err| {
+        ApiError::internal(format!(
+            "failed to generate verification key for `{}`: {err}",
+            served.info.name
+        ))
+    })?;
+    *cached = Some(vk.clone());
+    Ok(vk)
+}
+
+fn verify_response(outcome: airbender_host::Result<()>) -> VerifyResponse {
+    match outcome {
+        Ok(()) => VerifyResponse {
+            verified: true,
+            error: None,
+        },
+        Err(err) => VerifyResponse {
+            verified: false,
+            error: Some(err.to_string()),
+        },
+    }
+}
+
+fn find_program<'a>(state: &'a ServeState, name: &str) -> ApiResult<&'a ServedProgram> {
+    state
+        .programs
+        .get(name)
+        .ok_or_else(|| ApiError::not_found(format!("unknown program `{name}`")))
+}
+
+fn ensure_succeeded(state: &ServeState, id: u64) -> ApiResult<airbender_host::service::JobStatus> {
+    let status = state.store.status(id).ok_or_else(|| job_not_found(id))?;
+    match status.state {
+        JobState::Succeeded => Ok(status),
+        JobState::Failed => Err(ApiError::conflict(format!(
+            "job {id} failed: {}",
+            status.error.as_deref().unwrap_or("unknown error")
+        ))),
+        JobState::Queued | JobState::Running => {
+            Err(ApiError::conflict(format!("job {id} is not finished yet")))
+        }
+    }
+}
+
+fn job_not_found(id: u64) -> ApiError {
+    ApiError::not_found(format!("unknown job {id}"))
+}
+
+fn parse_job_id(raw: &str) -> ApiResult<u64> {
+    raw.parse()
+        .map_err(|_| ApiError::bad_request(format!("invalid job id `{raw}`")))
+}
+
+fn parse_json<T: DeserializeOwned>(body: &[u8]) -> ApiResult<T> {
+    serde_json::from_slice(body)
+        .map_err(|err| ApiError::bad_request(format!("invalid JSON body: {err}")))
+}
+
+fn decode_proof(bytes: &[u8]) -> ApiResult<Proof> {
+    Proof::from_bytes(bytes)
+        .map_err(|err| ApiError::bad_request(format!("failed to decode proof: {err}")))
+}
+
+fn pad_output_words(words: &[u32]) -> ApiResult<[u32; 8]> {
+    if words.len() > 8 {
+        return Err(ApiError::bad_request(format!(
+            "`expected_output` accepts at most 8 words (got {})",
+            words.len()
+        )));
+    }
+    let mut padded = [0u32; 8];
+    padded[..words.len()].copy_from_slice(words);
+    Ok(padded)
+}
+
+/// Looks up a query parameter, decoding `%XX` escapes.
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| percent_decode(value))
+}
+
+fn percent_decode(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(if bytes[index] == b'+' {
+                    b' '
+                } else {
+                    bytes[index]
+                });
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::serve::store::JobStore;
+    use airbender_host::ImageId;
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn caps_request_bodies() {
+        let body = read_body(&mut &b"1234"[..], Some(4), 4).expect("body within the limit");
+        assert_eq!(body, b"1234");
+
+        let err = read_body(&mut &b"12345"[..], Some(5), 4).expect_err("declared length");
+        assert_eq!(err.status, 413);
+        // Chunked bodies carry no length, so the cap applies while reading.
+        let err = read_body(&mut &b"12345"[..], None, 4).expect_err("streamed length");
+        assert_eq!(err.status, 413);
+    }
+
+    #[test]
+    fn routes_program_listing_and_unknown_endpoints() {
+        let (state, _state_dir) = empty_state();
+
+        let reply = route(&state, &Method::Get, "/v1/programs", &[]).expect("list programs");
+        assert_eq!(reply.status, 200);
+        assert_eq!(reply.body, b"[]");
+
+        let err = route(&state, &Method::Get, "/v2/programs", &[]).expect_err("unknown prefix");
+        assert_eq!(err.status, 404);
+        let err = route(&state, &Method::Delete, "/v1/programs", &[]).expect_err("bad method");
+        assert_eq!(err.status, 404);
+        let err = route(
+            &state,
+            &Method::Post,
+            "/v1/programs/app/jobs",
+            br#"{"input_words":[]}"#,
+        )
+        .expect_err("unknown program");
+        assert_eq!(err.status, 404);
+        assert!(err.message.contains("unknown program `app`"));
+    }
+
+    #[test]
+    fn reports_job_lookup_errors() {
+        let (state, _state_dir) = empty_state();
+
+        let err = route(&state, &Method::Get, "/v1/jobs/abc", &[]).expect_err("invalid id");
+        assert_eq!(err.status, 400);
+        let err = route(&state, &Method::Get, "/v1/jobs/7", &[]).expect_err("missing job");
+        assert_eq!(err.status, 404);
+
+        let id = state
+            .store
+            .create("app", ImageId::from_bytes([1; 32]), vec![1])
+            .expect("create job");
+        let reply = route(&state, &Method::Get, &format!("/v1/jobs/{id}"), &[]).expect("status");
+        let status: airbender_host::service::JobStatus =
+            serde_json::from_slice(&reply.body).expect("decode status");
+        assert_eq!(status.state, JobState::Queued);
+
+        let err = route(&state, &Method::Get, &format!("/v1/jobs/{id}/proof"), &[])
+            .expect_err("unfinished job");
+        assert_eq!(err.status, 409);
+    }
+
+    #[test]
+    fn decodes_query_parameters() {
+        assert_eq!(
+            query_param("a=1&expected_output=42%2C0x2a", "expected_output").as_deref(),
+            Some("42,0x2a")
+        );
+        assert_eq!(query_param("a=1", "expected_output"), None);
+        assert_eq!(percent_decode("100%"), "100%");
+    }
+
+    #[test]
+    fn pads_expected_output_words() {
+        assert_eq!(pad_output_words(&[1, 2]).unwrap(), [1, 2, 0, 0, 0, 0, 0, 0]);
+        let err = pad_output_words(&[0; 9]).expect_err("too many words");
+        assert_eq!(err.status, 400);
+    }
+
+    fn empty_state() -> (ServeState, tempfile::TempDir) {
+        let state_dir = tempfile::tempdir().expect("create state directory");
+        let (store, _) = JobStore::open(state_dir.path()).expect("open store");
+        let state = ServeState {
+            store: Arc::new(store),
+            programs: BTreeMap::new(),
+        };
+        (state, state_dir)
+    }
+}
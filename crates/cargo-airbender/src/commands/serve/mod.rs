@@ -0,0 +1,263 @@
+//! `cargo airbender serve`: local proving service over HTTP/JSON.
+//!
+//! Each served program owns a dedicated prover thread that keeps its prover warm between
+//! jobs. Jobs are persisted in the state directory, so queued work survives restarts.
+
+mod http;
+mod store;
+
+use super::prove::ProverSettings;
+use crate::cli::ServeArgs;
+use crate::error::{CliError, Result};
+use crate::ui;
+use airbender_host::service::ProgramInfo;
+use airbender_host::{Program, Prover, VerificationKey};
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use store::JobStore;
+
+pub fn run(args: ServeArgs) -> Result<()> {
+    let settings = ProverSettings {
+        backend: args.backend,
+        threads: args.threads,
+        cycles: args.cycles,
+        ram_bound: args.ram_bound,
+        level: args.level,
+    };
+
+    let (store, pending) = JobStore::open(&args.state_dir).map_err(|err| {
+        CliError::with_source(
+            format!(
+                "failed to open service state in `{}`",
+                args.state_dir.display()
+            ),
+            err,
+        )
+    })?;
+    let store = Arc::new(store);
+
+    let mut programs = BTreeMap::new();
+    for dist_dir in &args.dist {
+        let name = program_name(dist_dir)?;
+        if programs.contains_key(&name) {
+            return Err(CliError::new(format!(
+                "program name `{name}` is used by more than one dist directory"
+            ))
+            .with_hint("serve dist directories with distinct final path components"));
+        }
+
+        let program = Program::load(dist_dir).map_err(|err| {
+            CliError::with_source(
+                format!("failed to load program from `{}`", dist_dir.display()),
+                err,
+            )
+        })?;
+        let app_bin = program.app_bin().ok_or_else(|| {
+            CliError::new(format!("`{}` is not a dist directory", dist_dir.display()))
+                .with_hint("serve unpacked dist directories rather than bundles")
+        })?;
+        let queue = spawn_prover_thread(&name, app_bin, settings, Arc::clone(&store))?;
+        let info = ProgramInfo {
+            name: name.clone(),
+            package: program.manifest().package.clone(),
+            image_id: program.image_id(),
+            backend: settings.backend_name().to_string(),
+            level: settings.proof_level(),
+        };
+        programs.insert(
+            name,
+            ServedProgram {
+                program,
+                info,
+                queue,
+                vk: Mutex::new(None),
+            },
+        );
+    }
+
+    for (job_id, program) in &pending {
+        let queued_image_id = store.status(*job_id).map(|status| status.image_id);
+        let reason = match programs.get(program) {
+            Some(served) if queued_image_id == Some(served.info.image_id) => {
+                let _ = served.queue.send(*job_id);
+                continue;
+            }
+            Some(_) => format!("program `{program}` was rebuilt since the job was queued"),
+            None => format!("program `{program}` is not served anymore"),
+        };
+        if let Err(err) = store.mark_failed(*job_id, reason) {
+            tracing::error!("failed to update job {job_id}: {err}");
+        }
+    }
+
+    let server = tiny_http::Server::http(&args.listen).map_err(|err| {
+        CliError::new(format!("failed to listen on `{}`: {err}", args.listen))
+            .with_hint("pick a free address with `--listen <host:port>`")
+    })?;
+
+    ui::success("proving service started");
+    ui::field("listen", format!("http://{}", args.listen));
+    ui::field("backend", settings.backend_name());
+    ui::field("state", args.state_dir.display());
+    for name in programs.keys() {
+        ui::field("program", name);
+    }
+    if !pending.is_empty() {
+        ui::info(format!("resumed {} pending job(s)", pending.len()));
+    }
+
+    let state = Arc::new(ServeState { store, programs });
+    let server = Arc::new(server);
+    let mut workers = Vec::with_capacity(HTTP_WORKERS);
+    for index in 0..HTTP_WORKERS {
+        let state = Arc::clone(&state);
+        let server = Arc::clone(&server);
+        let worker = std::thread::Builder::new()
+            .name(format!("airbender-serve-http-{index}"))
+            .spawn(move || {
+                while let Ok(request) = server.recv() {
+                    http::handle(&state, request);
+                }
+            })
+            .map_err(|err| CliError::with_source("failed to spawn HTTP worker thread", err))?;
+        workers.push(worker);
+    }
+    for worker in workers {
+        let _ = worker.join();
+    }
+
+    Ok(())
+}
+
+/// Number of threads serving HTTP requests; further requests wait for a free thread.
+const HTTP_WORKERS: usize = 8;
+
+/// Shared state of a running service.
+struct ServeState {
+    store: Arc<JobStore>,
+    programs: BTreeMap<String, ServedProgram>,
+}
+
+struct ServedProgram {
+    program: Program,
+    info: ProgramInfo,
+    queue: mpsc::Sender<u64>,
+    /// Verification key, generated on first use.
+    vk: Mutex<Option<VerificationKey>>,
+}
+
+fn program_name(dist_dir: &Path) -> Result<String> {
+    dist_dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            CliError::new(format!(
+                "cannot derive a program name from `{}`",
+                dist_dir.display()
+            ))
+            .with_hint("pass dist app directories such as `dist/app`")
+        })
+}
+
+/// Starts the prover thread of a program and returns its job queue.
+///
+/// The prover is built on the thread itself, and initialization errors are reported
+/// before the service starts accepting requests.
+fn spawn_prover_thread(
+    name: &str,
+    app_bin: PathBuf,
+    settings: ProverSettings,
+    store: Arc<JobStore>,
+) -> Result<mpsc::Sender<u64>> {
+    let (queue_tx, queue_rx) = mpsc::channel::<u64>();
+    let (init_tx, init_rx) = mpsc::channel();
+
+    std::thread::Builder::new()
+        .name(format!("airbender-serve-{name}"))
+        .spawn(move || {
+            let mut prover = match settings.build(&app_bin) {
+                Ok(prover) => Some(prover),
+                Err(err) => {
+                    let _ = init_tx.send(Err(err));
+                    return;
+                }
+            };
+            let _ = init_tx.send(Ok(()));
+
+            while let Ok(job_id) = queue_rx.recv() {
+                if prover.is_none() {
+                    prover = settings.build(&app_bin).ok();
+                }
+                let outcome = match &prover {
+                    Some(prover) => prove_job(prover.as_ref(), &store, job_id),
+                    None => JobOutcome::Failed("prover is unavailable".to_string()),
+                };
+                if matches!(outcome, JobOutcome::Panicked) {
+                    // The prover state is unknown after a panic; rebuild it for the next job.
+                    prover = None;
+                }
+            }
+        })
+        .map_err(|err| CliError::with_source("failed to spawn prover thread", err))?;
+
+    match init_rx.recv() {
+        Ok(Ok(())) => Ok(queue_tx),
+        Ok(Err(err)) => Err(err),
+        Err(_) => Err(CliError::new(format!(
+            "prover thread for `{name}` exited during initialization"
+        ))),
+    }
+}
+
+enum JobOutcome {
+    Succeeded,
+    Failed(String),
+    Panicked,
+}
+
+fn prove_job(prover: &dyn Prover, store: &JobStore, job_id: u64) -> JobOutcome {
+    let Some(input_words) = store.input_words(job_id) else {
+        return JobOutcome::Failed(format!("unknown job {job_id}"));
+    };
+    if let Err(err) = store.mark_running(job_id) {
+        tracing::error!("failed to update job {job_id}: {err}");
+    }
+
+    let (outcome, update) =
+        match panic::catch_unwind(AssertUnwindSafe(|| prover.prove(&input_words))) {
+            Ok(Ok(result)) => {
+                let update = store.mark_succeeded(job_id, &result);
+                (JobOutcome::Succeeded, update)
+            }
+            Ok(Err(err)) => {
+                let reason = err.to_string();
+                let update = store.mark_failed(job_id, reason.clone());
+                (JobOutcome::Failed(reason), update)
+            }
+            Err(payload) => {
+                let reason = format!("prover panicked: {}", panic_message(payload.as_ref()));
+                let update = store.mark_failed(job_id, reason);
+                (JobOutcome::Panicked, update)
+            }
+        };
+    if let Err(err) = update {
+        tracing::error!("failed to update job {job_id}: {err}");
+    }
+    if let JobOutcome::Failed(reason) = &outcome {
+        tracing::warn!("job {job_id} failed: {reason}");
+    }
+
+    outcome
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .or_else(|| payload.downcast_ref::<&str>().copied())
+        .unwrap_or("unknown panic payload")
+}
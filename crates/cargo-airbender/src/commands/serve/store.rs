@@ -0,0 +1,228 @@
+//! Persistent job state for the proving service.
+//!
+//! Layout under the state directory:
+//! - `jobs/<id>/job.json`: job status and input words,
+//! - `jobs/<id>/proof.bin`: bincode-encoded proof, written before the job is marked as succeeded.
+
+use airbender_host::service::{JobState, JobStatus};
+use airbender_host::{ImageId, ProveResult};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+const JOB_FILE: &str = "job.json";
+const PROOF_FILE: &str = "proof.bin";
+
+/// Persisted job entry.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+struct JobRecord {
+    status: JobStatus,
+    input_words: Vec<u32>,
+}
+
+pub(super) struct JobStore {
+    jobs_dir: PathBuf,
+    inner: Mutex<StoreInner>,
+}
+
+struct StoreInner {
+    next_id: u64,
+    jobs: BTreeMap<u64, JobRecord>,
+}
+
+impl JobStore {
+    /// Opens the store, loading every persisted job.
+    ///
+    /// Jobs that were queued or running when the previous process stopped are reset to
+    /// `queued`; their ids are returned so the caller can schedule them again.
+    pub(super) fn open(state_dir: &Path) -> io::Result<(Self, Vec<(u64, String)>)> {
+        let jobs_dir = state_dir.join("jobs");
+        fs::create_dir_all(&jobs_dir)?;
+
+        let mut jobs = BTreeMap::new();
+        for entry in fs::read_dir(&jobs_dir)? {
+            let entry = entry?;
+            let job_file = entry.path().join(JOB_FILE);
+            if !job_file.is_file() {
+                continue;
+            }
+            let record: JobRecord = serde_json::from_slice(&fs::read(&job_file)?)
+                .map_err(|err| invalid_data(&job_file, err))?;
+            jobs.insert(record.status.id, record);
+        }
+
+        let mut pending = Vec::new();
+        for record in jobs.values_mut() {
+            if !record.status.state.is_finished() {
+                record.status.state = JobState::Queued;
+                pending.push((record.status.id, record.status.program.clone()));
+            }
+        }
+
+        let next_id = jobs.keys().next_back().map_or(1, |id| id + 1);
+        let store = Self {
+            jobs_dir,
+            inner: Mutex::new(StoreInner { next_id, jobs }),
+        };
+        for (id, _) in &pending {
+            let inner = store.lock();
+            store.persist(&inner.jobs[id])?;
+        }
+
+        Ok((store, pending))
+    }
+
+    /// Records a new queued job for the given program build and returns its id.
+    pub(super) fn create(
+        &self,
+        program: &str,
+        image_id: ImageId,
+        input_words: Vec<u32>,
+    ) -> io::Result<u64> {
+        let mut inner = self.lock();
+        let id = inner.next_id;
+        let record = JobRecord {
+            status: JobStatus {
+                id,
+                program: program.to_string(),
+                image_id,
+                state: JobState::Queued,
+                cycles: None,
+                receipt: None,
+                error: None,
+            },
+            input_words,
+        };
+        fs::create_dir_all(self.job_dir(id))?;
+        self.persist(&record)?;
+        inner.next_id += 1;
+        inner.jobs.insert(id, record);
+        Ok(id)
+    }
+
+    pub(super) fn status(&self, id: u64) -> Option<JobStatus> {
+        self.lock()
+            .jobs
+            .get(&id)
+            .map(|record| record.status.clone())
+    }
+
+    pub(super) fn input_words(&self, id: u64) -> Option<Vec<u32>> {
+        self.lock()
+            .jobs
+            .get(&id)
+            .map(|record| record.input_words.clone())
+    }
+
+    /// Reads the encoded proof of a succeeded job.
+    pub(super) fn proof_bytes(&self, id: u64) -> io::Result<Vec<u8>> {
+        fs::read(self.job_dir(id).join(PROOF_FILE))
+    }
+
+    pub(super) fn mark_running(&self, id: u64) -> io::Result<()> {
+        self.update(id, |status| status.state = JobState::Running)
+    }
+
+    /// Stores the proof and marks the job as succeeded.
+    pub(super) fn mark_succeeded(&self, id: u64, result: &ProveResult) -> io::Result<()> {
+        let encoded = result.proof.to_bytes().map_err(io::Error::other)?;
+        write_atomically(&self.job_dir(id).join(PROOF_FILE), &encoded)?;
+        self.update(id, |status| {
+            status.state = JobState::Succeeded;
+            status.cycles = Some(result.cycles);
+            status.receipt = Some(result.receipt.clone());
+        })
+    }
+
+    pub(super) fn mark_failed(&self, id: u64, error: String) -> io::Result<()> {
+        self.update(id, |status| {
+            status.state = JobState::Failed;
+            status.error = Some(error);
+        })
+    }
+
+    fn update(&self, id: u64, apply: impl FnOnce(&mut JobStatus)) -> io::Result<()> {
+        let mut inner = self.lock();
+        let record = inner
+            .jobs
+            .get_mut(&id)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("unknown job {id}")))?;
+        apply(&mut record.status);
+        self.persist(record)
+    }
+
+    fn persist(&self, record: &JobRecord) -> io::Result<()> {
+        let payload = serde_json::to_vec_pretty(record).map_err(io::Error::other)?;
+        write_atomically(&self.job_dir(record.status.id).join(JOB_FILE), &payload)
+    }
+
+    fn job_dir(&self, id: u64) -> PathBuf {
+        self.jobs_dir.join(id.to_string())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, StoreInner> {
+        match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+/// Writes through a temporary file so a crash never leaves a truncated file behind.
+fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    fs::write(&temp_path, contents)?;
+    fs::rename(&temp_path, path)
+}
+
+fn invalid_data(path: &Path, err: serde_json::Error) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("failed to parse `{}`: {err}", path.display()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reopening_requeues_unfinished_jobs_and_keeps_finished_ones() {
+        let temp_dir = tempfile::tempdir().expect("create temp dir");
+        let state_dir = temp_dir.path();
+        let (store, pending) = JobStore::open(state_dir).expect("open store");
+        assert!(pending.is_empty());
+
+        let image_id = ImageId::from_bytes([1; 32]);
+        let queued = store
+            .create("app", image_id, vec![1, 2])
+            .expect("create job");
+        let running = store.create("app", image_id, vec![3]).expect("create job");
+        let failed = store.create("other", image_id, vec![]).expect("create job");
+        store.mark_running(running).expect("mark running");
+        store
+            .mark_failed(failed, "boom".to_string())
+            .expect("mark failed");
+        drop(store);
+
+        let (store, pending) = JobStore::open(state_dir).expect("reopen store");
+        assert_eq!(
+            pending,
+            vec![(queued, "app".to_string()), (running, "app".to_string())]
+        );
+        let status = store.status(running).expect("running job status");
+        assert_eq!(status.state, JobState::Queued);
+        let status = store.status(failed).expect("failed job status");
+        assert_eq!(status.state, JobState::Failed);
+        assert_eq!(status.error.as_deref(), Some("boom"));
+        assert_eq!(store.input_words(queued), Some(vec![1, 2]));
+        assert_eq!(status.image_id, image_id);
+
+        let next = store.create("app", image_id, vec![]).expect("create job");
+        assert_eq!(next, failed + 1);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::cli::VerifyBuildArgs;
+use crate::error::{CliError, Result};
+use crate::ui;
+use airbender_build::{verify_build, ArtifactComparison, BuildVerification};
+
+pub fn run(args: VerifyBuildArgs) -> Result<()> {
+    let project_dir = match args.project {
+        Some(path) => path,
+        None => std::env::current_dir().map_err(|err| {
+            CliError::with_source("failed to resolve current working directory", err)
+        })?,
+    };
+    if !project_dir.join("Cargo.toml").is_file() {
+        return Err(CliError::new(format!(
+            "guest project `{}` does not contain a Cargo.toml",
+            project_dir.display()
+        ))
+        .with_hint("use --project <path-to-guest-crate>"));
+    }
+
+    let verification = verify_build(&args.dist, &project_dir).map_err(|err| {
+        CliError::with_source(
+            format!(
+                "failed to rebuild `{}` for verification",
+                args.dist.display()
+            ),
+            err,
+        )
+        .with_hint("set `RUST_LOG=info` if you need backend diagnostic logs")
+    })?;
+
+    print_comparison("app.bin", &verification.bin);
+    print_comparison("app.text", &verification.text);
+    if let Some(expected) = verification.expected_image_id {
+        ui::field("expected_image_id", expected);
+    }
+    ui::field("image_id", verification.actual_image_id);
+
+    if verification.is_reproducible() {
+        ui::blank_line();
+        ui::success(format!(
+            "`{}` reproduces {}",
+            verification.package,
+            args.dist.display()
+        ));
+        return Ok(());
+    }
+
+    print_differences(&verification);
+    Err(CliError::new(format!(
+        "rebuilt `{}` does not match {}",
+        verification.package,
+        args.dist.display()
+    ))
+    .with_hint("check out the recorded commit and toolchain, then verify again"))
+}
+
+fn print_comparison(name: &str, comparison: &ArtifactComparison) {
+    let status = if comparison.matches() {
+        "match"
+    } else {
+        "MISMATCH"
+    };
+    ui::field(
+        name,
+        format!(
+            "{status} (expected {}, rebuilt {})",
+            comparison.expected_sha256, comparison.actual_sha256
+        ),
+    );
+}
+
+fn print_differences(verification: &BuildVerification) {
+    if !verification.section_diffs.is_empty() {
+        ui::blank_line();
+        ui::info("differing sections");
+        for diff in &verification.section_diffs {
+            let size =
+                |size: Option<u64>| size.map_or("absent".to_string(), |s| format!("{s} bytes"));
+            let mut summary = format!(
+                "expected {}, rebuilt {}",
+                size(diff.expected_size),
+                size(diff.actual_size)
+            );
+            if let Some(offset) = diff.first_difference {
+                summary.push_str(&format!(
+                    "; {} differing bytes, first at offset {offset:#x}",
+                    diff.differing_bytes
+                ));
+            }
+            ui::field(&diff.name, summary);
+        }
+    }
+
+    if !verification.provenance_diffs.is_empty() {
+        ui::blank_line();
+        ui::info("provenance differences");
+        for diff in &verification.provenance_diffs {
+            ui::field(
+                diff.field,
+                format!("expected {}, rebuilt {}", diff.expected, diff.actual),
+            );
+        }
+    }
+}
@@ -1,12 +1,9 @@
 use crate::cli::{GenerateVkArgs, ProverLevelArg, VerifyProofArgs};
 use crate::error::{CliError, Result};
 use crate::ui;
-use serde::{de::DeserializeOwned, Serialize};
 use std::path::Path;
 
 pub fn generate(args: GenerateVkArgs) -> Result<()> {
-    ensure_gpu_vk_support()?;
-
     let vk = match args.level {
         ProverLevelArg::RecursionUnified => {
             let vk = airbender_host::compute_unified_vk(&args.app_bin).map_err(|err| {
@@ -39,7 +36,10 @@ pub fn generate(args: GenerateVkArgs) -> Result<()> {
         }
     };
 
-    write_bincode(&args.output, &vk)?;
+    let encoded = vk
+        .to_bytes()
+        .map_err(|err| CliError::with_source("failed to encode verification keys", err))?;
+    write_file(&args.output, &encoded)?;
 
     ui::success("verification keys generated");
     ui::field("level", level_name(args.level));
@@ -48,44 +48,27 @@ pub fn generate(args: GenerateVkArgs) -> Result<()> {
     Ok(())
 }
 
-fn ensure_gpu_vk_support() -> Result<()> {
-    #[cfg(feature = "gpu-prover")]
-    {
-        Ok(())
-    }
-
-    #[cfg(not(feature = "gpu-prover"))]
-    {
-        Err(CliError::new(
-            "verification key generation requires GPU support in `cargo-airbender`",
-        )
-        .with_hint(
-            "rebuild `cargo-airbender` with default features or pass `--features gpu-prover` to use `generate-vk`",
-        ))
-    }
-}
-
 pub fn verify(args: VerifyProofArgs) -> Result<()> {
     let expected_output_words = parse_expected_output_words(args.expected_output.as_deref())?;
 
-    let proof: airbender_host::Proof = read_bincode(&args.proof).map_err(|err| {
+    let vk = airbender_host::VerificationKey::from_bytes(&read_file(&args.vk)?).map_err(|err| {
         CliError::with_source(
-            format!("failed to decode proof from `{}`", args.proof.display()),
+            format!(
+                "failed to decode verification key file `{}`",
+                args.vk.display()
+            ),
             err,
         )
     })?;
 
-    let vk: airbender_host::VerificationKey = read_bincode(&args.vk).map_err(|err| {
+    let proof = airbender_host::decode_proof(&read_file(&args.proof)?, &vk).map_err(|err| {
         CliError::with_source(
-            format!(
-                "failed to decode verification key file `{}`",
-                args.vk.display()
-            ),
+            format!("failed to decode proof from `{}`", args.proof.display()),
             err,
         )
     })?;
 
-    let level = match &proof {
+    let (level, image_id) = match &proof {
         airbender_host::Proof::Dev(_) => {
             return Err(CliError::new(
                 "detected a dev proof; `cargo airbender verify-proof` supports only real proofs",
@@ -101,7 +84,7 @@ pub fn verify(args: VerifyProofArgs) -> Result<()> {
 
             airbender_host::verify_real_proof_with_vk(proof, &vk, expected_output_commit)
                 .map_err(|err| CliError::with_source("proof verification failed", err))?;
-            proof.level()
+            (proof.level(), proof.image_id())
         }
     };
 
@@ -111,6 +94,7 @@ pub fn verify(args: VerifyProofArgs) -> Result<()> {
 
     ui::success("proof verified");
     ui::field("level", host_level_name(level));
+    ui::field("image_id", image_id);
     if let Some(words) = expected_output_words {
         ui::field("expected_output", format_output_words(&words));
     }
@@ -118,7 +102,7 @@ pub fn verify(args: VerifyProofArgs) -> Result<()> {
     Ok(())
 }
 
-fn parse_expected_output_words(raw: Option<&str>) -> Result<Option<[u32; 8]>> {
+pub(crate) fn parse_expected_output_words(raw: Option<&str>) -> Result<Option<[u32; 8]>> {
     let Some(raw) = raw else {
         return Ok(None);
     };
@@ -221,56 +205,41 @@ fn host_level_name(level: airbender_host::ProverLevel) -> &'static str {
     }
 }
 
-fn read_bincode<T: DeserializeOwned>(path: &Path) -> Result<T> {
-    let bytes = std::fs::read(path).map_err(|err| {
-        CliError::with_source(format!("failed to read `{}`", path.display()), err)
-    })?;
-    let (decoded, read_len): (T, usize) =
-        bincode::serde::decode_from_slice(&bytes, bincode::config::standard())
-            .map_err(|err| CliError::with_source("failed to decode bincode payload", err))?;
-
-    if read_len != bytes.len() {
-        tracing::warn!(
-            "bincode decoded {} bytes but file is {} bytes",
-            read_len,
-            bytes.len()
-        );
-    }
-    Ok(decoded)
+fn read_file(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path)
+        .map_err(|err| CliError::with_source(format!("failed to read `{}`", path.display()), err))
 }
 
-fn write_bincode<T: Serialize>(path: &Path, value: &T) -> Result<()> {
-    let encoded = bincode::serde::encode_to_vec(value, bincode::config::standard())
-        .map_err(|err| CliError::with_source("failed to encode bincode payload", err))?;
-    std::fs::write(path, encoded).map_err(|err| {
-        CliError::with_source(format!("failed to write `{}`", path.display()), err)
-    })?;
-
-    Ok(())
+fn write_file(path: &Path, contents: &[u8]) -> Result<()> {
+    std::fs::write(path, contents)
+        .map_err(|err| CliError::with_source(format!("failed to write `{}`", path.display()), err))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    #[cfg(not(feature = "gpu-prover"))]
     use crate::cli::GenerateVkArgs;
-    #[cfg(not(feature = "gpu-prover"))]
     use std::path::PathBuf;
 
-    #[cfg(not(feature = "gpu-prover"))]
     #[test]
-    fn generate_vk_requires_gpu_support() {
-        let err = generate(GenerateVkArgs {
-            app_bin: PathBuf::from("app.bin"),
-            output: PathBuf::from("vk.bin"),
-            level: ProverLevelArg::Base,
-        })
-        .expect_err("generate-vk must require gpu-prover support");
+    fn generate_vk_does_not_require_gpu_support() {
+        for level in [
+            ProverLevelArg::Base,
+            ProverLevelArg::RecursionUnrolled,
+            ProverLevelArg::RecursionUnified,
+        ] {
+            let err = generate(GenerateVkArgs {
+                app_bin: PathBuf::from("missing-app.bin"),
+                output: PathBuf::from("vk.bin"),
+                level,
+            })
+            .expect_err("generate-vk must fail for a missing binary");
 
-        assert!(
-            err.to_string().contains("requires GPU support"),
-            "unexpected error: {err}"
-        );
+            assert!(
+                err.to_string().contains("failed to compute"),
+                "unexpected error: {err}"
+            );
+        }
     }
 
     #[test]
@@ -0,0 +1,25 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use airbender::guest::{read, verify_unified_proof};
+use airbender_core::aggregation::{claims_commitment, AggregatedClaim};
+use alloc::vec::Vec;
+
+/// Verifies the recursion-unified proofs sent by the host and commits to the claims.
+///
+/// Inputs are `true` followed by a proof, repeated, and a final `false`; see
+/// `airbender_host::Aggregator`.
+#[airbender::main]
+fn main() -> [u32; 8] {
+    let mut claims = Vec::new();
+    while read::<bool>().expect("failed to read continuation flag") {
+        let verified = verify_unified_proof().expect("failed to verify proof");
+        claims.push(AggregatedClaim {
+            recursion_chain: verified.recursion_chain,
+            output: verified.output,
+        });
+    }
+    claims_commitment(&claims)
+}